@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
+    config::ConnectionLimits,
     mode::{InputMode, Scene},
     node_stats::NodeStats,
 };
@@ -22,6 +23,7 @@ pub enum Action {
 
     StoreDiscordUserName(String),
     StoreNodesToStart(usize),
+    StoreConnectionLimits(ConnectionLimits),
 
     Tick,
     Render,
@@ -49,6 +51,7 @@ pub enum HomeActions {
 
     TriggerBetaProgramme,
     TriggerManageNodes,
+    TriggerOptions,
     TriggerHelp,
     TriggerResetNodesPopUp,
 
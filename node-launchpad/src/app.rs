@@ -12,7 +12,7 @@ use crate::{
     action::Action,
     components::{
         beta_programme::BetaProgramme, footer::Footer, help::HelpPopUp, home::Home,
-        manage_nodes::ManageNodes, reset_popup::ResetNodesPopup, Component,
+        manage_nodes::ManageNodes, options::Options, reset_popup::ResetNodesPopup, Component,
     },
     config::{AppData, Config},
     mode::{InputMode, Scene},
@@ -50,6 +50,7 @@ impl App {
         let home = Home::new(
             app_data.nodes_to_start,
             &app_data.discord_username,
+            app_data.connection_limits,
             peers_args,
             safenode_path,
         )
@@ -57,6 +58,7 @@ impl App {
         let config = Config::new()?;
         let discord_username_input = BetaProgramme::new(app_data.discord_username.clone());
         let manage_nodes = ManageNodes::new(app_data.nodes_to_start)?;
+        let options = Options::new(app_data.connection_limits);
         let footer = Footer::new(app_data.nodes_to_start > 0);
         let help = HelpPopUp::default();
         let reset_nodes = ResetNodesPopup::default();
@@ -71,6 +73,7 @@ impl App {
                 Box::new(home),
                 Box::new(discord_username_input),
                 Box::new(manage_nodes),
+                Box::new(options),
                 Box::new(help),
                 Box::new(reset_nodes),
             ],
@@ -193,6 +196,11 @@ impl App {
                         self.app_data.nodes_to_start = count;
                         self.app_data.save()?;
                     }
+                    Action::StoreConnectionLimits(limits) => {
+                        debug!("Storing connection limits: {limits:?}");
+                        self.app_data.connection_limits = limits;
+                        self.app_data.save()?;
+                    }
                     _ => {}
                 }
                 for component in self.components.iter_mut() {
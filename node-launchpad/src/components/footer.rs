@@ -93,6 +93,7 @@ impl Component for Footer {
             | Scene::BetaProgramme
             | Scene::HelpPopUp
             | Scene::ManageNodes
+            | Scene::Options
             | Scene::ResetPopUp => {
                 let line1 = Line::from(vec![
                     Span::styled(" [Ctrl+S] ", command_style),
@@ -117,7 +118,6 @@ impl Component for Footer {
 
                 (line1, line2)
             }
-            Scene::Options => (Line::from("none"), Line::from("none")),
         };
 
         f.render_widget(Paragraph::new(line1), layer_one[1]);
@@ -9,7 +9,7 @@
 use super::{manage_nodes::GB_PER_NODE, utils::centered_rect_fixed, Component, Frame};
 use crate::{
     action::{Action, HomeActions},
-    config::Config,
+    config::{Config, ConnectionLimits},
     mode::{InputMode, Scene},
     node_stats::NodeStats,
     style::{
@@ -51,6 +51,7 @@ pub struct Home {
     node_table_state: TableState,
     nodes_to_start: usize,
     discord_username: String,
+    connection_limits: ConnectionLimits,
     // Currently the node registry file does not support concurrent actions and thus can lead to
     // inconsistent state. Another solution would be to have a file lock/db.
     lock_registry: Option<LockRegistryState>,
@@ -70,6 +71,7 @@ impl Home {
     pub async fn new(
         allocated_disk_space: usize,
         discord_username: &str,
+        connection_limits: ConnectionLimits,
         peers_args: PeersArgs,
         safenode_path: Option<PathBuf>,
     ) -> Result<Self> {
@@ -89,6 +91,7 @@ impl Home {
             node_table_state: Default::default(),
             lock_registry: None,
             discord_username: discord_username.to_string(),
+            connection_limits,
             safenode_path,
         };
 
@@ -235,6 +238,7 @@ impl Component for Home {
                 }
                 Scene::BetaProgramme
                 | Scene::ManageNodes
+                | Scene::Options
                 | Scene::HelpPopUp
                 | Scene::ResetPopUp => self.active = true,
                 _ => self.active = false,
@@ -249,6 +253,22 @@ impl Component for Home {
                     return Ok(Some(Action::HomeActions(HomeActions::StartNodes)));
                 }
             }
+            Action::StoreConnectionLimits(limits) => {
+                let has_changed = self.connection_limits != limits;
+                let we_have_nodes = !self.node_services.is_empty();
+
+                self.connection_limits = limits;
+
+                // the limits are part of the service definitions, so the nodes have to be re-added
+                if we_have_nodes && has_changed {
+                    self.lock_registry = Some(LockRegistryState::ResettingNodes);
+                    info!(
+                        "Resetting safenode services because the connection limits were changed."
+                    );
+                    let action_sender = self.get_actions_sender()?;
+                    reset_nodes(action_sender, true);
+                }
+            }
             Action::StoreDiscordUserName(username) => {
                 let has_changed = self.discord_username != username;
                 let we_have_nodes = !self.node_services.is_empty();
@@ -280,6 +300,7 @@ impl Component for Home {
                 maintain_n_running_nodes(
                     self.nodes_to_start as u16,
                     self.discord_username.clone(),
+                    self.connection_limits,
                     self.peers_args.clone(),
                     self.should_we_run_nat_detection(),
                     self.safenode_path.clone(),
@@ -350,6 +371,9 @@ impl Component for Home {
             Action::HomeActions(HomeActions::TriggerManageNodes) => {
                 return Ok(Some(Action::SwitchScene(Scene::ManageNodes)));
             }
+            Action::HomeActions(HomeActions::TriggerOptions) => {
+                return Ok(Some(Action::SwitchScene(Scene::Options)));
+            }
             Action::HomeActions(HomeActions::TriggerHelp) => {
                 return Ok(Some(Action::SwitchScene(Scene::HelpPopUp)));
             }
@@ -625,6 +649,7 @@ async fn run_nat_detection_process() -> Result<()> {
 fn maintain_n_running_nodes(
     count: u16,
     owner: String,
+    connection_limits: ConnectionLimits,
     peers_args: PeersArgs,
     run_nat_detection: bool,
     safenode_path: Option<PathBuf>,
@@ -656,6 +681,10 @@ fn maintain_n_running_nodes(
             false,
             None,
             None,
+            connection_limits.max_connections,
+            connection_limits.max_download_bytes_per_sec,
+            None,
+            connection_limits.max_upload_bytes_per_sec,
             None,
             None,
             owner,
@@ -663,6 +692,7 @@ fn maintain_n_running_nodes(
             None,
             None,
             safenode_path,
+            vec![],
             None,
             false,
             None,
@@ -6,113 +6,259 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::Component;
-use crate::{
-    action::Action,
-    mode::{InputMode, Scene},
-};
 use color_eyre::Result;
 use crossterm::event::{Event, KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
 use tui_input::{backend::crossterm::EventHandler, Input};
 
-#[derive(Default)]
+use crate::{
+    action::Action,
+    config::ConnectionLimits,
+    mode::{InputMode, Scene},
+    style::{clear_area, EUCALYPTUS, GHOST_WHITE, LIGHT_PERIWINKLE, VIVID_SKY_BLUE},
+};
+
+use super::{utils::centered_rect_fixed, Component};
+
+/// The bandwidth caps are entered in KB/s.
+const KB: u64 = 1000;
+const MAX_INPUT_DIGITS: usize = 9;
+
+const FIELD_TITLES: [&str; 3] = [
+    "Max connections",
+    "Max upload (KB/s)",
+    "Max download (KB/s)",
+];
+
+/// Lets the user cap the connections and the bandwidth of each node.
+/// An empty field leaves the corresponding limit unset.
 pub struct Options {
-    // state
-    show_scene: bool,
-    input_mode: InputMode,
-    input: Input,
+    /// Whether the component is active right now, capturing keystrokes + drawing things.
+    active: bool,
+    /// The max connections, max upload and max download inputs, in that order.
+    inputs: [Input; 3],
+    selected_input: usize,
+    // cache the old values incase user presses Esc.
+    old_values: [String; 3],
+}
+
+impl Options {
+    pub fn new(limits: ConnectionLimits) -> Self {
+        let to_value = |limit: Option<u64>| limit.map(|v| v.to_string()).unwrap_or_default();
+        Self {
+            active: false,
+            inputs: [
+                Input::default().with_value(to_value(limits.max_connections.map(u64::from))),
+                Input::default().with_value(to_value(
+                    limits.max_upload_bytes_per_sec.map(|bytes| bytes / KB),
+                )),
+                Input::default().with_value(to_value(
+                    limits.max_download_bytes_per_sec.map(|bytes| bytes / KB),
+                )),
+            ],
+            selected_input: 0,
+            old_values: Default::default(),
+        }
+    }
+
+    fn get_input_val(&self, index: usize) -> Option<u64> {
+        self.inputs[index]
+            .value()
+            .parse()
+            .ok()
+            .filter(|value| *value > 0)
+    }
+
+    fn get_limits(&self) -> ConnectionLimits {
+        ConnectionLimits {
+            max_connections: self
+                .get_input_val(0)
+                .map(|value| value.min(u32::MAX as u64) as u32),
+            max_upload_bytes_per_sec: self.get_input_val(1).map(|kb| kb * KB),
+            max_download_bytes_per_sec: self.get_input_val(2).map(|kb| kb * KB),
+        }
+    }
 }
 
 impl Component for Options {
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Vec<Action>> {
-        // while in entry mode, keybinds are not captured, so gotta exit entry mode from here
-        match key.code {
+        if !self.active {
+            return Ok(vec![]);
+        }
+
+        // while in entry mode, key bindings are not captured, so gotta exit entry mode from here
+        let send_back = match key.code {
+            KeyCode::Enter => {
+                let limits = self.get_limits();
+                debug!("Got Enter, storing the connection limits {limits:?} and switching scene");
+                vec![
+                    Action::StoreConnectionLimits(limits),
+                    Action::SwitchScene(Scene::Home),
+                ]
+            }
             KeyCode::Esc => {
-                return Ok(vec![Action::SwitchInputMode(InputMode::Navigation)]);
+                debug!(
+                    "Got Esc, restoring the old values {:?} and switching to home",
+                    self.old_values
+                );
+                for (input, old_value) in self.inputs.iter_mut().zip(self.old_values.iter()) {
+                    *input = input.clone().with_value(old_value.clone());
+                }
+                vec![Action::SwitchScene(Scene::Home)]
             }
-            KeyCode::Down => {
-                // self.select_next_input_field();
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected_input = (self.selected_input + 1) % self.inputs.len();
+                vec![]
             }
-            KeyCode::Up => {
-                // self.select_previous_input_field();
+            KeyCode::Up | KeyCode::BackTab => {
+                self.selected_input =
+                    (self.selected_input + self.inputs.len() - 1) % self.inputs.len();
+                vec![]
             }
-            _ => {}
-        }
-        self.input.handle_event(&Event::Key(key));
-        Ok(vec![])
+            KeyCode::Char(c) if c.is_numeric() => {
+                let input = &mut self.inputs[self.selected_input];
+                // don't allow leading zeros
+                if (c == '0' && input.value().is_empty()) || input.value().len() >= MAX_INPUT_DIGITS
+                {
+                    return Ok(vec![]);
+                }
+                input.handle_event(&Event::Key(key));
+                vec![]
+            }
+            KeyCode::Backspace => {
+                self.inputs[self.selected_input].handle_event(&Event::Key(key));
+                vec![]
+            }
+            _ => {
+                vec![]
+            }
+        };
+        Ok(send_back)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        match action {
+        let send_back = match action {
             Action::SwitchScene(scene) => match scene {
-                Scene::Options => self.show_scene = true,
-                _ => self.show_scene = false,
+                Scene::Options => {
+                    self.active = true;
+                    self.selected_input = 0;
+                    self.old_values = self.inputs.clone().map(|input| input.value().to_string());
+                    // set to entry input mode as we want to handle everything within our handle_key_events
+                    // so by default if this scene is active, we capture inputs.
+                    Some(Action::SwitchInputMode(InputMode::Entry))
+                }
+                _ => {
+                    self.active = false;
+                    None
+                }
             },
-            Action::SwitchInputMode(mode) => self.input_mode = mode,
-            _ => {}
+            _ => None,
         };
-        Ok(None)
+        Ok(send_back)
     }
 
     fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> Result<()> {
-        if !self.show_scene {
+        if !self.active {
             return Ok(());
         }
 
-        // index 0 is reserved for tab; 2 is for keybindings
-        let layer_zero = Layout::new(
-            Direction::Vertical,
-            [Constraint::Max(1), Constraint::Min(15), Constraint::Max(3)],
-        )
-        .split(area);
-
-        // break the index 1 into sub sections
+        let layer_zero = centered_rect_fixed(52, 15, area);
         let layer_one = Layout::new(
             Direction::Vertical,
             [
+                // for the pop_up_border
+                Constraint::Length(2),
+                // for the input fields
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                // gap before help
+                Constraint::Length(1),
+                // for the help
                 Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
+                // for the dash
+                Constraint::Min(1),
+                // for the buttons
+                Constraint::Length(1),
+                // for the pop_up_border
+                Constraint::Length(1),
             ],
         )
-        .split(layer_zero[1]);
-
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Peer MultiAddress"),
-            );
-        f.render_widget(input, layer_one[0]);
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(Block::default().borders(Borders::ALL).title("Home Network"));
-        f.render_widget(input, layer_one[1]);
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Data dir Path"),
+        .split(layer_zero);
+        let pop_up_border = Paragraph::new("").block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Connection Limits")
+                .title_style(Style::new().fg(EUCALYPTUS))
+                .padding(Padding::uniform(2))
+                .border_style(Style::new().fg(EUCALYPTUS)),
+        );
+        clear_area(f, layer_zero);
+
+        // ==== input fields ====
+        for (index, title) in FIELD_TITLES.iter().enumerate() {
+            let layer_input_field = Layout::new(
+                Direction::Horizontal,
+                [
+                    // for the gap
+                    Constraint::Length(3),
+                    // title
+                    Constraint::Length(22),
+                    // Input box
+                    Constraint::Min(10),
+                ],
+            )
+            .split(layer_one[index + 1]);
+
+            let title_style = if index == self.selected_input {
+                Style::default().fg(EUCALYPTUS).bold()
+            } else {
+                Style::default().fg(GHOST_WHITE)
+            };
+            f.render_widget(
+                Paragraph::new(title.to_string()).style(title_style),
+                layer_input_field[1],
             );
-        f.render_widget(input, layer_one[2]);
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(Block::default().borders(Borders::ALL).title("Log dir Path"));
-        f.render_widget(input, layer_one[3]);
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(Block::default().borders(Borders::ALL).title("Node Version"));
-        f.render_widget(input, layer_one[4]);
-        let input = Paragraph::new(self.input.value())
-            .style(Style::default())
-            .block(Block::default().borders(Borders::ALL).title("RPC Address"));
-        f.render_widget(input, layer_one[5]);
+
+            let value = match self.get_input_val(index) {
+                Some(value) => value.to_string(),
+                None => "No limit".to_string(),
+            };
+            let input = Paragraph::new(value).style(Style::new().fg(VIVID_SKY_BLUE));
+            f.render_widget(input, layer_input_field[2]);
+        }
+
+        // ==== help ====
+        let help = Paragraph::new(
+            "  Note: The limits apply to each node. Leave a\n  field empty for no limit. Changing them\n  resets the nodes.",
+        )
+        .fg(GHOST_WHITE);
+        f.render_widget(help, layer_one[5]);
+
+        // ==== dash ====
+        let dash = Block::new()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::new().fg(GHOST_WHITE));
+        f.render_widget(dash, layer_one[6]);
+
+        // ==== buttons ====
+        let buttons_layer =
+            Layout::horizontal(vec![Constraint::Percentage(45), Constraint::Percentage(55)])
+                .split(layer_one[7]);
+
+        let button_no = Line::from(vec![Span::styled(
+            "  Close [Esc]",
+            Style::default().fg(LIGHT_PERIWINKLE),
+        )]);
+        f.render_widget(button_no, buttons_layer[0]);
+        let button_yes = Line::from(vec![Span::styled(
+            "Save Limits [Enter]  ",
+            Style::default().fg(EUCALYPTUS),
+        )]);
+        let button_yes = Paragraph::new(button_yes).alignment(Alignment::Right);
+        f.render_widget(button_yes, buttons_layer[1]);
+
+        f.render_widget(pop_up_border, layer_zero);
 
         Ok(())
     }
@@ -52,6 +52,16 @@ pub async fn configure_winsw() -> Result<()> {
 pub struct AppData {
     pub discord_username: String,
     pub nodes_to_start: usize,
+    #[serde(default)]
+    pub connection_limits: ConnectionLimits,
+}
+
+/// The limits applied to each node, none are applied when not set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConnectionLimits {
+    pub max_connections: Option<u32>,
+    pub max_upload_bytes_per_sec: Option<u64>,
+    pub max_download_bytes_per_sec: Option<u64>,
 }
 
 impl AppData {
@@ -956,6 +956,7 @@ mod tests {
             show_holders: false,
             max_repayments_for_failed_data: 1,
             collect_registers: false,
+            skip_existing_chunks: false,
         };
         let make_data_public = false;
         (cfg, make_data_public)
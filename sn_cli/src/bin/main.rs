@@ -16,7 +16,11 @@ use subcommands::{
     folders::folders_cmds,
     register::register_cmds,
     wallet::{
-        hot_wallet::{wallet_cmds, wallet_cmds_without_client, WalletCmds},
+        accounts::{
+            accounts_cmds, accounts_cmds_without_client, existing_account_root_dir, AccountsCmds,
+        },
+        hot_wallet::{unlock_wallet, wallet_cmds, wallet_cmds_without_client, WalletCmds},
+        multisig::MultisigCmds,
         wo_wallet::{wo_wallet_cmds, wo_wallet_cmds_without_client, WatchOnlyWalletCmds},
     },
     Opt, SubCmd,
@@ -27,7 +31,9 @@ use clap::Parser;
 use color_eyre::Result;
 use indicatif::ProgressBar;
 use sn_client::transfers::bls_secret_from_hex;
-use sn_client::{Client, ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver};
+use sn_client::{
+    Client, ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver, RecordCacheConfig,
+};
 #[cfg(feature = "metrics")]
 use sn_logging::{metrics::init_metrics, Level, LogBuilder, LogFormat};
 use std::{io, path::PathBuf, time::Duration};
@@ -73,15 +79,59 @@ async fn main() -> Result<()> {
     );
 
     let client_data_dir_path = get_client_data_dir_path()?;
+    // The accounts are derived from the default wallet, and share its password if it has one
+    let accounts_password = match &opt.cmd {
+        SubCmd::Wallet(WalletCmds::Accounts(cmds)) => {
+            let password = unlock_wallet(&client_data_dir_path)?;
+            if let AccountsCmds::List | AccountsCmds::New { .. } = cmds {
+                accounts_cmds_without_client(cmds, &client_data_dir_path, password.as_deref())?;
+                return Ok(());
+            }
+            password
+        }
+        _ => None,
+    };
+    // The wallet and data of an account other than the default one are kept under its own dir
+    let root_dir = match opt.account {
+        Some(account) => existing_account_root_dir(&client_data_dir_path, account)?,
+        None => client_data_dir_path.clone(),
+    };
+
+    // Unlock an encrypted hot wallet once for the whole command
+    if !matches!(
+        &opt.cmd,
+        SubCmd::WatchOnlyWallet(_)
+            | SubCmd::Wallet(
+                WalletCmds::Accounts(_)
+                    | WalletCmds::Encrypt
+                    | WalletCmds::ChangePassword
+                    | WalletCmds::Multisig(
+                        MultisigCmds::Propose { .. }
+                            | MultisigCmds::CoSign { .. }
+                            | MultisigCmds::Broadcast { .. }
+                    )
+            )
+    ) {
+        unlock_wallet(&root_dir)?;
+    }
+
     // Perform actions that do not require us connecting to the network and return early
     if let SubCmd::Wallet(cmds) = &opt.cmd {
         if let WalletCmds::Address { .. }
         | WalletCmds::Balance { .. }
         | WalletCmds::Create { .. }
+        | WalletCmds::Encrypt
+        | WalletCmds::ChangePassword
         | WalletCmds::Sign { .. }
+        | WalletCmds::Multisig(
+            MultisigCmds::Deal { .. }
+            | MultisigCmds::Create { .. }
+            | MultisigCmds::Propose { .. }
+            | MultisigCmds::CoSign { .. },
+        )
         | WalletCmds::Status = cmds
         {
-            wallet_cmds_without_client(cmds, &client_data_dir_path).await?;
+            wallet_cmds_without_client(cmds, &root_dir).await?;
             return Ok(());
         }
     }
@@ -93,7 +143,7 @@ async fn main() -> Result<()> {
         | WatchOnlyWalletCmds::Create { .. }
         | WatchOnlyWalletCmds::Transaction { .. } = cmds
         {
-            wo_wallet_cmds_without_client(cmds, &client_data_dir_path).await?;
+            wo_wallet_cmds_without_client(cmds, &root_dir).await?;
             return Ok(());
         }
     }
@@ -128,6 +178,10 @@ async fn main() -> Result<()> {
     )
     .await;
     let client = match result {
+        Ok(client) if opt.cache => client.with_record_cache(RecordCacheConfig {
+            dir: Some(client_data_dir_path.join("record_cache")),
+            ..Default::default()
+        }),
         Ok(client) => client,
         Err(err) => {
             // clean up progress bar
@@ -144,20 +198,23 @@ async fn main() -> Result<()> {
     // Hence capture the result and print it out explicity.
     let cmd_str = format!("{:?}", opt.cmd);
     let result = match opt.cmd {
-        SubCmd::Wallet(cmds) => {
-            wallet_cmds(cmds, &client, &client_data_dir_path, should_verify_store).await
+        SubCmd::Wallet(WalletCmds::Accounts(cmds)) => {
+            accounts_cmds(
+                &cmds,
+                &client,
+                &client_data_dir_path,
+                accounts_password.as_deref(),
+            )
+            .await
         }
+        SubCmd::Wallet(cmds) => wallet_cmds(cmds, &client, &root_dir, should_verify_store).await,
         SubCmd::WatchOnlyWallet(cmds) => {
-            wo_wallet_cmds(cmds, &client, &client_data_dir_path, should_verify_store).await
-        }
-        SubCmd::Files(cmds) => {
-            files_cmds(cmds, &client, &client_data_dir_path, should_verify_store).await
-        }
-        SubCmd::Folders(cmds) => {
-            folders_cmds(cmds, &client, &client_data_dir_path, should_verify_store).await
+            wo_wallet_cmds(cmds, &client, &root_dir, should_verify_store).await
         }
+        SubCmd::Files(cmds) => files_cmds(cmds, &client, &root_dir, should_verify_store).await,
+        SubCmd::Folders(cmds) => folders_cmds(cmds, &client, &root_dir, should_verify_store).await,
         SubCmd::Register(cmds) => {
-            register_cmds(cmds, &client, &client_data_dir_path, should_verify_store).await
+            register_cmds(cmds, &client, &root_dir, should_verify_store).await
         }
     };
     println!("Completed with {result:?} of execute {cmd_str:?}");
@@ -7,8 +7,8 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use autonomi::{
-    download_file, download_files, ChunkManager, Estimator, FilesUploader, UploadedFile,
-    UPLOADED_FILES,
+    audit_file, download_file, download_files, ChunkManager, Estimator, FilesUploader,
+    UploadedFile, UPLOADED_FILES,
 };
 use clap::Parser;
 use color_eyre::{
@@ -56,6 +56,12 @@ pub enum FilesCmds {
         /// to 'persistent' (most effort).
         #[clap(long, default_value_t = RetryStrategy::Balanced, short = 'r', help = "Sets the retry strategy on upload failure. Options: 'quick' for minimal effort, 'balanced' for moderate effort, or 'persistent' for maximum effort.")]
         retry_strategy: RetryStrategy,
+        /// Check which chunks are already stored on the network before paying for them.
+        ///
+        /// The chunks found are neither paid for nor uploaded again, which saves payments when
+        /// uploading data that is mostly on the network already.
+        #[clap(long, default_value = "false")]
+        skip_existing_chunks: bool,
     },
     Download {
         /// The name to apply to the downloaded file.
@@ -86,6 +92,20 @@ pub enum FilesCmds {
         #[clap(long, default_value_t = RetryStrategy::Quick, short = 'r', help = "Sets the retry strategy on download failure. Options: 'quick' for minimal effort, 'balanced' for moderate effort, or 'persistent' for maximum effort.")]
         retry_strategy: RetryStrategy,
     },
+    /// Audit a file uploaded by the current user, checking its chunks are still held on the network.
+    ///
+    /// The close group of each chunk is asked for a proof of holding it. The chunks held by none of
+    /// them are uploaded again, if their local artifacts are still available.
+    Audit {
+        /// The hex address of the uploaded file.
+        #[clap(name = "address")]
+        file_addr: String,
+        /// Audit only this many randomly picked chunks of the file.
+        ///
+        /// All the chunks are audited by default.
+        #[clap(long, short = 's')]
+        sample: Option<usize>,
+    },
 }
 
 pub(crate) async fn files_cmds(
@@ -110,6 +130,7 @@ pub(crate) async fn files_cmds(
             batch_size,
             retry_strategy,
             make_data_public,
+            skip_existing_chunks,
         } => {
             let files_count = count_files_in_path_recursively(&file_path);
 
@@ -127,6 +148,7 @@ pub(crate) async fn files_cmds(
                 batch_size,
                 verify_store,
                 retry_strategy,
+                skip_existing_chunks,
                 ..Default::default()
             };
             let files_uploader = FilesUploader::new(client.clone(), root_dir.to_path_buf())
@@ -245,6 +267,24 @@ pub(crate) async fn files_cmds(
                 }
             }
         }
+        FilesCmds::Audit { file_addr, sample } => {
+            let bytes = hex::decode(&file_addr)
+                .map_err(|err| eyre!("Input address is not a hex string: {err}"))?;
+            let xor_name = XorName(
+                bytes
+                    .try_into()
+                    .map_err(|_| eyre!("Failed to parse XorName from hex string"))?,
+            );
+            let files_api = FilesApi::new(client.clone(), root_dir.to_path_buf());
+            audit_file(
+                &files_api,
+                root_dir,
+                ChunkAddress::new(xor_name),
+                sample,
+                verify_store,
+            )
+            .await?
+        }
     }
     Ok(())
 }
@@ -61,6 +61,18 @@ pub(crate) struct Opt {
     /// This may increase operation speed, but offers no guarantees that operations were successful.
     #[clap(global = true, long = "no-verify", short = 'x')]
     pub no_verify: bool,
+
+    /// Cache the chunks, spends and registers fetched from the network, in memory and on disk.
+    ///
+    /// Repeated downloads of the same data are then served from the cache.
+    #[clap(global = true, long = "cache")]
+    pub cache: bool,
+
+    /// The account, derived from the mnemonic, whose wallet and data the command acts on.
+    ///
+    /// The default wallet is the account 0.
+    #[clap(global = true, long = "account")]
+    pub account: Option<u32>,
 }
 
 #[derive(Subcommand, Debug)]
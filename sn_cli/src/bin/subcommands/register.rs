@@ -14,7 +14,7 @@ use sn_client::protocol::storage::RegisterAddress;
 use sn_client::registers::Permissions;
 
 use sn_client::{Client, Error as ClientError, WalletClient};
-use std::path::Path;
+use std::{collections::BTreeSet, path::Path};
 use xor_name::XorName;
 
 #[derive(Subcommand, Debug)]
@@ -53,6 +53,10 @@ pub enum RegisterCmds {
         /// Use this flag if you are providing the register names instead of the addresses
         #[clap(name = "name", short = 'n')]
         use_name: bool,
+        /// Print all the entries ever written to the registers, from the first to the latest,
+        /// instead of only the latest ones.
+        #[clap(long)]
+        history: bool,
     },
 }
 
@@ -74,7 +78,8 @@ pub(crate) async fn register_cmds(
         RegisterCmds::Get {
             addresses,
             use_name,
-        } => get_registers(addresses, use_name, client).await?,
+            history,
+        } => get_registers(addresses, use_name, history, client).await?,
     }
     Ok(())
 }
@@ -158,7 +163,12 @@ async fn edit_register(
     Ok(())
 }
 
-async fn get_registers(addresses: Vec<String>, use_name: bool, client: &Client) -> Result<()> {
+async fn get_registers(
+    addresses: Vec<String>,
+    use_name: bool,
+    history: bool,
+    client: &Client,
+) -> Result<()> {
     for addr in addresses {
         let (address, printing_name) = parse_addr(&addr, use_name, client.signer_pk())?;
 
@@ -168,13 +178,22 @@ async fn get_registers(addresses: Vec<String>, use_name: bool, client: &Client)
             Ok(register) => {
                 println!("Successfully retrieved Register {printing_name}");
                 let entries = register.read();
-                println!("Register entries:");
-                for (hash, bytes) in entries {
-                    let data_str = match String::from_utf8(bytes.clone()) {
-                        Ok(data_str) => data_str,
-                        Err(_) => format!("{bytes:?}"),
-                    };
-                    println!("{hash:?}: {data_str}");
+                if history {
+                    let latest: BTreeSet<_> = entries.into_iter().map(|(hash, _)| hash).collect();
+                    println!("Register history, from the first entry written:");
+                    for (hash, bytes) in register.history() {
+                        let marker = if latest.contains(&hash) {
+                            " (latest)"
+                        } else {
+                            ""
+                        };
+                        println!("{hash:?}: {}{marker}", entry_to_string(&bytes));
+                    }
+                } else {
+                    println!("Register entries:");
+                    for (hash, bytes) in entries {
+                        println!("{hash:?}: {}", entry_to_string(&bytes));
+                    }
                 }
             }
             Err(error) => {
@@ -189,6 +208,14 @@ async fn get_registers(addresses: Vec<String>, use_name: bool, client: &Client)
     Ok(())
 }
 
+/// Returns the entry as a string if it is valid UTF-8, or its bytes otherwise
+fn entry_to_string(bytes: &[u8]) -> String {
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(data_str) => data_str,
+        Err(_) => format!("{bytes:?}"),
+    }
+}
+
 /// Parse str and return the address and the register info for printing
 fn parse_addr(
     address_str: &str,
@@ -6,9 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub(crate) mod accounts;
 mod audit;
 pub(crate) mod helpers;
 pub(crate) mod hot_wallet;
+pub(crate) mod multisig;
 pub(crate) mod wo_wallet;
 
 use sn_client::transfers::{CashNote, HotWallet, MainPubkey, NanoTokens, WatchOnlyWallet};
@@ -0,0 +1,170 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use clap::Parser;
+use color_eyre::{eyre::eyre, Help, Result};
+use sn_client::{
+    acc_packet::{account_root_dir, account_wallet_key, create_account_wallet, list_accounts},
+    transfers::{HotWallet, SpendAddress, GENESIS_SPEND_UNIQUE_KEY},
+    Client,
+};
+use std::path::{Path, PathBuf};
+
+/// The number of consecutive unused accounts after which the recovery stops looking for more.
+const DEFAULT_ACCOUNTS_GAP_LIMIT: u32 = 20;
+
+#[derive(Parser, Debug)]
+pub enum AccountsCmds {
+    /// List the accounts with a wallet, along with their address and balance.
+    List,
+    /// Derive the wallet of a new account from the mnemonic.
+    New {
+        /// Optional passphrase the mnemonic of the default wallet was used with.
+        #[clap(long)]
+        passphrase: Option<String>,
+    },
+    /// Recover the wallets of the accounts which were used to make transfers, along with their
+    /// unspent change, by looking for their spends on the Network.
+    ///
+    /// The accounts are scanned in order, until as many consecutive ones as the gap limit are
+    /// found unused. Note that the accounts which only received tokens can't be found.
+    Recover {
+        /// The number of consecutive unused accounts after which to stop looking for more.
+        #[clap(long, default_value_t = DEFAULT_ACCOUNTS_GAP_LIMIT)]
+        gap_limit: u32,
+        /// Optional passphrase the mnemonic of the default wallet was used with.
+        #[clap(long)]
+        passphrase: Option<String>,
+    },
+}
+
+/// The accounts are derived from the default wallet at `root_dir`, and have their main key
+/// encrypted with its password, if it has one.
+pub(crate) fn accounts_cmds_without_client(
+    cmds: &AccountsCmds,
+    root_dir: &Path,
+    password: Option<&str>,
+) -> Result<()> {
+    match cmds {
+        AccountsCmds::List => {
+            let accounts = list_accounts(root_dir)?;
+            println!(
+                "{} accounts found at {}:",
+                accounts.len(),
+                root_dir.display()
+            );
+            for account in accounts {
+                let account_root_dir = account_root_dir(root_dir, account);
+                let wallet = match password {
+                    Some(password) if HotWallet::is_encrypted(&account_root_dir) => {
+                        HotWallet::load_from_with_password(&account_root_dir, password)
+                    }
+                    _ => HotWallet::load_from(&account_root_dir),
+                };
+                match wallet {
+                    Ok(wallet) => println!(
+                        "- account {account}: {:?}, balance {}",
+                        wallet.address(),
+                        wallet.balance()
+                    ),
+                    Err(err) => println!("- account {account}: {err}"),
+                }
+            }
+            Ok(())
+        }
+        AccountsCmds::New { passphrase } => {
+            let account = list_accounts(root_dir)?
+                .last()
+                .map_or(1, |last_account| last_account + 1);
+            let wallet = create_account_wallet(root_dir, account, passphrase.as_deref(), password)?;
+            println!(
+                "Created the wallet of account {account}, for main public key: {:?}",
+                wallet.address()
+            );
+            Ok(())
+        }
+        cmd => Err(eyre!(
+            "{cmd:?} has to be processed after connecting to the network"
+        )),
+    }
+}
+
+pub(crate) async fn accounts_cmds(
+    cmds: &AccountsCmds,
+    client: &Client,
+    root_dir: &Path,
+    password: Option<&str>,
+) -> Result<()> {
+    match cmds {
+        AccountsCmds::Recover {
+            gap_limit,
+            passphrase,
+        } => {
+            recover_accounts(
+                client,
+                root_dir,
+                *gap_limit,
+                passphrase.as_deref(),
+                password,
+            )
+            .await
+        }
+        cmd => accounts_cmds_without_client(cmd, root_dir, password),
+    }
+}
+
+async fn recover_accounts(
+    client: &Client,
+    root_dir: &Path,
+    gap_limit: u32,
+    passphrase: Option<&str>,
+    password: Option<&str>,
+) -> Result<()> {
+    println!("Crawling the spends of the Network for the transfers of the accounts...");
+    let genesis_addr = SpendAddress::from_unique_pubkey(&GENESIS_SPEND_UNIQUE_KEY);
+    let dag = client
+        .spend_dag_build_from(genesis_addr, None, false)
+        .await?;
+
+    let existing_accounts = list_accounts(root_dir)?;
+    let mut unused_in_a_row = 0;
+    for account in 1..=u32::MAX {
+        if unused_in_a_row == gap_limit {
+            println!("Stopped at account {account}, after {gap_limit} unused accounts in a row");
+            break;
+        }
+        let key = account_wallet_key(root_dir, account, passphrase)?;
+        if !dag.has_change_outputs(&key) {
+            unused_in_a_row += 1;
+            continue;
+        }
+        unused_in_a_row = 0;
+
+        if existing_accounts.contains(&account) {
+            println!("Account {account} was used, and already has a wallet");
+            continue;
+        }
+        let mut wallet = create_account_wallet(root_dir, account, passphrase, password)?;
+        wallet.deposit_and_store_to_disk(&dag.unspent_change_cash_notes(&key))?;
+        println!(
+            "Recovered account {account}: {:?}, balance {}",
+            wallet.address(),
+            wallet.balance()
+        );
+    }
+    Ok(())
+}
+
+/// The root dir of the wallet of the account, which has to exist already.
+pub(crate) fn existing_account_root_dir(root_dir: &Path, account: u32) -> Result<PathBuf> {
+    if !list_accounts(root_dir)?.contains(&account) {
+        return Err(eyre!("No wallet found for account {account}")
+            .suggestion("Create one with `safe wallet accounts new`"));
+    }
+    Ok(account_root_dir(root_dir, account))
+}
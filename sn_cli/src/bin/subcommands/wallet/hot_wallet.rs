@@ -7,8 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
+    accounts::AccountsCmds,
     audit::{audit, verify_spend_at},
     helpers::{get_faucet, receive},
+    multisig::{multisig_cmds, multisig_cmds_without_client, MultisigCmds},
     WalletApiHelper,
 };
 use crate::get_stdin_response;
@@ -19,7 +21,7 @@ use color_eyre::{
     eyre::{bail, eyre},
     Result,
 };
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Password};
 use sn_client::transfers::{
     HotWallet, MainPubkey, MainSecretKey, NanoTokens, Transfer, TransferError, UnsignedTransfer,
     WalletError,
@@ -29,6 +31,10 @@ use sn_client::{
 };
 use std::{path::Path, str::FromStr};
 
+/// Environment variable the password of an encrypted hot wallet is read from, instead of
+/// prompting for it.
+const WALLET_PASSWORD_ENV: &str = "SAFE_WALLET_PASSWORD";
+
 // Please do not remove the blank lines in these doc comments.
 // They are used for inserting line breaks when the help menu is rendered in the UI.
 #[derive(Parser, Debug)]
@@ -54,7 +60,27 @@ pub enum WalletCmds {
         /// Hex-encoded main secret key.
         #[clap(name = "key")]
         key: String,
+        /// Encrypt the key with a password, which will be prompted for.
+        #[clap(long)]
+        encrypt: bool,
     },
+    /// Encrypt the key of the hot wallet with a password.
+    ///
+    /// The plain key file is replaced with the encrypted one. The password is then prompted for
+    /// once per command, unless it is set in the SAFE_WALLET_PASSWORD environment variable.
+    Encrypt,
+    /// Change the password the key of the hot wallet is encrypted with.
+    ChangePassword,
+    /// Manage the accounts derived from the mnemonic, each with its own wallet.
+    ///
+    /// The account 0 is the default wallet, the others are picked with the `--account` option.
+    #[clap(subcommand)]
+    Accounts(AccountsCmds),
+    /// Create, co-sign and broadcast the spends of a treasury shared by several members.
+    ///
+    /// Each spend has to be signed by a number of the members, set when creating the treasury.
+    #[clap(subcommand)]
+    Multisig(MultisigCmds),
     /// Get tokens from a faucet.
     GetFaucet {
         /// The http url of the faucet to get tokens from.
@@ -128,6 +154,13 @@ pub enum WalletCmds {
         #[clap(long, name = "sk_str")]
         sk_str: Option<String>,
     },
+    /// Restore the CashNotes of the wallet by crawling the spends of the Network,
+    /// e.g. after losing the wallet directory but not the mnemonic.
+    ///
+    /// Only the change of the transfers made from the wallet can be found, the CashNotes
+    /// received from others cannot be told apart on the Network.
+    /// Note that this might take a very long time, as the entire spend DAG is crawled.
+    Restore,
     Status,
 }
 
@@ -162,11 +195,10 @@ pub(crate) async fn wallet_cmds_without_client(cmds: &WalletCmds, root_dir: &Pat
             }
             Ok(())
         }
-        WalletCmds::Create { key } => {
+        WalletCmds::Create { key, encrypt } => {
             let sk = SecretKey::from_hex(key)
                 .map_err(|err| eyre!("Failed to parse hex-encoded SK: {err:?}"))?;
             let main_sk = MainSecretKey::new(sk);
-            // TODO: encrypt wallet file
             // check for existing wallet with balance
             let existing_balance = match WalletApiHelper::load_from(root_dir) {
                 Ok(wallet) => wallet.balance(),
@@ -187,14 +219,32 @@ pub(crate) async fn wallet_cmds_without_client(cmds: &WalletCmds, root_dir: &Pat
             }
             // Create the new wallet with the new key
             let main_pubkey = main_sk.main_pubkey();
-            let local_wallet = HotWallet::create_from_key(root_dir, main_sk)?;
+            let local_wallet = if *encrypt {
+                HotWallet::create_from_key_with_password(root_dir, main_sk, &new_password()?)?
+            } else {
+                HotWallet::create_from_key(root_dir, main_sk)?
+            };
             let balance = local_wallet.balance();
             println!(
                 "Hot Wallet created (balance {balance}) for main public key: {main_pubkey:?}."
             );
             Ok(())
         }
+        WalletCmds::Encrypt => {
+            HotWallet::encrypt(root_dir, &new_password()?)?;
+            println!("The hot wallet key is now encrypted.");
+            Ok(())
+        }
+        WalletCmds::ChangePassword => {
+            let password = Password::new()
+                .with_prompt("Enter the current wallet password")
+                .interact()?;
+            HotWallet::change_password(root_dir, &password, &new_password()?)?;
+            println!("The hot wallet password has been changed.");
+            Ok(())
+        }
         WalletCmds::Sign { tx, force } => sign_transaction(tx, root_dir, *force),
+        WalletCmds::Multisig(cmds) => multisig_cmds_without_client(cmds, root_dir),
         WalletCmds::Status => {
             let mut wallet = WalletApiHelper::load_from(root_dir)?;
             println!("{}", wallet.balance());
@@ -242,12 +292,56 @@ pub(crate) async fn wallet_cmds(
             spend_address,
             genesis,
         } => verify_spend_at(spend_address, genesis, client, root_dir).await,
+        WalletCmds::Multisig(cmds) => multisig_cmds(cmds, client, verify_store).await,
+        WalletCmds::Restore => {
+            let mut wallet = load_account_wallet_or_create_with_mnemonic(root_dir, None)?;
+            println!(
+                "Crawling the spends of the Network for the CashNotes of {:?}...",
+                wallet.address()
+            );
+            let cash_notes = client.restore_cash_notes(wallet.key()).await?;
+            wallet.deposit_and_store_to_disk(&cash_notes)?;
+            println!(
+                "Restored {} CashNotes, the wallet balance is now {}",
+                cash_notes.len(),
+                wallet.balance()
+            );
+            Ok(())
+        }
         cmd => Err(eyre!(
             "{cmd:?} has to be processed before connecting to the network"
         )),
     }
 }
 
+/// Unlocks the hot wallet for the rest of the session if its key is encrypted, so that the
+/// password is only prompted for once. Returns the password of an encrypted wallet.
+pub(crate) fn unlock_wallet(root_dir: &Path) -> Result<Option<String>> {
+    if !HotWallet::is_encrypted(root_dir) {
+        return Ok(None);
+    }
+
+    let password = match std::env::var(WALLET_PASSWORD_ENV) {
+        Ok(password) => password,
+        Err(_) => Password::new()
+            .with_prompt("Enter the wallet password")
+            .interact()?,
+    };
+    let _ = HotWallet::load_from_with_password(root_dir, &password)?;
+    Ok(Some(password))
+}
+
+fn new_password() -> Result<String> {
+    let password = Password::new()
+        .with_prompt("Enter a new wallet password")
+        .with_confirmation(
+            "Repeat the new wallet password",
+            "The passwords do not match",
+        )
+        .interact()?;
+    Ok(password)
+}
+
 async fn send(
     amount: String,
     to: String,
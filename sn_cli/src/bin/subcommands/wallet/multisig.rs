@@ -0,0 +1,254 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::wo_wallet::broadcast_transaction;
+
+use clap::Parser;
+use color_eyre::{
+    eyre::{bail, eyre},
+    Result,
+};
+use dialoguer::Confirm;
+use sn_client::transfers::{
+    rng, MainPubkey, MultisigKeyDeal, MultisigPubkeySet, MultisigSecretKeyShare,
+    PartiallySignedTransfer, UnsignedTransfer,
+};
+use sn_client::{acc_packet::load_account_wallet_or_create_with_mnemonic, Client};
+use std::path::Path;
+
+// Please do not remove the blank lines in these doc comments.
+// They are used for inserting line breaks when the help menu is rendered in the UI.
+#[derive(Parser, Debug)]
+pub enum MultisigCmds {
+    /// Deal the keys of a new treasury to its members, a number of whom have to sign each spend.
+    ///
+    /// Each member runs this cmd with the same arguments, the hot wallet of the member being one
+    /// of the members. The printed deal is to be shared with all the other members.
+    Deal {
+        /// The number of members who have to sign a spend.
+        #[clap(name = "required signatures")]
+        required_signatures: usize,
+        /// Hex-encoded main public keys of the hot wallets of the members, in the same order for all of them.
+        #[clap(long, num_args = 1.., required = true)]
+        members: Vec<String>,
+    },
+    /// Combine the deals of all the members into the keys of the new treasury.
+    ///
+    /// Each member runs this cmd with the deals of all the members, and gets its own key share,
+    /// to be kept secret. The funds of the treasury are followed with a watch-only wallet of its address.
+    Create {
+        /// The number of members who have to sign a spend.
+        #[clap(name = "required signatures")]
+        required_signatures: usize,
+        /// Hex-encoded main public keys of the hot wallets of the members, as given to the 'deal' cmd.
+        #[clap(long, num_args = 1.., required = true)]
+        members: Vec<String>,
+        /// Hex-encoded deals of all the members.
+        #[clap(long, num_args = 1.., required = true)]
+        deals: Vec<String>,
+    },
+    /// Start collecting the signatures of an unsigned transaction from a treasury, as built
+    /// by the watch-only wallet 'transaction' command.
+    Propose {
+        /// Hex-encoded unsigned transaction.
+        #[clap(name = "tx")]
+        tx: String,
+        /// Hex-encoded key set of the treasury.
+        #[clap(name = "key set")]
+        key_set: String,
+    },
+    /// Add the signature of a member of the treasury to a multisig transaction.
+    CoSign {
+        /// Hex-encoded multisig transaction.
+        #[clap(name = "tx")]
+        tx: String,
+        /// Hex-encoded key share of the member.
+        #[clap(long)]
+        key_share: String,
+        /// Avoid prompts by assuming `yes` as the answer.
+        #[clap(long, name = "force", default_value = "false")]
+        force: bool,
+    },
+    /// Combine the signatures of a multisig transaction and broadcast it to the network.
+    Broadcast {
+        /// Hex-encoded multisig transaction, signed by enough members.
+        #[clap(name = "tx")]
+        tx: String,
+        /// Avoid prompts by assuming `yes` as the answer.
+        #[clap(long, name = "force", default_value = "false")]
+        force: bool,
+    },
+}
+
+pub(crate) fn multisig_cmds_without_client(cmds: &MultisigCmds, root_dir: &Path) -> Result<()> {
+    match cmds {
+        MultisigCmds::Deal {
+            required_signatures,
+            members,
+        } => {
+            let wallet = load_account_wallet_or_create_with_mnemonic(root_dir, None)?;
+            let members = parse_members(members)?;
+            let deal = MultisigKeyDeal::new(
+                *required_signatures,
+                &members,
+                wallet.key(),
+                &mut rng::thread_rng(),
+            )?;
+            println!(
+                "The deal of member {} of the treasury, to be shared with all the other members:\n\n{}\n",
+                deal.dealer(),
+                deal.to_hex()?
+            );
+            println!(
+                "Once all the members have dealt, please create the treasury keys with the 'wallet multisig create' cmd."
+            );
+            Ok(())
+        }
+        MultisigCmds::Create {
+            required_signatures,
+            members,
+            deals,
+        } => {
+            let wallet = load_account_wallet_or_create_with_mnemonic(root_dir, None)?;
+            let members = parse_members(members)?;
+            let deals = deals
+                .iter()
+                .map(|deal| MultisigKeyDeal::from_hex(deal))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (key_set, key_share) = MultisigPubkeySet::from_deals(
+                *required_signatures,
+                &members,
+                wallet.key(),
+                &deals,
+            )?;
+            println!(
+                "Created a treasury of {} members, {required_signatures} of whom have to sign each spend.",
+                members.len()
+            );
+            println!(
+                "Address of the treasury: {}",
+                key_set.main_pubkey().to_hex()
+            );
+            println!("Key set of the treasury:\n\n{}\n", key_set.to_hex()?);
+            println!(
+                "Key share of member {}, to be kept secret:\n\n{}\n",
+                key_share.index(),
+                key_share.to_hex()
+            );
+            Ok(())
+        }
+        MultisigCmds::Propose { tx, key_set } => {
+            let unsigned_transfer: UnsignedTransfer = rmp_serde::from_slice(&hex::decode(tx)?)?;
+            let key_set = MultisigPubkeySet::from_hex(key_set)?;
+            let transfer = PartiallySignedTransfer::new(unsigned_transfer, key_set)?;
+            print_transfer(&transfer)?;
+            println!(
+                "Please share the above text with the members, to sign it with the 'wallet multisig co-sign' cmd."
+            );
+            Ok(())
+        }
+        MultisigCmds::CoSign {
+            tx,
+            key_share,
+            force,
+        } => co_sign(tx, key_share, *force),
+        cmd => Err(eyre!("{cmd:?} requires us to be connected to the Network")),
+    }
+}
+
+pub(crate) async fn multisig_cmds(
+    cmds: MultisigCmds,
+    client: &Client,
+    verify_store: bool,
+) -> Result<()> {
+    match cmds {
+        MultisigCmds::Broadcast { tx, force } => {
+            let transfer: PartiallySignedTransfer = rmp_serde::from_slice(&hex::decode(tx)?)?;
+            let signed_spends = transfer.signed_spends()?;
+            println!("The signatures of the multisig transaction have been successfully combined:");
+            broadcast_transaction(
+                signed_spends,
+                transfer.unsigned_transfer.output_details,
+                transfer.unsigned_transfer.change_id,
+                client,
+                verify_store,
+                force,
+            )
+            .await
+        }
+        cmd => Err(eyre!(
+            "{cmd:?} has to be processed before connecting to the network"
+        )),
+    }
+}
+
+fn co_sign(tx: &str, key_share: &str, force: bool) -> Result<()> {
+    let mut transfer: PartiallySignedTransfer = rmp_serde::from_slice(&hex::decode(tx)?)?;
+    let key_share = MultisigSecretKeyShare::from_hex(key_share)?;
+
+    println!("The multisig transaction has been successfully decoded:");
+    let tx = &transfer.unsigned_transfer.tx;
+    for (i, input) in tx.inputs.iter().enumerate() {
+        println!("\nSpending input #{i}:");
+        println!("\tKey: {}", input.unique_pubkey.to_hex());
+        println!("\tAmount: {}", input.amount);
+    }
+    for (i, output) in tx.outputs.iter().enumerate() {
+        println!("\nOutput #{i}:");
+        println!("\tKey: {}", output.unique_pubkey.to_hex());
+        println!("\tAmount: {}", output.amount);
+    }
+    if transfer.signers().contains(&key_share.index()) {
+        bail!(
+            "The transaction has already been signed by member {}",
+            key_share.index()
+        );
+    }
+
+    if !force {
+        println!("\n** Please make sure the above information is correct before signing it. **\n");
+        let confirmation = Confirm::new()
+            .with_prompt("Do you want to sign the above transaction?")
+            .interact()?;
+
+        if !confirmation {
+            println!("Transaction not signed.");
+            return Ok(());
+        }
+    }
+
+    transfer.co_sign(&key_share)?;
+    print_transfer(&transfer)?;
+    match transfer.missing_signatures() {
+        0 => println!(
+            "The transaction is signed by enough members, please broadcast it with the 'wallet multisig broadcast' cmd."
+        ),
+        missing => println!(
+            "{missing} more members have to sign it with the 'wallet multisig co-sign' cmd."
+        ),
+    }
+    Ok(())
+}
+
+fn parse_members(members: &[String]) -> Result<Vec<MainPubkey>> {
+    let members = members
+        .iter()
+        .map(MainPubkey::from_hex)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(members)
+}
+
+fn print_transfer(transfer: &PartiallySignedTransfer) -> Result<()> {
+    println!(
+        "The multisig transaction, signed by {} of the {} required members:\n\n{}\n",
+        transfer.signers().len(),
+        transfer.key_set.required_signatures(),
+        hex::encode(rmp_serde::to_vec(transfer)?)
+    );
+    Ok(())
+}
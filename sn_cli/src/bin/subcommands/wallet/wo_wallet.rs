@@ -256,6 +256,26 @@ async fn broadcast_signed_spends(
     ) = rmp_serde::from_slice(&hex::decode(signed_tx)?)?;
 
     println!("The signed transaction has been successfully decoded:");
+    broadcast_transaction(
+        signed_spends,
+        output_details,
+        change_id,
+        client,
+        verify_store,
+        force,
+    )
+    .await
+}
+
+/// Display the signed transaction, and broadcast it to the network once confirmed.
+pub(super) async fn broadcast_transaction(
+    signed_spends: BTreeSet<SignedSpend>,
+    output_details: BTreeMap<UniquePubkey, (MainPubkey, DerivationIndex)>,
+    change_id: UniquePubkey,
+    client: &Client,
+    verify_store: bool,
+    force: bool,
+) -> Result<()> {
     let mut transaction = None;
     for (i, signed_spend) in signed_spends.iter().enumerate() {
         println!("\nSpending input #{i}:");
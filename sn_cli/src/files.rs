@@ -6,12 +6,14 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod audit;
 mod chunk_manager;
 mod download;
 mod estimate;
 mod files_uploader;
 mod upload;
 
+pub use audit::audit_file;
 pub use chunk_manager::ChunkManager;
 pub use download::{download_file, download_files};
 pub use estimate::Estimator;
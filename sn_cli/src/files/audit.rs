@@ -0,0 +1,135 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    upload::{UploadedFile, UPLOADED_FILES},
+    ChunkManager,
+};
+use bytes::Bytes;
+use color_eyre::{eyre::eyre, Help, Result};
+use rand::{seq::SliceRandom, thread_rng};
+use sn_client::{
+    protocol::storage::{Chunk, ChunkAddress},
+    FilesApi,
+};
+use std::{fs, path::Path};
+use tracing::{info, warn};
+
+/// Audits the chunks of a file uploaded by the current user, asking the close group of each of
+/// them for proofs of holding it. Only `sample_size` randomly picked chunks are audited, if set.
+///
+/// The chunks held by none of their close group are uploaded again, if their SE artifacts are
+/// still available locally.
+pub async fn audit_file(
+    files_api: &FilesApi,
+    root_dir: &Path,
+    head_address: ChunkAddress,
+    sample_size: Option<usize>,
+    verify_store: bool,
+) -> Result<()> {
+    let uploaded_file_path = root_dir.join(UPLOADED_FILES).join(head_address.to_hex());
+    if !uploaded_file_path.exists() {
+        return Err(
+            eyre!("No file uploaded by the current user at {head_address:?}").suggestion(
+                "Please provide the hex address of a file listed in the uploaded_files directory",
+            ),
+        );
+    }
+    let uploaded_file = UploadedFile::read(&uploaded_file_path)?;
+    let data_map_chunk = match uploaded_file.data_map {
+        Some(data_map) => Chunk {
+            address: head_address,
+            value: data_map,
+        },
+        None => {
+            files_api
+                .client()
+                .get_chunk(head_address, false, None)
+                .await?
+        }
+    };
+
+    let mut chunk_addresses = files_api.file_chunk_addresses(data_map_chunk).await?;
+    if let Some(sample_size) = sample_size {
+        chunk_addresses.shuffle(&mut thread_rng());
+        chunk_addresses.truncate(sample_size);
+    }
+    println!(
+        "Auditing {} chunks of {:?}...",
+        chunk_addresses.len(),
+        uploaded_file.filename
+    );
+
+    let chunk_manager = ChunkManager::new(root_dir);
+    let mut chunks_to_upload = Vec::new();
+    let mut lost_chunks = 0;
+    for address in chunk_addresses {
+        let artifact = chunk_manager.chunk_artifact(address.xorname());
+        // the proofs are checked against the content of the chunk, which has to be fetched
+        // from the network when it is not around locally anymore
+        let chunk = match &artifact {
+            Some(path) => Chunk::new(Bytes::from(fs::read(path)?)),
+            None => match files_api.client().get_chunk(address, false, None).await {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    warn!("Failed to fetch the chunk {address:?} to audit it: {err:?}");
+                    println!(
+                        "Chunk {}: could not be fetched from the network, nor found locally",
+                        address.to_hex()
+                    );
+                    lost_chunks += 1;
+                    continue;
+                }
+            },
+        };
+
+        let audit = files_api.client().audit_chunk(&chunk).await?;
+        info!("Audited chunk {address:?}: {audit:?}");
+        println!(
+            "Chunk {}: held by {} of its {} close peers",
+            address.to_hex(),
+            audit.holders.len(),
+            audit.holders.len() + audit.failures.len()
+        );
+        for peer in &audit.holders {
+            println!("    holder: {peer}");
+        }
+        for peer in &audit.failures {
+            println!("    failed: {peer}");
+        }
+
+        if audit.is_missing() {
+            if artifact.is_some() {
+                chunks_to_upload.push(chunk);
+            } else {
+                lost_chunks += 1;
+            }
+        }
+    }
+
+    if !chunks_to_upload.is_empty() {
+        println!(
+            "Uploading {} missing chunks again from their local artifacts...",
+            chunks_to_upload.len()
+        );
+        let _ = files_api
+            .pay_for_chunks(chunks_to_upload.iter().map(|chunk| *chunk.name()).collect())
+            .await?;
+        for chunk in chunks_to_upload {
+            files_api
+                .get_local_payment_and_upload_chunk(chunk, verify_store, None)
+                .await?;
+        }
+        println!("Uploaded the missing chunks again.");
+    }
+    if lost_chunks > 0 {
+        println!("{lost_chunks} chunks are missing and cannot be uploaded again, as their local artifacts are gone.");
+    }
+
+    Ok(())
+}
@@ -450,6 +450,20 @@ impl ChunkManager {
             .collect()
     }
 
+    /// The path of the SE chunk in the CHUNK_ARTIFACTS_DIR, if it has not been removed yet.
+    pub(crate) fn chunk_artifact(&self, chunk_name: &XorName) -> Option<PathBuf> {
+        let chunk_file_name = hex::encode(chunk_name);
+        WalkDir::new(&self.artifacts_dir)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .flatten()
+            .find(|entry| {
+                entry.file_type().is_file() && entry.file_name() == chunk_file_name.as_str()
+            })
+            .map(|entry| entry.into_path())
+    }
+
     /// Returns an iterator over the list of chunked files
     pub(crate) fn iter_chunked_files(&mut self) -> impl Iterator<Item = &ChunkedFile> {
         self.chunks.values()
@@ -100,7 +100,8 @@ pub async fn download_file(
     let mut files_download = FilesDownload::new(files_api.clone())
         .set_batch_size(batch_size)
         .set_show_holders(show_holders)
-        .set_retry_strategy(retry_strategy);
+        .set_retry_strategy(retry_strategy)
+        .set_resumable(true);
 
     println!("Downloading {file_name:?} from {xor_name:64x} with batch-size {batch_size}");
     debug!("Downloading {file_name:?} from {:64x}", xor_name);
@@ -140,6 +141,15 @@ pub async fn download_file(
                         err
                     }).ok();
                 }
+                FilesDownloadEvent::Resumed(count) => {
+                    info!("Resuming download with {count} chunks already fetched");
+                    if let Some(progress_bar) = &progress_bar {
+                        progress_bar.inc(count as u64);
+                    }
+                }
+                FilesDownloadEvent::JournalRemoved => {
+                    debug!("Removed the download journal");
+                }
                 FilesDownloadEvent::Error => {
                     error!("Got FilesDownloadEvent::Error");
                 }
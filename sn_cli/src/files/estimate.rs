@@ -10,13 +10,20 @@ use super::ChunkManager;
 
 use std::path::{Path, PathBuf};
 
+use bytes::Bytes;
 use color_eyre::Result;
+use futures::StreamExt;
 
 use sn_client::{
-    protocol::{storage::ChunkAddress, NetworkAddress},
+    protocol::{
+        storage::{Chunk, ChunkAddress},
+        NetworkAddress,
+    },
     transfers::NanoTokens,
-    FilesApi,
+    FilesApi, BATCH_SIZE,
 };
+use tracing::warn;
+use xor_name::XorName;
 
 pub struct Estimator {
     chunk_manager: ChunkManager,
@@ -31,7 +38,8 @@ impl Estimator {
         }
     }
 
-    /// Estimate the upload cost of a chosen file
+    /// Estimate the upload cost of a chosen file.
+    /// The chunks already stored on the network are left out of the estimate, as they are not paid for.
     pub async fn estimate_cost(
         mut self,
         path: PathBuf,
@@ -48,7 +56,12 @@ impl Estimator {
             .balance()
             .as_nano();
 
-        for (chunk_address, _location) in self.chunk_manager.get_chunks() {
+        let chunks = self.chunk_manager.get_chunks();
+        let chunks_count = chunks.len();
+        let missing_chunks = self.missing_chunks(chunks).await;
+        let already_stored_count = chunks_count - missing_chunks.len();
+
+        for chunk_address in missing_chunks {
             let c = self.files_api.clone();
 
             tokio::spawn(async move {
@@ -70,6 +83,7 @@ impl Estimator {
         let total = balance.saturating_sub(estimate);
 
         println!("**************************************");
+        println!("Chunks already stored on the network: {already_stored_count} of {chunks_count}");
         println!("Your current balance: {}", NanoTokens::from(balance));
         println!("Transfer cost estimate: {}", NanoTokens::from(estimate));
         println!(
@@ -80,4 +94,30 @@ impl Estimator {
 
         Ok(())
     }
+
+    /// Checks which chunks are already stored on the network, in batches.
+    /// Returns the names of the chunks that are not, the ones that could not be read being counted as such.
+    async fn missing_chunks(&self, chunks: Vec<(XorName, PathBuf)>) -> Vec<XorName> {
+        futures::stream::iter(chunks)
+            .map(|(xorname, path)| {
+                let client = self.files_api.client().clone();
+                async move {
+                    let exists = match std::fs::read(&path) {
+                        Ok(bytes) => client
+                            .quickly_check_if_chunk_stored(&Chunk::new(Bytes::from(bytes)))
+                            .await
+                            .is_ok(),
+                        Err(err) => {
+                            warn!("Could not read chunk {xorname:?} at {path:?}: {err:?}");
+                            false
+                        }
+                    };
+                    (xorname, exists)
+                }
+            })
+            .buffer_unordered(BATCH_SIZE)
+            .filter_map(|(xorname, exists)| async move { (!exists).then_some(xorname) })
+            .collect()
+            .await
+    }
 }
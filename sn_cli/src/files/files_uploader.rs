@@ -442,6 +442,16 @@ impl FilesUploadStatusNotifier for StdOutPrinter {
             the leftover {} chunks in {elapsed}",
             upload_sum.skipped_count, upload_sum.uploaded_count,
         );
+        if upload_sum.already_stored_count > 0 {
+            println!(
+                "{} chunks were found on the network before paying for them, and skipped",
+                upload_sum.already_stored_count
+            );
+            info!(
+                "{} chunks were found on the network before paying for them, and skipped",
+                upload_sum.already_stored_count
+            );
+        }
         println!("**************************************");
         println!("*          Payment Details           *");
         println!("**************************************");
@@ -6,15 +6,88 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::error::Result;
-use sn_transfers::{get_faucet_data_dir, HotWallet};
+use sn_transfers::{get_faucet_data_dir, HotWallet, MainSecretKey, WalletError, WALLET_DIR_NAME};
 
 pub mod user_secret;
 
 const DEFAULT_WALLET_DERIVIATION_PASSPHRASE: &str = "default";
 
+/// Dir holding the wallets of the accounts derived from the mnemonic, other than the default one.
+const ACCOUNTS_DIR_NAME: &str = "accounts";
+
+/// The root dir of the wallet of an account derived from the mnemonic stored at `root_dir`.
+/// The account 0 is the default wallet, at the `root_dir` itself.
+pub fn account_root_dir(root_dir: &Path, account: u32) -> PathBuf {
+    if account == 0 {
+        root_dir.to_path_buf()
+    } else {
+        root_dir.join(ACCOUNTS_DIR_NAME).join(account.to_string())
+    }
+}
+
+/// The indexes of the accounts with a wallet under `root_dir`, in ascending order.
+pub fn list_accounts(root_dir: &Path) -> Result<Vec<u32>> {
+    let mut accounts = vec![];
+    if root_dir.join(WALLET_DIR_NAME).is_dir() {
+        accounts.push(0);
+    }
+
+    let accounts_dir = root_dir.join(ACCOUNTS_DIR_NAME);
+    if accounts_dir.is_dir() {
+        for entry in std::fs::read_dir(accounts_dir)? {
+            let entry = entry?;
+            let account = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u32>().ok());
+            match account {
+                Some(account) if entry.path().join(WALLET_DIR_NAME).is_dir() => {
+                    accounts.push(account)
+                }
+                _ => warn!("Ignoring unexpected entry in the accounts dir: {entry:?}"),
+            }
+        }
+    }
+    accounts.sort_unstable();
+    Ok(accounts)
+}
+
+/// Derive the main key of an account from the mnemonic stored at `root_dir`.
+pub fn account_wallet_key(
+    root_dir: &Path,
+    account: u32,
+    derivation_passphrase: Option<&str>,
+) -> Result<MainSecretKey> {
+    let mnemonic = user_secret::read_mnemonic_from_disk(root_dir)?;
+    let passphrase = derivation_passphrase.unwrap_or(DEFAULT_WALLET_DERIVIATION_PASSPHRASE);
+    user_secret::indexed_account_wallet_secret_key(mnemonic, passphrase, account)
+}
+
+/// Derive the wallet of an account from the mnemonic stored at `root_dir`, and store it under
+/// the root dir of that account, its main key encrypted with the password if any.
+/// This will overwrite any existing wallet of the account.
+pub fn create_account_wallet(
+    root_dir: &Path,
+    account: u32,
+    derivation_passphrase: Option<&str>,
+    password: Option<&str>,
+) -> Result<HotWallet> {
+    let key = account_wallet_key(root_dir, account, derivation_passphrase)?;
+
+    let account_root_dir = account_root_dir(root_dir, account);
+    info!("Creating the wallet of account {account} at {account_root_dir:?}");
+    let wallet = match password {
+        Some(password) => {
+            HotWallet::create_from_key_with_password(&account_root_dir, key, password)?
+        }
+        None => HotWallet::create_from_key(&account_root_dir, key)?,
+    };
+    Ok(wallet)
+}
+
 /// Load a account from disk, with wallet, or create a new one using the mnemonic system
 pub fn load_account_wallet_or_create_with_mnemonic(
     root_dir: &Path,
@@ -23,6 +96,8 @@ pub fn load_account_wallet_or_create_with_mnemonic(
     let wallet = HotWallet::load_from(root_dir);
     match wallet {
         Ok(wallet) => Ok(wallet),
+        // the wallet exists, it just has to be unlocked with its password first
+        Err(error @ WalletError::EncryptedMainSecretKey(_)) => Err(error.into()),
         Err(error) => {
             warn!("Issue loading wallet, creating a new one: {error}");
             println!("Issue loading wallet from {root_dir:?}");
@@ -58,3 +133,35 @@ pub fn create_faucet_account_and_wallet() -> HotWallet {
     load_account_wallet_or_create_with_mnemonic(&root_dir, None)
         .expect("Faucet wallet shall be created successfully.")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+    use rand::Rng;
+
+    #[test]
+    fn accounts_are_derived_from_the_same_mnemonic() -> Result<()> {
+        let root_dir =
+            std::env::temp_dir().join(format!("accounts_{}", rand::thread_rng().gen::<u64>()));
+        std::fs::create_dir_all(&root_dir)?;
+        let mnemonic = user_secret::random_eip2333_mnemonic()?;
+        user_secret::write_mnemonic_to_disk(&root_dir, &mnemonic)?;
+
+        let default_wallet = load_account_wallet_or_create_with_mnemonic(&root_dir, None)?;
+        let account_wallet = create_account_wallet(&root_dir, 2, None, None)?;
+        assert_ne!(default_wallet.address(), account_wallet.address());
+        assert_eq!(
+            create_account_wallet(&root_dir, 0, None, None)?.address(),
+            default_wallet.address()
+        );
+        assert_eq!(
+            HotWallet::load_from(&account_root_dir(&root_dir, 2))?.address(),
+            account_wallet.address()
+        );
+        assert_eq!(list_accounts(&root_dir)?, vec![0, 2]);
+
+        std::fs::remove_dir_all(root_dir)?;
+        Ok(())
+    }
+}
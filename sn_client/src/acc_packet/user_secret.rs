@@ -20,7 +20,8 @@ const MNEMONIC_FILENAME: &str = "account_secret";
 
 const ACCOUNT_ROOT_XORNAME_DERIVATION: &str = "m/1/0";
 
-const ACCOUNT_WALLET_DERIVATION: &str = "m/2/0";
+/// The wallet of the account `n` is derived at `m/2/n`, the default wallet being the account 0.
+const ACCOUNT_WALLET_DERIVATION_PREFIX: &str = "m/2";
 
 pub fn random_eip2333_mnemonic() -> Result<bip39::Mnemonic> {
     let mut entropy = [1u8; 32];
@@ -35,12 +36,23 @@ pub fn random_eip2333_mnemonic() -> Result<bip39::Mnemonic> {
 pub fn account_wallet_secret_key(
     mnemonic: bip39::Mnemonic,
     passphrase: &str,
+) -> Result<MainSecretKey> {
+    indexed_account_wallet_secret_key(mnemonic, passphrase, 0)
+}
+
+/// Derive the wallet secret key of the account of the given index from the mnemonic.
+/// The account 0 is the default wallet of the mnemonic.
+pub fn indexed_account_wallet_secret_key(
+    mnemonic: bip39::Mnemonic,
+    passphrase: &str,
+    account: u32,
 ) -> Result<MainSecretKey> {
     let seed = mnemonic.to_seed(passphrase);
 
     let root_sk =
         eip2333::derive_master_sk(&seed).map_err(|_err| Error::InvalidMnemonicSeedPhrase)?;
-    let derived_key = eip2333::derive_child_sk(root_sk, ACCOUNT_WALLET_DERIVATION);
+    let derivation_path = format!("{ACCOUNT_WALLET_DERIVATION_PREFIX}/{account}");
+    let derived_key = eip2333::derive_child_sk(root_sk, &derivation_path);
     let key_bytes = derived_key.serialize();
     let sk = SecretKey::from_bytes(key_bytes.into()).map_err(|_err| Error::InvalidKeyBytes)?;
     Ok(MainSecretKey::new(sk))
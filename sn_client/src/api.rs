@@ -8,10 +8,11 @@
 
 use super::{
     error::{Error, Result},
+    record_cache::{RecordCache, RecordCacheConfig},
     Client, ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver, ClientRegister,
     WalletClient,
 };
-use bls::{PublicKey, SecretKey, Signature};
+use bls::{Ciphertext, PublicKey, SecretKey, Signature};
 use libp2p::{
     identity::Keypair,
     kad::{Quorum, Record},
@@ -125,6 +126,7 @@ impl Client {
             network: network.clone(),
             events_broadcaster,
             signer: Arc::new(signer),
+            record_cache: None,
         };
 
         // subscribe to our events channel first, so we don't have intermittent
@@ -244,6 +246,28 @@ impl Client {
         Ok(client)
     }
 
+    /// Caches the immutable records fetched by the client, in memory and optionally on disk.
+    ///
+    /// # Arguments
+    /// * 'config' - [RecordCacheConfig]
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sn_client::{Client, Error, RecordCacheConfig};
+    /// use bls::SecretKey;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(),Error>{
+    /// let client = Client::new(SecretKey::random(), None, None, None)
+    ///     .await?
+    ///     .with_record_cache(RecordCacheConfig::default());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_record_cache(mut self, config: RecordCacheConfig) -> Self {
+        self.record_cache = Some(Arc::new(RecordCache::new(config)));
+        self
+    }
+
     fn handle_network_event(&mut self, event: NetworkEvent, peers_added: &mut usize) -> Result<()> {
         match event {
             NetworkEvent::PeerAdded(peer_id, _connected_peer) => {
@@ -522,6 +546,21 @@ impl Client {
         ClientRegister::retrieve(self.clone(), address).await
     }
 
+    /// Retrieve an encrypted Register from the Network, with its encryption key set to read and
+    /// write its entries: the key shared by the owner if provided, else the key derived from
+    /// ours, which only works for the owner of the Register.
+    pub async fn get_encrypted_register(
+        &self,
+        address: RegisterAddress,
+        shared_key: Option<&Ciphertext>,
+    ) -> Result<ClientRegister> {
+        let register = self.get_register(address).await?;
+        match shared_key {
+            Some(shared_key) => register.with_shared_encryption_key(shared_key),
+            None => register.with_owner_encryption(),
+        }
+    }
+
     /// Create a new Register on the Network.
     /// Tops up payments and retries if necessary and verification failed
     ///
@@ -724,6 +763,22 @@ impl Client {
         info!("Getting chunk: {address:?}");
         let key = NetworkAddress::from_chunk_address(address).to_record_key();
 
+        // chunks are immutable, the cached copy never gets stale, but could get corrupted
+        if let Some(cache) = &self.record_cache {
+            if let Some(record) = cache.get(&key, None) {
+                match try_deserialize_record::<Chunk>(&record) {
+                    Ok(chunk) if XorName::from_content(chunk.value()) == *address.xorname() => {
+                        debug!("Got chunk {address:?} from the record cache");
+                        return Ok(chunk);
+                    }
+                    _ => {
+                        warn!("Dropping cached chunk {address:?}, its content does not match its address");
+                        cache.remove(&key);
+                    }
+                }
+            }
+        }
+
         let expected_holders = if show_holders {
             let result: HashSet<_> = self
                 .network
@@ -747,6 +802,13 @@ impl Client {
         let header = RecordHeader::from_record(&record)?;
         if let RecordKind::Chunk = header.kind {
             let chunk: Chunk = try_deserialize_record(&record)?;
+            if let Some(cache) = &self.record_cache {
+                if XorName::from_content(chunk.value()) == *address.xorname() {
+                    cache.put(&record);
+                } else {
+                    warn!("Not caching chunk {address:?}, its content does not match its address");
+                }
+            }
             Ok(chunk)
         } else {
             Err(NetworkError::RecordKindMismatch(RecordKind::Chunk).into())
@@ -780,6 +842,30 @@ impl Client {
         Ok(())
     }
 
+    /// Quickly checks if a `Chunk` is already stored by the nodes close to it on the network,
+    /// by asking them for a proof of its existence without any retries.
+    ///
+    /// To be used before paying for a chunk, to skip the ones that are already stored.
+    pub async fn quickly_check_if_chunk_stored(&self, chunk: &Chunk) -> Result<()> {
+        let address = chunk.network_address();
+        info!("Quickly checking for existing chunk: {address:?}");
+        let random_nonce = thread_rng().gen::<u64>();
+        let record_value = try_serialize_record(&chunk, RecordKind::Chunk)?;
+        let expected_proof = ChunkProof::new(record_value.as_ref(), random_nonce);
+
+        self.network
+            .verify_chunk_existence(
+                address,
+                random_nonce,
+                expected_proof,
+                Quorum::N(NonZeroUsize::new(2).ok_or(Error::NonZeroUsizeWasInitialisedAsZero)?),
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
     /// Verify if a `Register` is stored by expected nodes on the network.
     ///
     /// # Arguments
@@ -941,6 +1027,7 @@ impl Client {
                 target_record: None,
                 expected_holders: Default::default(),
             },
+            true,
         )
         .await
     }
@@ -957,6 +1044,7 @@ impl Client {
                 target_record: None,
                 expected_holders: Default::default(),
             },
+            false,
         )
         .await
     }
@@ -972,6 +1060,7 @@ impl Client {
                 target_record: None,
                 expected_holders: Default::default(),
             },
+            false,
         )
         .await
     }
@@ -983,12 +1072,20 @@ impl Client {
         self.peek_a_spend(genesis_addr).await.is_ok()
     }
 
+    /// Spends fetched with a `Majority` quorum are confirmed, thus can be cached when `use_cache`.
     async fn try_fetch_spend_from_network(
         &self,
         address: SpendAddress,
         get_cfg: GetRecordCfg,
+        use_cache: bool,
     ) -> Result<SignedSpend> {
         let key = NetworkAddress::from_spend_address(address).to_record_key();
+        let cache = self.record_cache.as_ref().filter(|_| use_cache);
+
+        if let Some(record) = cache.and_then(|cache| cache.get(&key, None)) {
+            debug!("Got spend at {address:?} from the record cache");
+            return verify_spend_record(&address, &record);
+        }
 
         info!(
             "Getting spend at {address:?} with record_key {:?}",
@@ -1003,31 +1100,11 @@ impl Client {
             PrettyPrintRecordKey::from(&record.key)
         );
 
-        let signed_spend = get_signed_spend_from_record(&address, &record)?;
-
-        // check addr
-        let spend_addr = SpendAddress::from_unique_pubkey(signed_spend.unique_pubkey());
-        if address != spend_addr {
-            let s = format!("Spend got from the Network at {address:?} contains different spend address: {spend_addr:?}");
-            warn!("{s}");
-            return Err(Error::Transfer(TransferError::InvalidSpendValue(
-                *signed_spend.unique_pubkey(),
-            )));
-        }
-
-        // check spend
-        match signed_spend.verify(signed_spend.spent_tx_hash()) {
-            Ok(()) => {
-                trace!("Verified signed spend got from network for {address:?}");
-                Ok(signed_spend.clone())
-            }
-            Err(err) => {
-                warn!("Invalid signed spend got from network for {address:?}: {err:?}.");
-                Err(Error::CouldNotVerifyTransfer(format!(
-                    "Verification failed for spent at {address:?} with error {err:?}"
-                )))
-            }
+        let signed_spend = verify_spend_record(&address, &record)?;
+        if let Some(cache) = cache {
+            cache.put(&record);
         }
+        Ok(signed_spend)
     }
 
     /// This function is used to receive a Vector of CashNoteRedemptions and turn them back into spendable CashNotes.
@@ -1077,7 +1154,36 @@ impl Client {
     }
 }
 
-fn get_register_from_record(record: &Record) -> Result<SignedRegister> {
+/// Checks the record holds a single validly signed spend for the address.
+fn verify_spend_record(address: &SpendAddress, record: &Record) -> Result<SignedSpend> {
+    let signed_spend = get_signed_spend_from_record(address, record)?;
+
+    // check addr
+    let spend_addr = SpendAddress::from_unique_pubkey(signed_spend.unique_pubkey());
+    if *address != spend_addr {
+        let s = format!("Spend got from the Network at {address:?} contains different spend address: {spend_addr:?}");
+        warn!("{s}");
+        return Err(Error::Transfer(TransferError::InvalidSpendValue(
+            *signed_spend.unique_pubkey(),
+        )));
+    }
+
+    // check spend
+    match signed_spend.verify(signed_spend.spent_tx_hash()) {
+        Ok(()) => {
+            trace!("Verified signed spend got from network for {address:?}");
+            Ok(signed_spend)
+        }
+        Err(err) => {
+            warn!("Invalid signed spend got from network for {address:?}: {err:?}.");
+            Err(Error::CouldNotVerifyTransfer(format!(
+                "Verification failed for spent at {address:?} with error {err:?}"
+            )))
+        }
+    }
+}
+
+pub(crate) fn get_register_from_record(record: &Record) -> Result<SignedRegister> {
     let header = RecordHeader::from_record(record)?;
 
     if let RecordKind::Register = header.kind {
@@ -11,7 +11,7 @@ use crate::{Client, Error, SpendDag};
 use futures::{future::join_all, StreamExt};
 use sn_networking::{GetRecordError, NetworkError};
 use sn_transfers::{
-    SignedSpend, SpendAddress, SpendReason, WalletError, WalletResult,
+    CashNote, MainSecretKey, SignedSpend, SpendAddress, SpendReason, WalletError, WalletResult,
     DEFAULT_NETWORK_ROYALTIES_PK, GENESIS_SPEND_UNIQUE_KEY, NETWORK_ROYALTIES_PK,
 };
 use std::{
@@ -133,6 +133,24 @@ impl Client {
         Ok(dag)
     }
 
+    /// Crawls the entire SpendDag from Genesis to find the unspent CashNotes owned by `owner`,
+    /// to restore a wallet which was lost. The key of the owner is needed to find its change,
+    /// see [`sn_transfers::DerivationIndex::for_change`].
+    ///
+    /// Only the change outputs can be found, the derivation indexes of the outputs paid by others
+    /// are random and only known from the CashNotes they sent
+    pub async fn restore_cash_notes(&self, owner: &MainSecretKey) -> WalletResult<Vec<CashNote>> {
+        let genesis_addr = SpendAddress::from_unique_pubkey(&GENESIS_SPEND_UNIQUE_KEY);
+        let dag = self.spend_dag_build_from(genesis_addr, None, false).await?;
+        let cash_notes = dag.unspent_change_cash_notes(owner);
+        info!(
+            "Found {} unspent CashNotes owned by {:?} in the SpendDag",
+            cash_notes.len(),
+            owner.main_pubkey()
+        );
+        Ok(cash_notes)
+    }
+
     /// Get spends from a set of given SpendAddresses
     /// Recursivly fetching till reached frontline of the DAG tree.
     /// Return with UTXOs for re-attempt (with insertion time stamp)
@@ -12,7 +12,8 @@ use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
 use sn_transfers::{
-    is_genesis_spend, CashNoteRedemption, Hash, NanoTokens, SignedSpend, SpendAddress,
+    is_genesis_spend, CashNote, CashNoteRedemption, DerivationIndex, Hash, MainSecretKey,
+    NanoTokens, SignedSpend, SpendAddress, Transaction, UniquePubkey,
 };
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -399,6 +400,79 @@ impl SpendDag {
         &self.faults
     }
 
+    /// Get the unspent CashNotes of the DAG owned by `owner`, out of the change outputs
+    /// of the transactions, whose derivation index can be found again from the transaction
+    /// inputs with the key of the owner. See [`DerivationIndex::for_change`].
+    pub fn unspent_change_cash_notes(&self, owner: &MainSecretKey) -> Vec<CashNote> {
+        let mut cash_notes = Vec::new();
+        for (tx, unique_pubkey, derivation_index) in self.change_outputs(owner) {
+            let addr = SpendAddress::from_unique_pubkey(&unique_pubkey);
+            if !matches!(
+                self.get_spend(&addr),
+                SpendDagGet::Utxo | SpendDagGet::SpendNotFound
+            ) {
+                debug!("Change output at {addr:?} is already spent");
+                continue;
+            }
+
+            // the CashNote is only valid along with the spends of all the inputs of its tx
+            let parent_spends: Option<BTreeSet<SignedSpend>> = tx
+                .inputs
+                .iter()
+                .map(|input| {
+                    match self.get_spend(&SpendAddress::from_unique_pubkey(&input.unique_pubkey)) {
+                        SpendDagGet::Spend(spend) if &spend.spend.spent_tx == tx => Some(*spend),
+                        _ => None,
+                    }
+                })
+                .collect();
+            let Some(parent_spends) = parent_spends else {
+                warn!("Ignoring change output at {addr:?} as the spends of its tx are not all valid in the DAG");
+                continue;
+            };
+
+            cash_notes.push(CashNote {
+                unique_pubkey,
+                parent_tx: tx.clone(),
+                parent_spends,
+                main_pubkey: owner.main_pubkey(),
+                derivation_index,
+            });
+        }
+        cash_notes
+    }
+
+    /// Whether any transaction of the DAG has a change output going back to `owner`, spent or not,
+    /// which tells the key was used to make transfers.
+    pub fn has_change_outputs(&self, owner: &MainSecretKey) -> bool {
+        !self.change_outputs(owner).is_empty()
+    }
+
+    // Private helper: the change outputs going back to `owner`, with the tx they are from
+    fn change_outputs(
+        &self,
+        owner: &MainSecretKey,
+    ) -> Vec<(&Transaction, UniquePubkey, DerivationIndex)> {
+        let main_pubkey = owner.main_pubkey();
+        let txs: BTreeSet<&Transaction> = self
+            .all_spends()
+            .into_iter()
+            .map(|spend| &spend.spend.spent_tx)
+            .collect();
+
+        txs.into_iter()
+            .filter_map(|tx| {
+                let derivation_index =
+                    DerivationIndex::for_change(owner, tx.inputs.iter().map(|i| &i.unique_pubkey));
+                let unique_pubkey = main_pubkey.new_unique_pubkey(&derivation_index);
+                tx.outputs
+                    .iter()
+                    .any(|o| o.unique_pubkey == unique_pubkey)
+                    .then_some((tx, unique_pubkey, derivation_index))
+            })
+            .collect()
+    }
+
     /// Get all royalties from the DAG
     pub fn all_royalties(&self) -> crate::Result<Vec<CashNoteRedemption>> {
         let spends = self.all_spends();
@@ -13,7 +13,7 @@ use std::collections::BTreeSet;
 use setup::MockNetwork;
 
 use eyre::Result;
-use sn_transfers::SpendAddress;
+use sn_transfers::{MainSecretKey, SpendAddress};
 
 use crate::{SpendDag, SpendFault};
 
@@ -46,6 +46,41 @@ fn test_spend_dag_verify_valid_simple() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_spend_dag_finds_unspent_change() -> Result<()> {
+    let mut net = MockNetwork::genesis()?;
+    let genesis = net.genesis_spend;
+
+    let owner1 = net.new_pk_with_balance(100)?;
+    let owner2 = net.new_pk_with_balance(0)?;
+    let owner3 = net.new_pk_with_balance(0)?;
+
+    net.send(&owner1, &owner2, 30)?;
+    net.send(&owner1, &owner3, 20)?;
+
+    let mut dag = SpendDag::new(genesis);
+    for spend in net.spends.iter() {
+        dag.insert(spend.address(), spend.clone());
+    }
+
+    // only the change of the last transfer is still unspent
+    let owner1_cash_notes = &net.wallets.get(&owner1).expect("owner1 wallet to exist").cn;
+    let owner1_sk = &net.wallets.get(&owner1).expect("owner1 wallet to exist").sk;
+    let found = dag.unspent_change_cash_notes(owner1_sk);
+    assert_eq!(&found, owner1_cash_notes);
+    assert_eq!(found[0].value()?.as_nano(), 50);
+
+    // the outputs paid by others have random derivation indexes
+    let owner2_sk = &net.wallets.get(&owner2).expect("owner2 wallet to exist").sk;
+    assert!(dag.unspent_change_cash_notes(owner2_sk).is_empty());
+
+    // the key of owner1 was used to make transfers, and the change can't be told apart without it
+    assert!(dag.has_change_outputs(owner1_sk));
+    assert!(!dag.has_change_outputs(owner2_sk));
+    assert!(!dag.has_change_outputs(&MainSecretKey::random()));
+    Ok(())
+}
+
 #[test]
 fn test_spend_dag_double_spend_poisonning() -> Result<()> {
     let mut net = MockNetwork::genesis()?;
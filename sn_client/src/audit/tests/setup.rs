@@ -116,10 +116,10 @@ impl MockNetwork {
             to_wallet.sk.main_pubkey(),
             DerivationIndex::random(&mut rng),
         )];
-        let transfer = OfflineTransfer::new(
+        let transfer = OfflineTransfer::new_with_restorable_change(
             cash_notes_with_keys,
             recipient,
-            from_wallet.sk.main_pubkey(),
+            &from_wallet.sk,
             SpendReason::default(),
         )
         .map_err(|e| eyre!("failed to create transfer: {}", e))?;
@@ -49,6 +49,18 @@ pub enum Error {
     #[error("Decrypting a Folder's item failed: {0}")]
     FolderEntryDecryption(EntryHash),
 
+    #[error("Encrypting a Register's entry failed")]
+    RegisterEntryEncryption,
+
+    #[error("Decrypting a Register's entry failed: {0}")]
+    RegisterEntryDecryption(EntryHash),
+
+    #[error("The Register has no encryption key set")]
+    RegisterEncryptionKeyMissing,
+
+    #[error("The shared Register encryption key could not be decrypted with our key")]
+    InvalidRegisterEncryptionKey,
+
     #[error("SelfEncryption Error {0}.")]
     SelfEncryptionIO(#[from] self_encryption::Error),
 
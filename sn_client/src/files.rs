@@ -6,7 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub(crate) mod audit;
 pub(crate) mod download;
+mod journal;
+pub(crate) mod reader;
 
 use crate::{
     acc_packet::load_account_wallet_or_create_with_mnemonic, chunks::Error as ChunksError,
@@ -0,0 +1,71 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::download::FilesDownload;
+use crate::{error::Result, Client, FilesApi};
+use libp2p::PeerId;
+use rand::{thread_rng, Rng};
+use sn_protocol::{
+    messages::ChunkProof,
+    storage::{try_serialize_record, Chunk, ChunkAddress, RecordKind},
+};
+
+/// The outcome of asking the close group of a chunk for proofs of holding it.
+#[derive(Clone, Debug)]
+pub struct ChunkAudit {
+    pub address: ChunkAddress,
+    /// The peers which proved to hold the chunk
+    pub holders: Vec<PeerId>,
+    /// The peers of the close group which failed to prove holding the chunk
+    pub failures: Vec<PeerId>,
+}
+
+impl ChunkAudit {
+    /// Whether none of the close group holds the chunk anymore.
+    pub fn is_missing(&self) -> bool {
+        self.holders.is_empty()
+    }
+}
+
+impl Client {
+    /// Audit a `Chunk` by asking each peer of its close group for a nonce-based proof of holding it.
+    ///
+    /// The content of the chunk is needed to check the proofs against.
+    pub async fn audit_chunk(&self, chunk: &Chunk) -> Result<ChunkAudit> {
+        let address = chunk.network_address();
+        info!("Auditing chunk: {address:?}");
+        let random_nonce = thread_rng().gen::<u64>();
+        let record_value = try_serialize_record(&chunk, RecordKind::Chunk)?;
+        let expected_proof = ChunkProof::new(record_value.as_ref(), random_nonce);
+
+        let (holders, failures) = self
+            .network
+            .get_chunk_existence_proofs(address, random_nonce, expected_proof)
+            .await?;
+
+        Ok(ChunkAudit {
+            address: *chunk.address(),
+            holders,
+            failures,
+        })
+    }
+}
+
+impl FilesApi {
+    /// The addresses of the chunks holding the content of a file, from its data map chunk.
+    pub async fn file_chunk_addresses(&self, data_map_chunk: Chunk) -> Result<Vec<ChunkAddress>> {
+        let data_map = FilesDownload::new(self.clone())
+            .unpack_chunk(data_map_chunk)
+            .await?;
+        Ok(data_map
+            .infos()
+            .iter()
+            .map(|info| ChunkAddress::new(info.dst_hash))
+            .collect())
+    }
+}
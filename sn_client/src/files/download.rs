@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::{journal::DownloadJournal, reader::DataMapReader};
 use crate::{
     chunks::{DataMapLevel, Error as ChunksError},
     error::{Error as ClientError, Result},
@@ -13,7 +14,6 @@ use crate::{
 };
 use bytes::Bytes;
 use futures::StreamExt;
-use itertools::Itertools;
 use self_encryption::{decrypt_full_set, DataMap, EncryptedChunk, StreamSelfDecryptor};
 use sn_networking::target_arch::Instant;
 use sn_protocol::storage::{Chunk, ChunkAddress, RetryStrategy};
@@ -33,6 +33,11 @@ pub enum FilesDownloadEvent {
     /// very large.
     /// Note: This count currently is not accurate. It does not take into account how we fetch the initial head chunk.
     DatamapCount(usize),
+    /// The download is resumed from its journal, with this number of chunks already fetched by
+    /// a previous attempt. They are read from the disk and not reported as `Downloaded`.
+    Resumed(usize),
+    /// The download has completed and its journal has been removed from the disk.
+    JournalRemoved,
     /// The download process has terminated with an error.
     Error,
 }
@@ -44,6 +49,24 @@ enum DownloadReturnType {
     WrittenToFileSystem,
 }
 
+// Internally used to either decrypt the chunks to a file as they come, or to collect them.
+enum DownloadKind {
+    FileSystem(StreamSelfDecryptor),
+    Memory(Vec<EncryptedChunk>),
+}
+
+impl DownloadKind {
+    fn process(&mut self, encrypted_chunk: EncryptedChunk) -> Result<()> {
+        match self {
+            DownloadKind::FileSystem(decryptor) => {
+                let _ = decryptor.next_encrypted(encrypted_chunk)?;
+            }
+            DownloadKind::Memory(collector) => collector.push(encrypted_chunk),
+        }
+        Ok(())
+    }
+}
+
 /// `FilesDownload` provides functionality for downloading chunks with support for retries and queuing.
 /// This struct is not cloneable. To create a new instance with default configuration, use the `new` function.
 /// To modify the configuration, use the provided setter methods (`set_...` functions).
@@ -52,6 +75,7 @@ pub struct FilesDownload {
     batch_size: usize,
     show_holders: bool,
     retry_strategy: RetryStrategy,
+    resumable: bool,
     // API
     api: FilesApi,
     // Events
@@ -67,6 +91,7 @@ impl FilesDownload {
             batch_size: BATCH_SIZE,
             show_holders: false,
             retry_strategy: RetryStrategy::Quick,
+            resumable: false,
             api: files_api,
             event_sender: None,
             logged_event_sender_absence: false,
@@ -97,6 +122,16 @@ impl FilesDownload {
         self
     }
 
+    /// Sets the option to keep a journal of the chunks fetched when downloading to a path, for an
+    /// interrupted download to resume where it stopped when started again with the same path.
+    /// The journal is kept in a hidden directory next to the file and removed once it is downloaded.
+    ///
+    /// By default, this option is set to false.
+    pub fn set_resumable(mut self, resumable: bool) -> Self {
+        self.resumable = resumable;
+        self
+    }
+
     /// Returns a receiver for file download events.
     /// This method is optional and the download process can be performed without it.
     pub fn get_events(&mut self) -> mpsc::Receiver<FilesDownloadEvent> {
@@ -120,11 +155,25 @@ impl FilesDownload {
         address: ChunkAddress,
         position: usize,
         length: usize,
+    ) -> Result<Bytes> {
+        self.read_range(address, position, length).await
+    }
+
+    /// Read `length` bytes of a file starting at `offset`, without downloading the whole file.
+    /// Only the encrypted chunks overlapping the range are fetched from the network.
+    ///
+    /// Reading past the end of the file returns the bytes up to the end of it.
+    /// To stream a large file instead, use `open_reader`.
+    pub async fn read_range(
+        &mut self,
+        data_map_addr: ChunkAddress,
+        offset: usize,
+        length: usize,
     ) -> Result<Bytes> {
         // clean up the trackers/stats
         self.logged_event_sender_absence = false;
 
-        let result = self.download_from_inner(address, position, length).await;
+        let result = self.read_range_inner(data_map_addr, offset, length).await;
 
         // send an event indicating that the download process completed with an error
         if result.is_err() {
@@ -138,59 +187,92 @@ impl FilesDownload {
         result
     }
 
+    /// Read `length` bytes of a file starting at `position`, without sending any event.
+    #[deprecated(note = "Use `read_range` instead")]
     pub async fn download_from_inner(
         &mut self,
         address: ChunkAddress,
         position: usize,
         length: usize,
     ) -> Result<Bytes> {
-        debug!("Reading {length} bytes at: {address:?}, starting from position: {position}");
+        self.read_range_inner(address, position, length).await
+    }
+
+    async fn read_range_inner(
+        &mut self,
+        data_map_addr: ChunkAddress,
+        offset: usize,
+        length: usize,
+    ) -> Result<Bytes> {
+        debug!("Reading {length} bytes at: {data_map_addr:?}, starting from offset: {offset}");
         let chunk = self
             .api
             .client
-            .get_chunk(address, false, Some(self.retry_strategy))
+            .get_chunk(data_map_addr, false, Some(self.retry_strategy))
             .await?;
 
-        // First try to deserialize a LargeFile, if it works, we go and seek it.
-        // If an error occurs, we consider it to be a SmallFile.
-        if let Ok(data_map) = self.unpack_chunk(chunk.clone()).await {
-            let info = self_encryption::seek_info(data_map.file_size(), position, length);
-            let range = &info.index_range;
-            let all_infos = data_map.infos();
-
-            let to_download = (range.start..range.end + 1)
-                .clone()
-                .map(|i| all_infos[i].clone())
-                .collect_vec();
-            let to_download = DataMap::new(to_download);
-
-            // not written to file and return the encrypted chunks
-            if let DownloadReturnType::EncryptedChunks(encrypted_chunks) =
-                self.read(to_download, None, true, false).await?
-            {
-                let bytes = self_encryption::decrypt_range(
-                    &data_map,
-                    &encrypted_chunks,
-                    info.relative_pos,
-                    length,
-                )
-                .map_err(ChunksError::SelfEncryption)?;
-                return Ok(bytes);
-            } else {
-                error!("IncorrectDownloadOption: expected to get the encrypted chunks back");
-                return Err(ClientError::IncorrectDownloadOption);
+        match self.unpack_chunk(chunk.clone()).await {
+            Ok(data_map) => self.read_data_map_range(&data_map, offset, length).await,
+            Err(ClientError::Chunks(ChunksError::Deserialisation(_))) => {
+                // Only in case of a deserialisation error,
+                // shall consider the head chunk to be a SmallFile.
+                let mut bytes = chunk.value().clone();
+                if offset >= bytes.len() {
+                    return Ok(Bytes::new());
+                }
+                let _ = bytes.split_to(offset);
+                bytes.truncate(length);
+
+                Ok(bytes)
+            }
+            Err(err) => {
+                error!("Encounter error when unpack head_chunk {data_map_addr:?} : {err:?}");
+                Err(err)
             }
         }
+    }
 
-        // The error above is ignored to avoid leaking the storage format detail of SmallFiles and LargeFiles.
-        // The basic idea is that we're trying to deserialize as one, and then the other.
-        // The cost of it is that some errors will not be seen without a refactor.
-        let mut bytes = chunk.value().clone();
+    /// Read `length` bytes of the file described by the `DataMap`, starting at `offset`.
+    /// Only the encrypted chunks overlapping the range are fetched from the network.
+    pub async fn read_data_map_range(
+        &mut self,
+        data_map: &DataMap,
+        offset: usize,
+        length: usize,
+    ) -> Result<Bytes> {
+        let Some((to_download, relative_pos, length)) = seek_range(data_map, offset, length) else {
+            return Ok(Bytes::new());
+        };
 
-        let _ = bytes.split_to(position);
-        bytes.truncate(length);
+        // not written to file and return the encrypted chunks
+        if let DownloadReturnType::EncryptedChunks(encrypted_chunks) =
+            self.read(to_download, None, true, false).await?
+        {
+            let bytes =
+                self_encryption::decrypt_range(data_map, &encrypted_chunks, relative_pos, length)
+                    .map_err(ChunksError::SelfEncryption)?;
+            Ok(bytes)
+        } else {
+            error!("IncorrectDownloadOption: expected to get the encrypted chunks back");
+            Err(ClientError::IncorrectDownloadOption)
+        }
+    }
 
-        Ok(bytes)
+    /// Open a reader streaming the file from the network, implementing `AsyncRead` and `AsyncSeek`.
+    /// The chunks are fetched as the reads reach them, using the retry strategy set on `FilesDownload`.
+    ///
+    /// Only files large enough to be self-encrypted have a `DataMap` to stream from, reading a
+    /// small file returns a deserialisation error.
+    pub async fn open_reader(&mut self, data_map_addr: ChunkAddress) -> Result<DataMapReader> {
+        let head_chunk = self
+            .api
+            .client
+            .get_chunk(data_map_addr, false, Some(self.retry_strategy))
+            .await?;
+        let data_map = self.unpack_chunk(head_chunk).await?;
+
+        Ok(DataMapReader::new(self.api.client.clone(), data_map)
+            .set_retry_strategy(self.retry_strategy))
     }
 
     /// Download a file from the network and get the decrypted bytes.
@@ -343,12 +425,13 @@ impl FilesDownload {
         return_encrypted_chunks: bool,
         we_are_downloading_a_datamap: bool,
     ) -> Result<DownloadReturnType> {
-        // used internally
-        enum DownloadKind {
-            FileSystem(StreamSelfDecryptor),
-            Memory(Vec<EncryptedChunk>),
-        }
-
+        // only the chunks of the file are journaled, the datamap chunks are not worth it
+        let mut journal = match &decrypted_file_path {
+            Some(path) if self.resumable && !we_are_downloading_a_datamap => {
+                Some(DownloadJournal::open(path, &data_map)?)
+            }
+            _ => None,
+        };
         let mut download_kind = {
             if let Some(path) = decrypted_file_path {
                 DownloadKind::FileSystem(StreamSelfDecryptor::decrypt_to_file(
@@ -370,6 +453,14 @@ impl FilesDownload {
             self.send_event(FilesDownloadEvent::DatamapCount(expected_count))
                 .await?;
         }
+        if let Some(journal) = journal.as_ref().filter(|j| j.fetched_count() > 0) {
+            info!(
+                "Resuming download with {} of {expected_count} chunks already fetched",
+                journal.fetched_count()
+            );
+            self.send_event(FilesDownloadEvent::Resumed(journal.fetched_count()))
+                .await?;
+        }
 
         let now = Instant::now();
 
@@ -381,7 +472,15 @@ impl FilesDownload {
             .first()
             .ok_or_else(|| ClientError::EmptyDataMap)?
             .index;
-        let mut stream = futures::stream::iter(chunk_infos.into_iter())
+        // the chunks read from the journal are not fetched again, unless they turn out corrupted
+        let to_fetch: Vec<_> = chunk_infos
+            .into_iter()
+            .filter(|info| match journal.as_ref() {
+                Some(journal) if journal.contains(info.index) => journal.get(info.index).is_none(),
+                _ => true,
+            })
+            .collect();
+        let mut stream = futures::stream::iter(to_fetch.into_iter())
             .map(|chunk_info| {
                 Self::get_chunk(
                     client_clone.clone(),
@@ -395,8 +494,19 @@ impl FilesDownload {
 
         let mut chunk_download_cache = HashMap::new();
 
+        // process the chunks the journal already holds before the first missing one
+        while let Some(encrypted_chunk) =
+            Self::take_ready_chunk(&mut chunk_download_cache, journal.as_ref(), current_index)
+        {
+            download_kind.process(encrypted_chunk)?;
+            current_index += 1;
+        }
+
         while let Some(result) = stream.next().await {
             let (chunk_address, index, encrypted_chunk) = result?;
+            if let Some(journal) = journal.as_mut() {
+                journal.record(*chunk_address.xorname(), &encrypted_chunk)?;
+            }
             // notify about the download
             self.send_event(FilesDownloadEvent::Downloaded(chunk_address))
                 .await?;
@@ -404,25 +514,17 @@ impl FilesDownload {
 
             // check if current_index is present in the cache before comparing the fetched index.
             // try to keep removing from the cache until we run out of sequential chunks to insert.
-            while let Some(encrypted_chunk) = chunk_download_cache.remove(&current_index) {
+            while let Some(encrypted_chunk) =
+                Self::take_ready_chunk(&mut chunk_download_cache, journal.as_ref(), current_index)
+            {
                 debug!("Got current_index {current_index:?} from the download cache. Incrementing current index");
-                match &mut download_kind {
-                    DownloadKind::FileSystem(decryptor) => {
-                        let _ = decryptor.next_encrypted(encrypted_chunk)?;
-                    }
-                    DownloadKind::Memory(collector) => collector.push(encrypted_chunk),
-                }
+                download_kind.process(encrypted_chunk)?;
                 current_index += 1;
             }
             // now check if we can process the fetched index, else cache it.
             if index == current_index {
                 debug!("The downloaded chunk's index {index:?} matches the current index {current_index}. Processing it");
-                match &mut download_kind {
-                    DownloadKind::FileSystem(decryptor) => {
-                        let _ = decryptor.next_encrypted(encrypted_chunk)?;
-                    }
-                    DownloadKind::Memory(collector) => collector.push(encrypted_chunk),
-                }
+                download_kind.process(encrypted_chunk)?;
                 current_index += 1;
             } else {
                 // since we download the chunks concurrently without order, we cache the results for an index that
@@ -434,14 +536,11 @@ impl FilesDownload {
 
         // finally empty out the cache.
         debug!("Finally emptying out the download cache");
-        while let Some(encrypted_chunk) = chunk_download_cache.remove(&current_index) {
+        while let Some(encrypted_chunk) =
+            Self::take_ready_chunk(&mut chunk_download_cache, journal.as_ref(), current_index)
+        {
             debug!("Got current_index {current_index:?} from the download cache. Incrementing current index");
-            match &mut download_kind {
-                DownloadKind::FileSystem(decryptor) => {
-                    let _ = decryptor.next_encrypted(encrypted_chunk)?;
-                }
-                DownloadKind::Memory(collector) => collector.push(encrypted_chunk),
-            }
+            download_kind.process(encrypted_chunk)?;
             current_index += 1;
         }
         if !chunk_download_cache.is_empty() {
@@ -452,6 +551,11 @@ impl FilesDownload {
             return Err(ClientError::FailedToAssembleDownloadedChunks);
         }
 
+        if let Some(journal) = journal {
+            journal.remove()?;
+            self.send_event(FilesDownloadEvent::JournalRemoved).await?;
+        }
+
         let elapsed = now.elapsed();
         info!("Client downloaded file in {elapsed:?}");
 
@@ -495,6 +599,17 @@ impl FilesDownload {
         }
     }
 
+    /// Takes the chunk of `index` from the download cache, or from the journal of a previous attempt.
+    fn take_ready_chunk(
+        cache: &mut HashMap<usize, EncryptedChunk>,
+        journal: Option<&DownloadJournal>,
+        index: usize,
+    ) -> Option<EncryptedChunk> {
+        cache
+            .remove(&index)
+            .or_else(|| journal.and_then(|journal| journal.get(index)))
+    }
+
     async fn send_event(&mut self, event: FilesDownloadEvent) -> Result<()> {
         if let Some(sender) = self.event_sender.as_ref() {
             sender.send(event).await.map_err(|err| {
@@ -533,3 +648,89 @@ impl FilesDownload {
         Ok((chunk.address, index, encrypted_chunk))
     }
 }
+
+/// The chunks of the `DataMap` overlapping `length` bytes starting at `offset`, along with the
+/// position of the range within the first of them and its length clamped to the end of the file.
+/// Returns None if the range is empty.
+fn seek_range(data_map: &DataMap, offset: usize, length: usize) -> Option<(DataMap, usize, usize)> {
+    let file_size = data_map.file_size();
+    if length == 0 || offset >= file_size {
+        return None;
+    }
+    let length = length.min(file_size - offset);
+
+    let info = self_encryption::seek_info(file_size, offset, length);
+    let range = &info.index_range;
+    let to_download = DataMap::new(data_map.infos()[range.start..=range.end].to_vec());
+    Some((to_download, info.relative_pos, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    fn encrypt_random_file(size: usize) -> (Bytes, DataMap, Vec<EncryptedChunk>) {
+        let mut bytes = vec![0u8; size];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let bytes = Bytes::from(bytes);
+        let (data_map, encrypted_chunks) =
+            self_encryption::encrypt(bytes.clone()).expect("Failed to encrypt");
+        (bytes, data_map, encrypted_chunks)
+    }
+
+    /// Reads the range the way `read_data_map_range` does, with the chunks at hand instead of
+    /// fetching them. Also returns the indexes of the chunks it needed.
+    fn read_range(
+        data_map: &DataMap,
+        encrypted_chunks: &[EncryptedChunk],
+        offset: usize,
+        length: usize,
+    ) -> (Bytes, Vec<usize>) {
+        let Some((to_download, relative_pos, length)) = seek_range(data_map, offset, length) else {
+            return (Bytes::new(), vec![]);
+        };
+        let indexes: Vec<_> = to_download.infos().iter().map(|info| info.index).collect();
+        let needed: Vec<_> = encrypted_chunks
+            .iter()
+            .filter(|chunk| indexes.contains(&chunk.index))
+            .cloned()
+            .collect();
+        let bytes = self_encryption::decrypt_range(data_map, &needed, relative_pos, length)
+            .expect("Failed to decrypt the range");
+        (bytes, indexes)
+    }
+
+    #[test]
+    fn range_across_two_chunks_only_needs_those_chunks() {
+        let (bytes, data_map, encrypted_chunks) = encrypt_random_file(100_000);
+        let boundary = data_map.infos()[0].src_size;
+
+        let (read, indexes) = read_range(&data_map, &encrypted_chunks, boundary - 10, 20);
+        assert_eq!(read, bytes.slice(boundary - 10..boundary + 10));
+        assert_eq!(indexes, vec![0, 1]);
+
+        // a range ending right at the boundary stays within the first chunk
+        let (read, indexes) = read_range(&data_map, &encrypted_chunks, 0, boundary);
+        assert_eq!(read, bytes.slice(..boundary));
+        assert_eq!(indexes, vec![0]);
+    }
+
+    #[test]
+    fn range_past_the_end_is_clamped() {
+        let (bytes, data_map, encrypted_chunks) = encrypt_random_file(100_000);
+
+        let (read, _) = read_range(&data_map, &encrypted_chunks, bytes.len() - 5, 100);
+        assert_eq!(read, bytes.slice(bytes.len() - 5..));
+
+        let (read, indexes) = read_range(&data_map, &encrypted_chunks, bytes.len(), 100);
+        assert!(read.is_empty());
+        assert!(indexes.is_empty());
+    }
+
+    #[test]
+    fn zero_length_range_fetches_nothing() {
+        let (_, data_map, _) = encrypt_random_file(100_000);
+        assert!(seek_range(&data_map, 10, 0).is_none());
+    }
+}
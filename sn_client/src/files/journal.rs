@@ -0,0 +1,185 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Result;
+use bytes::Bytes;
+use self_encryption::{DataMap, EncryptedChunk};
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+use xor_name::XorName;
+
+/// The file listing the chunks fetched so far, one `<index> <hex chunk name>` line per chunk.
+const JOURNAL_FILE: &str = "journal";
+
+/// The progress of a download to the file system, persisted next to the downloaded file.
+/// The encrypted chunks are kept in the journal directory as they are fetched, to be read from
+/// the disk instead of the network if the download is interrupted and started again.
+pub(crate) struct DownloadJournal {
+    dir: PathBuf,
+    /// The chunks fetched and verified so far, by index
+    fetched: BTreeMap<usize, XorName>,
+}
+
+impl DownloadJournal {
+    /// Opens the journal of the download to `file_path`, creating it if there is none.
+    /// The entries of a previous download that don't match the `DataMap` are ignored.
+    pub(crate) fn open(file_path: &Path, data_map: &DataMap) -> Result<Self> {
+        let dir = Self::dir_for(file_path);
+        fs::create_dir_all(&dir)?;
+
+        let expected: BTreeMap<_, _> = data_map
+            .infos()
+            .into_iter()
+            .map(|info| (info.index, info.dst_hash))
+            .collect();
+        let mut fetched = BTreeMap::new();
+        let journal = fs::read_to_string(dir.join(JOURNAL_FILE)).unwrap_or_default();
+        for line in journal.lines() {
+            // the last line might have been partially written
+            let Some((index, name)) = Self::parse_line(line) else {
+                warn!("Ignoring invalid download journal line {line:?} in {dir:?}");
+                continue;
+            };
+            if expected.get(&index) == Some(&name) {
+                let _ = fetched.insert(index, name);
+            }
+        }
+
+        Ok(Self { dir, fetched })
+    }
+
+    /// The directory the journal of the download to `file_path` is kept in.
+    pub(crate) fn dir_for(file_path: &Path) -> PathBuf {
+        let mut name = std::ffi::OsString::from(".");
+        name.push(file_path.file_name().unwrap_or_default());
+        name.push(".download");
+        file_path.with_file_name(name)
+    }
+
+    /// The number of chunks fetched by the previous attempts.
+    pub(crate) fn fetched_count(&self) -> usize {
+        self.fetched.len()
+    }
+
+    pub(crate) fn contains(&self, index: usize) -> bool {
+        self.fetched.contains_key(&index)
+    }
+
+    /// Reads a chunk fetched by a previous attempt from the disk.
+    /// Returns None if it is not in the journal or does not match its name anymore.
+    pub(crate) fn get(&self, index: usize) -> Option<EncryptedChunk> {
+        let name = self.fetched.get(&index)?;
+        let content = fs::read(self.chunk_path(name)).ok()?;
+        if XorName::from_content(&content) != *name {
+            warn!("Journaled chunk {name:?} of index {index} is corrupted, fetching it again");
+            return None;
+        }
+        Some(EncryptedChunk {
+            index,
+            content: Bytes::from(content),
+        })
+    }
+
+    /// Persists a fetched chunk, if its content matches its name.
+    pub(crate) fn record(&mut self, name: XorName, chunk: &EncryptedChunk) -> Result<()> {
+        if XorName::from_content(&chunk.content) != name {
+            warn!(
+                "Not journaling chunk {name:?} of index {}, its content does not match its name",
+                chunk.index
+            );
+            return Ok(());
+        }
+
+        // write the chunk before listing it, so that a listed chunk is always complete
+        let chunk_path = self.chunk_path(&name);
+        let tmp_path = chunk_path.with_extension("tmp");
+        fs::write(&tmp_path, &chunk.content)?;
+        fs::rename(&tmp_path, &chunk_path)?;
+
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(JOURNAL_FILE))?;
+        writeln!(journal, "{} {}", chunk.index, hex::encode(name))?;
+
+        let _ = self.fetched.insert(chunk.index, name);
+        Ok(())
+    }
+
+    /// Removes the journal along with the chunks it holds, once the download has completed.
+    pub(crate) fn remove(self) -> Result<()> {
+        fs::remove_dir_all(&self.dir)?;
+        Ok(())
+    }
+
+    fn chunk_path(&self, name: &XorName) -> PathBuf {
+        self.dir.join(hex::encode(name))
+    }
+
+    fn parse_line(line: &str) -> Option<(usize, XorName)> {
+        let (index, name) = line.split_once(' ')?;
+        let index = index.parse().ok()?;
+        let name: [u8; 32] = hex::decode(name).ok()?.try_into().ok()?;
+        Some((index, XorName(name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+    use self_encryption::ChunkInfo;
+
+    #[test]
+    fn journal_keeps_the_fetched_chunks_across_attempts() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("file");
+
+        let chunks: Vec<_> = (0..3)
+            .map(|index| EncryptedChunk {
+                index,
+                content: Bytes::from(vec![index as u8; 10]),
+            })
+            .collect();
+        let data_map = DataMap::new(
+            chunks
+                .iter()
+                .map(|chunk| ChunkInfo {
+                    index: chunk.index,
+                    dst_hash: XorName::from_content(&chunk.content),
+                    src_hash: XorName::default(),
+                    src_size: 10,
+                })
+                .collect(),
+        );
+
+        let mut journal = DownloadJournal::open(&file_path, &data_map)?;
+        assert_eq!(journal.fetched_count(), 0);
+        journal.record(XorName::from_content(&chunks[0].content), &chunks[0])?;
+        journal.record(XorName::from_content(&chunks[2].content), &chunks[2])?;
+        // a chunk not matching its name is not recorded
+        journal.record(XorName::default(), &chunks[1])?;
+
+        let journal = DownloadJournal::open(&file_path, &data_map)?;
+        assert_eq!(journal.fetched_count(), 2);
+        assert!(!journal.contains(1));
+        assert_eq!(
+            journal.get(2).map(|chunk| chunk.content),
+            Some(chunks[2].content.clone())
+        );
+
+        journal.remove()?;
+        assert!(!DownloadJournal::dir_for(&file_path).exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{chunks::Error as ChunksError, error::Result, Client};
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt};
+use self_encryption::{decrypt_range, DataMap, EncryptedChunk};
+use sn_protocol::storage::{ChunkAddress, RetryStrategy};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, SeekFrom},
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// The default number of decrypted chunks kept in memory by a `DataMapReader`.
+pub const DEFAULT_CACHED_CHUNKS: usize = 8;
+
+/// Where the reader fetches the encrypted chunks from.
+/// An interface is used here for easy testing.
+trait ChunkSource: Send + Sync {
+    fn get_chunk(
+        &self,
+        address: ChunkAddress,
+        retry_strategy: RetryStrategy,
+    ) -> BoxFuture<'static, Result<Bytes>>;
+}
+
+impl ChunkSource for Client {
+    fn get_chunk(
+        &self,
+        address: ChunkAddress,
+        retry_strategy: RetryStrategy,
+    ) -> BoxFuture<'static, Result<Bytes>> {
+        let client = self.clone();
+        Box::pin(async move {
+            let chunk = client
+                .get_chunk(address, false, Some(retry_strategy))
+                .await?;
+            Ok(chunk.value)
+        })
+    }
+}
+
+/// Streams the content of a file from the network, given its `DataMap`.
+///
+/// Implements `AsyncRead` and `AsyncSeek`, fetching and decrypting a chunk only once a read reaches it.
+/// The most recently read chunks are cached, so that small sequential reads or seeking back a little
+/// do not fetch the same chunk again.
+pub struct DataMapReader {
+    // Configurations
+    retry_strategy: RetryStrategy,
+    cache_capacity: usize,
+    // API
+    source: Arc<dyn ChunkSource>,
+    data_map: Arc<DataMap>,
+    // State
+    chunk_starts: Vec<u64>,
+    file_size: u64,
+    position: u64,
+    cached_chunks: HashMap<usize, Bytes>,
+    cache_order: VecDeque<usize>,
+    pending_fetch: Option<(usize, BoxFuture<'static, Result<Bytes>>)>,
+}
+
+impl DataMapReader {
+    /// Creates a new reader positioned at the start of the file.
+    /// To modify the configuration, use the provided setter methods (`set_...` functions).
+    pub fn new(client: Client, data_map: DataMap) -> Self {
+        Self::with_source(Arc::new(client), data_map)
+    }
+
+    fn with_source(source: Arc<dyn ChunkSource>, data_map: DataMap) -> Self {
+        let chunk_starts = chunk_starts(&data_map);
+        let file_size = data_map.file_size() as u64;
+        Self {
+            retry_strategy: RetryStrategy::Quick,
+            cache_capacity: DEFAULT_CACHED_CHUNKS,
+            source,
+            data_map: Arc::new(data_map),
+            chunk_starts,
+            file_size,
+            position: 0,
+            cached_chunks: HashMap::new(),
+            cache_order: VecDeque::new(),
+            pending_fetch: None,
+        }
+    }
+
+    /// Sets the RetryStrategy used to fetch each chunk.
+    ///
+    /// By default, this option is set to RetryStrategy::Quick
+    pub fn set_retry_strategy(mut self, retry_strategy: RetryStrategy) -> Self {
+        self.retry_strategy = retry_strategy;
+        self
+    }
+
+    /// Sets the number of decrypted chunks to keep in memory.
+    ///
+    /// By default, this option is set to the constant `DEFAULT_CACHED_CHUNKS: usize = 8`.
+    pub fn set_cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.cache_capacity = cache_capacity.max(1);
+        self
+    }
+
+    /// The size of the file, in bytes.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Returns the decrypted chunk at `index`, fetching it if it is not cached.
+    fn poll_chunk(&mut self, cx: &mut Context<'_>, index: usize) -> Poll<Result<Bytes>> {
+        if let Some(chunk) = self.cached_chunks.get(&index) {
+            return Poll::Ready(Ok(chunk.clone()));
+        }
+
+        // a seek might have moved us to another chunk while a fetch was in progress
+        let fetch = match self.pending_fetch.take() {
+            Some((pending_index, fetch)) if pending_index == index => fetch,
+            _ => self.fetch_chunk(index),
+        };
+        let (_, fetch) = self.pending_fetch.insert((index, fetch));
+
+        let result = ready!(fetch.poll_unpin(cx));
+        self.pending_fetch = None;
+        if let Ok(chunk) = &result {
+            self.cache_chunk(index, chunk.clone());
+        }
+        Poll::Ready(result)
+    }
+
+    fn fetch_chunk(&self, index: usize) -> BoxFuture<'static, Result<Bytes>> {
+        let data_map = Arc::clone(&self.data_map);
+        let chunk_info = data_map.infos()[index].clone();
+        trace!("Fetching chunk of index {index} for the reader");
+        let fetch = self
+            .source
+            .get_chunk(ChunkAddress::new(chunk_info.dst_hash), self.retry_strategy);
+        Box::pin(async move {
+            let encrypted_chunk = EncryptedChunk {
+                index,
+                content: fetch.await?,
+            };
+            let bytes = decrypt_range(&data_map, &[encrypted_chunk], 0, chunk_info.src_size)
+                .map_err(ChunksError::SelfEncryption)?;
+            Ok(bytes)
+        })
+    }
+
+    fn cache_chunk(&mut self, index: usize, chunk: Bytes) {
+        if self.cached_chunks.insert(index, chunk).is_none() {
+            self.cache_order.push_back(index);
+        }
+        while self.cache_order.len() > self.cache_capacity {
+            if let Some(evicted) = self.cache_order.pop_front() {
+                let _ = self.cached_chunks.remove(&evicted);
+            }
+        }
+    }
+}
+
+impl AsyncRead for DataMapReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.position >= this.file_size || buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let index = chunk_index_at(&this.chunk_starts, this.position);
+        let chunk = ready!(this.poll_chunk(cx, index)).map_err(|err| {
+            error!("Failed to read chunk of index {index}: {err:?}");
+            io::Error::new(io::ErrorKind::Other, err.to_string())
+        })?;
+
+        let offset_in_chunk = (this.position - this.chunk_starts[index]) as usize;
+        let available = chunk.get(offset_in_chunk..).unwrap_or_default();
+        let len = available.len().min(buf.remaining());
+        buf.put_slice(&available[..len]);
+        this.position += len as u64;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for DataMapReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+        let new_position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => this.file_size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.position.checked_add_signed(offset),
+        };
+        this.position = new_position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.position))
+    }
+}
+
+/// The position in the file of the first byte of each chunk.
+fn chunk_starts(data_map: &DataMap) -> Vec<u64> {
+    data_map
+        .infos()
+        .iter()
+        .scan(0u64, |start, chunk_info| {
+            let chunk_start = *start;
+            *start += chunk_info.src_size as u64;
+            Some(chunk_start)
+        })
+        .collect()
+}
+
+/// The index of the chunk holding the byte at `position`.
+fn chunk_index_at(chunk_starts: &[u64], position: u64) -> usize {
+    chunk_starts
+        .partition_point(|start| *start <= position)
+        .saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+    use self_encryption::ChunkInfo;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+    use xor_name::XorName;
+
+    /// Serves the chunks of a file encrypted locally, keeping track of the fetches.
+    struct LocalChunks {
+        chunks: HashMap<XorName, Bytes>,
+        fetched: Mutex<Vec<XorName>>,
+    }
+
+    impl ChunkSource for LocalChunks {
+        fn get_chunk(
+            &self,
+            address: ChunkAddress,
+            _retry_strategy: RetryStrategy,
+        ) -> BoxFuture<'static, Result<Bytes>> {
+            self.fetched
+                .lock()
+                .expect("Failed to lock")
+                .push(*address.xorname());
+            let chunk = self
+                .chunks
+                .get(address.xorname())
+                .cloned()
+                .expect("Unknown chunk");
+            Box::pin(async move { Ok(chunk) })
+        }
+    }
+
+    fn local_reader(size: usize) -> (Bytes, DataMap, Arc<LocalChunks>, DataMapReader) {
+        let mut bytes = vec![0u8; size];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let bytes = Bytes::from(bytes);
+        let (data_map, encrypted_chunks) =
+            self_encryption::encrypt(bytes.clone()).expect("Failed to encrypt");
+        let chunks = encrypted_chunks
+            .into_iter()
+            .map(|chunk| (XorName::from_content(&chunk.content), chunk.content))
+            .collect();
+        let source = Arc::new(LocalChunks {
+            chunks,
+            fetched: Mutex::new(vec![]),
+        });
+        let reader = DataMapReader::with_source(source.clone(), data_map.clone());
+        (bytes, data_map, source, reader)
+    }
+
+    #[tokio::test]
+    async fn reads_across_a_chunk_boundary() -> io::Result<()> {
+        let (bytes, data_map, source, mut reader) = local_reader(100_000);
+        let infos = data_map.infos();
+        let boundary = infos[0].src_size;
+
+        let _ = reader.seek(SeekFrom::Start(boundary as u64 - 10)).await?;
+        let mut read = vec![0u8; 20];
+        reader.read_exact(&mut read).await?;
+        assert_eq!(read, bytes[boundary - 10..boundary + 10]);
+
+        // only the two chunks around the boundary got fetched
+        assert_eq!(
+            *source.fetched.lock().expect("Failed to lock"),
+            vec![infos[0].dst_hash, infos[1].dst_hash]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reads_stop_at_the_end_of_the_file() -> io::Result<()> {
+        let (bytes, _, _, mut reader) = local_reader(100_000);
+
+        let _ = reader.seek(SeekFrom::End(-5)).await?;
+        let mut read = vec![];
+        let _ = reader.read_to_end(&mut read).await?;
+        assert_eq!(read, bytes[bytes.len() - 5..]);
+
+        let _ = reader.seek(SeekFrom::End(10)).await?;
+        let mut buf = [0u8; 16];
+        assert_eq!(reader.read(&mut buf).await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn zero_length_read_fetches_nothing() -> io::Result<()> {
+        let (_, _, source, mut reader) = local_reader(100_000);
+
+        assert_eq!(reader.read(&mut []).await?, 0);
+        assert!(source.fetched.lock().expect("Failed to lock").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_index_is_found_from_the_chunk_sizes() {
+        let mut rng = rand::thread_rng();
+        let infos = [1024, 1024, 512]
+            .into_iter()
+            .enumerate()
+            .map(|(index, src_size)| ChunkInfo {
+                index,
+                dst_hash: XorName::random(&mut rng),
+                src_hash: XorName::random(&mut rng),
+                src_size,
+            })
+            .collect();
+        let starts = chunk_starts(&DataMap::new(infos));
+
+        assert_eq!(starts, vec![0, 1024, 2048]);
+        assert_eq!(chunk_index_at(&starts, 0), 0);
+        assert_eq!(chunk_index_at(&starts, 1023), 0);
+        assert_eq!(chunk_index_at(&starts, 1024), 1);
+        assert_eq!(chunk_index_at(&starts, 2559), 2);
+    }
+}
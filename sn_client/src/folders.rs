@@ -180,6 +180,7 @@ impl FoldersApi {
     }
 
     /// Sync local Folder with the network.
+    /// Once the Folder's Register is full, this also pays for the Registers continuing it.
     pub async fn sync(&mut self, upload_cfg: UploadCfg) -> Result<()> {
         let mut wallet_client = self.wallet()?;
 
@@ -18,6 +18,7 @@ mod event;
 mod faucet;
 mod files;
 mod folders;
+mod record_cache;
 mod register;
 mod uploader;
 mod wallet;
@@ -42,10 +43,13 @@ pub use self::{
     event::{ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver},
     faucet::fund_faucet_from_genesis_wallet,
     files::{
+        audit::ChunkAudit,
         download::{FilesDownload, FilesDownloadEvent},
+        reader::{DataMapReader, DEFAULT_CACHED_CHUNKS},
         FilesApi, BATCH_SIZE,
     },
     folders::{FolderEntry, FoldersApi, Metadata},
+    record_cache::RecordCacheConfig,
     register::ClientRegister,
     uploader::{UploadCfg, UploadEvent, UploadSummary, Uploader},
     wallet::{broadcast_signed_spends, send, StoragePaymentResult, WalletClient},
@@ -125,4 +129,5 @@ pub struct Client {
     network: Network,
     events_broadcaster: ClientEventsBroadcaster,
     signer: Arc<bls::SecretKey>,
+    record_cache: Option<Arc<record_cache::RecordCache>>,
 }
@@ -0,0 +1,341 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use bytes::Bytes;
+use libp2p::kad::{Record, RecordKey};
+use sn_networking::target_arch::{SystemTime, UNIX_EPOCH};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+/// The extension of the files a record is written to before being renamed to its cached file.
+const TMP_EXTENSION: &str = "tmp";
+
+/// Where and how much to cache the records fetched by a client.
+#[derive(Clone, Debug)]
+pub struct RecordCacheConfig {
+    /// Where the records are also cached on disk, they are only cached in memory if None
+    pub dir: Option<PathBuf>,
+    /// The cached records held in memory are bounded to this many bytes
+    pub max_memory_bytes: u64,
+    /// The cached records held on disk are bounded to this many bytes
+    pub max_disk_bytes: u64,
+    /// How long a cached Register is served for, before being fetched from the network again
+    pub register_freshness: Duration,
+}
+
+impl Default for RecordCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_memory_bytes: 64 * 1024 * 1024,
+            max_disk_bytes: 1024 * 1024 * 1024,
+            register_freshness: Duration::from_secs(30),
+        }
+    }
+}
+
+/// An LRU cache of the verified records fetched from the network, in memory and optionally on disk.
+///
+/// Only the records that cannot change shall be cached, or be served with a bounded age.
+#[derive(Debug)]
+pub(crate) struct RecordCache {
+    config: RecordCacheConfig,
+    /// The values, along with when they were fetched
+    memory: Mutex<Lru<(Bytes, SystemTime)>>,
+    /// When the records held on disk were fetched
+    disk: Mutex<Lru<SystemTime>>,
+}
+
+impl RecordCache {
+    /// Picks up the records cached on disk by a previous client, if any.
+    pub(crate) fn new(config: RecordCacheConfig) -> Self {
+        let mut disk = Lru::new(config.max_disk_bytes);
+        if let Some(dir) = &config.dir {
+            if let Err(err) = fs::create_dir_all(dir) {
+                warn!("Failed to create the record cache dir at {dir:?}: {err:?}");
+            }
+            for key in load_disk_index(dir, &mut disk) {
+                remove_cached_file(dir, &key);
+            }
+            debug!("Found {} records cached at {dir:?}", disk.entries.len());
+        }
+
+        Self {
+            memory: Mutex::new(Lru::new(config.max_memory_bytes)),
+            disk: Mutex::new(disk),
+            config,
+        }
+    }
+
+    pub(crate) fn register_freshness(&self) -> Duration {
+        self.config.register_freshness
+    }
+
+    /// The cached record, if it was fetched less than `max_age` ago when set.
+    pub(crate) fn get(&self, key: &RecordKey, max_age: Option<Duration>) -> Option<Record> {
+        let is_fresh = |fetched_at: SystemTime| {
+            max_age.map_or(true, |max_age| {
+                SystemTime::now()
+                    .duration_since(fetched_at)
+                    .unwrap_or_default()
+                    <= max_age
+            })
+        };
+
+        if let Some((value, fetched_at)) = lock(&self.memory).get(key) {
+            // the disk holds the same copy, no need to look there if this one is stale
+            return is_fresh(*fetched_at).then(|| Record::new(key.clone(), value.to_vec()));
+        }
+
+        let dir = self.config.dir.as_ref()?;
+        let fetched_at = *lock(&self.disk).get(key)?;
+        if !is_fresh(fetched_at) {
+            return None;
+        }
+        let value = match fs::read(cached_file_path(dir, key)) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("Failed to read a cached record from {dir:?}: {err:?}");
+                let _ = lock(&self.disk).remove(key);
+                return None;
+            }
+        };
+
+        let size = value.len() as u64;
+        let _ =
+            lock(&self.memory).insert(key.clone(), (Bytes::from(value.clone()), fetched_at), size);
+        Some(Record::new(key.clone(), value))
+    }
+
+    /// Caches a record that got verified, evicting the least recently used ones if needed.
+    pub(crate) fn put(&self, record: &Record) {
+        let fetched_at = SystemTime::now();
+        let size = record.value.len() as u64;
+        let value = Bytes::from(record.value.clone());
+        let _ = lock(&self.memory).insert(record.key.clone(), (value, fetched_at), size);
+
+        let Some(dir) = &self.config.dir else {
+            return;
+        };
+        // a crash mid-write shall not leave a truncated record to be served later on
+        let file_path = cached_file_path(dir, &record.key);
+        let tmp_file_path = file_path.with_extension(TMP_EXTENSION);
+        if let Err(err) = fs::write(&tmp_file_path, &record.value)
+            .and_then(|_| fs::rename(&tmp_file_path, &file_path))
+        {
+            warn!("Failed to cache a record to {dir:?}: {err:?}");
+            let _ = fs::remove_file(&tmp_file_path);
+            return;
+        }
+        let evicted = lock(&self.disk).insert(record.key.clone(), fetched_at, size);
+        for key in evicted {
+            remove_cached_file(dir, &key);
+        }
+    }
+
+    /// Drops a cached record, e.g. one found to be corrupted.
+    pub(crate) fn remove(&self, key: &RecordKey) {
+        let _ = lock(&self.memory).remove(key);
+        if let Some(dir) = &self.config.dir {
+            if lock(&self.disk).remove(key).is_some() {
+                remove_cached_file(dir, key);
+            }
+        }
+    }
+}
+
+fn lock<V>(lru: &Mutex<Lru<V>>) -> MutexGuard<'_, Lru<V>> {
+    // The cache stays usable even if a panic happened while holding the lock.
+    lru.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn cached_file_path(dir: &Path, key: &RecordKey) -> PathBuf {
+    dir.join(hex::encode(key.as_ref()))
+}
+
+fn remove_cached_file(dir: &Path, key: &RecordKey) {
+    if let Err(err) = fs::remove_file(cached_file_path(dir, key)) {
+        warn!("Failed to remove an evicted record from {dir:?}: {err:?}");
+    }
+}
+
+/// Indexes the records cached in `dir`, the least recently fetched first.
+/// Returns the ones evicted for going over the capacity.
+/// The records left half written by a previous client are removed.
+fn load_disk_index(dir: &Path, disk: &mut Lru<SystemTime>) -> Vec<RecordKey> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut cached: Vec<_> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+                if let Err(err) = fs::remove_file(&path) {
+                    warn!("Failed to remove a half written record at {path:?}: {err:?}");
+                }
+                return None;
+            }
+            let key = RecordKey::from(hex::decode(entry.file_name().to_str()?).ok()?);
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?;
+            Some((key, UNIX_EPOCH + modified, metadata.len()))
+        })
+        .collect();
+    cached.sort_by_key(|(_, modified, _)| *modified);
+
+    cached
+        .into_iter()
+        .flat_map(|(key, modified, size)| disk.insert(key, modified, size))
+        .collect()
+}
+
+/// Least recently used entries, bounded by their total size.
+#[derive(Debug)]
+struct Lru<V> {
+    /// The values, along with their size and when they were last used
+    entries: HashMap<RecordKey, (V, u64, u64)>,
+    /// The keys by when they were last used
+    recency: BTreeMap<u64, RecordKey>,
+    next_tick: u64,
+    total_size: u64,
+    max_size: u64,
+}
+
+impl<V> Lru<V> {
+    fn new(max_size: u64) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_tick: 0,
+            total_size: 0,
+            max_size,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.next_tick += 1;
+        self.next_tick
+    }
+
+    fn get(&mut self, key: &RecordKey) -> Option<&V> {
+        let tick = self.tick();
+        let (_, _, last_used) = self.entries.get_mut(key)?;
+        let _ = self.recency.remove(last_used);
+        *last_used = tick;
+        let _ = self.recency.insert(tick, key.clone());
+        self.entries.get(key).map(|(value, _, _)| value)
+    }
+
+    fn remove(&mut self, key: &RecordKey) -> Option<V> {
+        let (value, size, last_used) = self.entries.remove(key)?;
+        let _ = self.recency.remove(&last_used);
+        self.total_size -= size;
+        Some(value)
+    }
+
+    /// Returns the keys evicted to make room for the entry, which could be the entry itself
+    /// if it is larger than the capacity.
+    fn insert(&mut self, key: RecordKey, value: V, size: u64) -> Vec<RecordKey> {
+        let _ = self.remove(&key);
+        if size > self.max_size {
+            return vec![key];
+        }
+
+        let mut evicted = vec![];
+        while self.total_size + size > self.max_size {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((_, oldest_size, _)) = self.entries.remove(&oldest) {
+                self.total_size -= oldest_size;
+            }
+            evicted.push(oldest);
+        }
+
+        let tick = self.tick();
+        let _ = self.recency.insert(tick, key.clone());
+        let _ = self.entries.insert(key, (value, size, tick));
+        self.total_size += size;
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_records_are_evicted() {
+        let cache = RecordCache::new(RecordCacheConfig {
+            max_memory_bytes: 20,
+            ..Default::default()
+        });
+        let records: Vec<_> = (0..3u8)
+            .map(|i| Record::new(RecordKey::new(&[i]), vec![i; 8]))
+            .collect();
+
+        cache.put(&records[0]);
+        cache.put(&records[1]);
+        // using the first record makes the second one the least recently used
+        assert!(cache.get(&records[0].key, None).is_some());
+        cache.put(&records[2]);
+
+        assert_eq!(
+            cache.get(&records[0].key, None).map(|record| record.value),
+            Some(records[0].value.clone())
+        );
+        assert!(cache.get(&records[1].key, None).is_none());
+        assert!(cache.get(&records[2].key, None).is_some());
+
+        // a record older than the freshness asked for is not served
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(cache.get(&records[2].key, Some(Duration::ZERO)).is_none());
+        assert!(cache
+            .get(&records[2].key, Some(Duration::from_secs(60)))
+            .is_some());
+    }
+
+    #[test]
+    fn records_cached_on_disk_are_picked_up_by_the_next_cache() {
+        let tmp_dir = tempfile::tempdir().expect("create a temp dir");
+        let dir = tmp_dir.path().to_path_buf();
+        let config = RecordCacheConfig {
+            dir: Some(dir.clone()),
+            ..Default::default()
+        };
+        let record = Record::new(RecordKey::new(&[1]), vec![1; 8]);
+        let half_written =
+            cached_file_path(&dir, &RecordKey::new(&[2])).with_extension(TMP_EXTENSION);
+
+        let cache = RecordCache::new(config.clone());
+        cache.put(&record);
+        assert!(cached_file_path(&dir, &record.key).exists());
+        fs::write(&half_written, [2; 4]).expect("write to the cache dir");
+
+        let cache = RecordCache::new(config);
+        assert!(!half_written.exists());
+        assert_eq!(
+            cache.get(&record.key, None).map(|record| record.value),
+            Some(record.value.clone())
+        );
+
+        cache.remove(&record.key);
+        assert!(cache.get(&record.key, None).is_none());
+        assert!(!cached_file_path(&dir, &record.key).exists());
+    }
+}
@@ -6,8 +6,18 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::{wallet::StoragePaymentResult, Client, Error, Result, WalletClient};
-use bls::PublicKey;
+mod continuation;
+mod encryption;
+
+use self::{
+    continuation::{LinkedEntries, LinkedRegisters},
+    encryption::EncryptionKey,
+};
+use crate::{
+    api::get_register_from_record, wallet::StoragePaymentResult, Client, Error, Result,
+    WalletClient,
+};
+use bls::{Ciphertext, PublicKey};
 use crdts::merkle_reg::MerkleReg;
 use libp2p::{
     kad::{Quorum, Record},
@@ -20,7 +30,10 @@ use sn_protocol::{
     storage::{try_serialize_record, RecordKind, RetryStrategy},
     NetworkAddress,
 };
-use sn_registers::{Entry, EntryHash, Permissions, Register, RegisterAddress, SignedRegister};
+use sn_registers::{
+    Entry, EntryHash, Error as RegisterError, PermissionChange, Permissions, Register,
+    RegisterAddress, SignedRegister,
+};
 use sn_transfers::{NanoTokens, Payment};
 use std::collections::{BTreeSet, HashSet, LinkedList};
 use xor_name::XorName;
@@ -28,11 +41,24 @@ use xor_name::XorName;
 /// Cached operations made to an offline Register instance are applied locally only,
 /// and accumulated until the user explicitly calls 'sync'. The user can
 /// switch back to sync with the network for every op by invoking `online` API.
+///
+/// Once the Register is full, i.e. it holds `MAX_REG_NUM_ENTRIES` entries, the new entries are
+/// written to a continuation Register, which its current owner or any writer can start, at an
+/// address derived from the full one and the key of its creator.
+/// The entries of the Register and its continuations are read as the entries of a single Register.
+///
+/// An encrypted Register has its entries encrypted with AES-256-GCM-SIV, with a key derived from
+/// the owner's key, which the owner can share with the other writers. They are then read with the
+/// `_decrypted` APIs. Each encrypted entry takes 28 bytes more than the plain one, a nonce and a tag,
+/// leaving 996 of the `MAX_REG_ENTRY_SIZE` bytes for the entry.
 #[derive(Clone, custom_debug::Debug)]
 pub struct ClientRegister {
     #[debug(skip)]
     client: Client,
     pub(crate) register: Register,
+    continuations: Vec<Register>, // Registers continuing the above one, ordered by level.
+    #[debug(skip)]
+    encryption_key: Option<EncryptionKey>, // Set for encrypted Registers.
     ops: LinkedList<RegisterCmd>, // Cached operations.
 }
 
@@ -44,6 +70,8 @@ impl ClientRegister {
         Self {
             client,
             register,
+            continuations: vec![],
+            encryption_key: None,
             ops: LinkedList::new(),
         }
     }
@@ -98,6 +126,8 @@ impl ClientRegister {
         Self {
             client,
             register,
+            continuations: vec![],
+            encryption_key: None,
             ops: LinkedList::new(),
         }
     }
@@ -156,15 +186,116 @@ impl ClientRegister {
         Ok((reg, storage_cost, royalties_fees))
     }
 
+    /// Create a new encrypted Register locally.
+    /// Its entries are encrypted with a key derived from the client's key, the owner of the Register.
+    ///
+    /// # Arguments
+    /// * 'client' - [Client]
+    /// * 'meta' - [XorName]
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sn_client::{Client, ClientRegister, Error};
+    /// # use bls::SecretKey;
+    /// # use xor_name::XorName;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(),Error>{
+    /// # let mut rng = rand::thread_rng();
+    /// let client = Client::new(SecretKey::random(), None, None, None).await?;
+    /// let address = XorName::random(&mut rng);
+    /// let mut register = ClientRegister::create_encrypted(client, address);
+    /// register.write(b"sensitive pointer")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_encrypted(client: Client, meta: XorName) -> Self {
+        let mut register = Self::create_register(client, meta, Permissions::default());
+        register.encryption_key = Some(EncryptionKey::derive(
+            register.client.signer(),
+            register.address(),
+        ));
+        register
+    }
+
+    /// Create a new encrypted Register and send it to the Network.
+    /// Its entries are encrypted with a key derived from the client's key, the owner of the Register,
+    /// which can be shared with the writers allowed by the `perms` using `share_encryption_key`.
+    ///
+    /// # Arguments
+    /// * 'client' - [Client]
+    /// * 'meta' - [XorName]
+    /// * 'wallet_client' - A borrowed mutable [WalletClient]
+    /// * `verify_store` - A boolean to verify store. Set this to true for mandatory verification.
+    /// * 'perms' - [Permissions]
+    ///
+    /// Return type: Result<(Self, [NanoTokens], [NanoTokens])>
+    pub async fn create_online_encrypted(
+        client: Client,
+        meta: XorName,
+        wallet_client: &mut WalletClient,
+        verify_store: bool,
+        perms: Permissions,
+    ) -> Result<(Self, NanoTokens, NanoTokens)> {
+        let mut reg = Self::create_register(client, meta, perms);
+        reg.encryption_key = Some(EncryptionKey::derive(reg.client.signer(), reg.address()));
+        let (storage_cost, royalties_fees) = reg.sync(wallet_client, verify_store, None).await?;
+        Ok((reg, storage_cost, royalties_fees))
+    }
+
+    /// Encrypt the entries written from now on, and decrypt the entries read with the
+    /// `_decrypted` APIs, with the key derived from the client's key.
+    /// Only the owner of the Register can derive its encryption key,
+    /// the other writers have to use the key shared by the owner.
+    pub fn with_owner_encryption(mut self) -> Result<Self> {
+        if self.client.signer_pk() != self.owner() {
+            return Err(RegisterError::InvalidSecretKey)?;
+        }
+        self.encryption_key = Some(EncryptionKey::derive(self.client.signer(), self.address()));
+        Ok(self)
+    }
+
+    /// Encrypt the entries written from now on, and decrypt the entries read with the
+    /// `_decrypted` APIs, with the key shared by the owner of the Register,
+    /// as obtained with `share_encryption_key`.
+    pub fn with_shared_encryption_key(mut self, shared_key: &Ciphertext) -> Result<Self> {
+        self.encryption_key = Some(EncryptionKey::from_shared(
+            shared_key,
+            self.client.signer(),
+        )?);
+        Ok(self)
+    }
+
+    /// Return true if the entries written to the Register get encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_key.is_some()
+    }
+
+    /// Encrypt the encryption key of the Register to the provided writer's key.
+    /// The writer can then use it with `with_shared_encryption_key` to read and write entries.
+    /// It is up to the owner to send it to the writer.
+    pub fn share_encryption_key(&self, writer: PublicKey) -> Result<Ciphertext> {
+        let encryption_key = self
+            .encryption_key
+            .as_ref()
+            .ok_or(Error::RegisterEncryptionKeyMissing)?;
+        Ok(encryption_key.share(&writer))
+    }
+
     /// Retrieve a Register from the network to work on it offline.
+    /// The encryption key of an encrypted Register is not kept locally, it has to be set again
+    /// with `with_owner_encryption` or `with_shared_encryption_key`.
     pub(super) async fn retrieve(client: Client, address: RegisterAddress) -> Result<Self> {
-        let register = Self::get_register_from_network(&client, address).await?;
+        let register = Self::get_recent_register(&client, address).await?;
 
-        Ok(Self {
+        let mut client_register = Self {
             client,
             register,
+            continuations: vec![],
+            encryption_key: None,
             ops: LinkedList::new(),
-        })
+        };
+        client_register.merge_continuations_from_network().await?;
+        Ok(client_register)
     }
 
     /// Return type: [RegisterAddress]
@@ -235,6 +366,14 @@ impl ClientRegister {
         self.register.owner()
     }
 
+    /// Return the current owner of the Register, the one allowed to change its permissions.
+    /// This differs from the `owner` once the ownership got transferred.
+    ///
+    /// Return type: [PublicKey]
+    pub fn current_owner(&self) -> PublicKey {
+        self.register.current_owner()
+    }
+
     /// Returns the Permissions of the Register.
     ///
     /// Return type: [Permissions]
@@ -267,7 +406,7 @@ impl ClientRegister {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn permissions(&self) -> &Permissions {
+    pub fn permissions(&self) -> Permissions {
         self.register.permissions()
     }
 
@@ -303,13 +442,19 @@ impl ClientRegister {
     /// # }
     /// ```
     pub fn size(&self) -> u64 {
-        self.register.size()
+        self.registers().map(|register| register.size()).sum()
     }
 
     /// Return a value corresponding to the provided 'hash', if present.
-    // No usages found in All Places
-    pub fn get(&self, hash: EntryHash) -> Result<&Entry> {
-        let entry = self.register.get(hash)?;
+    pub fn get(&self, hash: EntryHash) -> Result<Entry> {
+        if self.continuations.is_empty() {
+            return Ok(self.register.get(hash)?.clone());
+        }
+        let entry = self
+            .linked_entries()
+            .get(&hash)
+            .cloned()
+            .ok_or(RegisterError::NoSuchEntry(hash))?;
         Ok(entry)
     }
 
@@ -333,7 +478,80 @@ impl ClientRegister {
     /// # }
     /// ```
     pub fn read(&self) -> BTreeSet<(EntryHash, Entry)> {
-        self.register.read()
+        if self.continuations.is_empty() {
+            return self.register.read();
+        }
+        self.linked_entries().read()
+    }
+
+    /// Read the last entry, or entries when there are branches, decrypting them with the
+    /// encryption key of the Register.
+    ///
+    /// Return type: Result<[BTreeSet]<([EntryHash], [Entry])>>
+    pub fn read_decrypted(&self) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        let encryption_key = self
+            .encryption_key
+            .as_ref()
+            .ok_or(Error::RegisterEncryptionKeyMissing)?;
+        self.read()
+            .into_iter()
+            .map(|(hash, entry)| {
+                let entry = encryption_key.decrypt_entry(hash, &entry)?;
+                Ok((hash, entry))
+            })
+            .collect()
+    }
+
+    /// Return the decrypted value corresponding to the provided 'hash', if present.
+    pub fn get_decrypted(&self, hash: EntryHash) -> Result<Entry> {
+        let encryption_key = self
+            .encryption_key
+            .as_ref()
+            .ok_or(Error::RegisterEncryptionKeyMissing)?;
+        encryption_key.decrypt_entry(hash, &self.get(hash)?)
+    }
+
+    /// Return all the entries of the Register, ordered from the first entries written to the
+    /// latest ones. An entry always comes after the entries it was written atop of.
+    pub fn history(&self) -> Vec<(EntryHash, Entry)> {
+        self.linked_entries().history()
+    }
+
+    /// Return the entries the provided entry was written atop of, directly or not,
+    /// ordered from the first entries written.
+    pub fn ancestors(&self, hash: &EntryHash) -> Result<Vec<(EntryHash, Entry)>> {
+        let entries = self.linked_entries();
+        Self::check_entries_exist(&entries, [hash])?;
+        Ok(entries.ancestors(hash))
+    }
+
+    /// Return the entries written atop of the provided entry, directly or not,
+    /// ordered from the first entries written.
+    pub fn descendants(&self, hash: &EntryHash) -> Result<Vec<(EntryHash, Entry)>> {
+        let entries = self.linked_entries();
+        Self::check_entries_exist(&entries, [hash])?;
+        Ok(entries.descendants(hash))
+    }
+
+    /// Return the entries written between two versions of the Register, given by their heads
+    /// as returned by `read`, ordered from the first entries written.
+    pub fn diff(
+        &self,
+        from: &BTreeSet<EntryHash>,
+        to: &BTreeSet<EntryHash>,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        let entries = self.linked_entries();
+        Self::check_entries_exist(&entries, from.iter().chain(to))?;
+        Ok(entries.diff(from, to))
+    }
+
+    /// Return the addresses of the Registers continuing this one, ordered by level.
+    /// A Register is continued by another one once it is full.
+    pub fn continuation_addresses(&self) -> Vec<RegisterAddress> {
+        self.continuations
+            .iter()
+            .map(|register| *register.address())
+            .collect()
     }
 
     /// Write a new value onto the Register atop latest value.
@@ -360,7 +578,7 @@ impl ClientRegister {
     /// # }
     /// ```
     pub fn write(&mut self, entry: &[u8]) -> Result<EntryHash> {
-        let children = self.register.read();
+        let children = self.read();
         if children.len() > 1 {
             return Err(Error::ContentBranchDetected(children));
         }
@@ -395,12 +613,7 @@ impl ClientRegister {
     /// # }
     /// ```
     pub fn write_merging_branches(&mut self, entry: &[u8]) -> Result<EntryHash> {
-        let children: BTreeSet<EntryHash> = self
-            .register
-            .read()
-            .into_iter()
-            .map(|(hash, _)| hash)
-            .collect();
+        let children: BTreeSet<EntryHash> = self.read().into_iter().map(|(hash, _)| hash).collect();
 
         self.write_atop(entry, &children)
     }
@@ -436,20 +649,57 @@ impl ClientRegister {
         entry: &[u8],
         children: &BTreeSet<EntryHash>,
     ) -> Result<EntryHash> {
-        // check permissions first
-        let public_key = self.client.signer_pk();
-        self.register.check_user_permissions(public_key)?;
-
-        let (entry_hash, op) = self
-            .register
-            .write(entry.into(), children, self.client.signer())?;
-        let cmd = RegisterCmd::Edit(op);
+        let entry = match &self.encryption_key {
+            Some(encryption_key) => encryption_key.encrypt_entry(entry)?,
+            None => entry.to_vec(),
+        };
 
-        self.ops.push_front(cmd);
+        let (entry_hash, cmds) = LinkedRegisters {
+            register: &mut self.register,
+            continuations: &mut self.continuations,
+            signer: self.client.signer(),
+        }
+        .write_atop(entry, children)?;
+        for cmd in cmds {
+            self.ops.push_front(cmd);
+        }
 
         Ok(entry_hash)
     }
 
+    /// Change the permissions of the Register, i.e. add or remove a writer, or transfer its ownership.
+    /// Only the current owner of the Register can change its permissions.
+    /// Removing a writer only prevents the entries it writes from now on to be accepted.
+    ///
+    /// # Arguments
+    /// * 'change' - [PermissionChange]
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sn_client::{Client, ClientRegister, Error};
+    /// # use bls::SecretKey;
+    /// # use sn_registers::PermissionChange;
+    /// # use xor_name::XorName;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(),Error>{
+    /// let mut rng = rand::thread_rng();
+    /// let client = Client::new(SecretKey::random(), None, None, None).await?;
+    /// let address = XorName::random(&mut rng);
+    /// let writer = SecretKey::random().public_key();
+    /// let mut mutable_register = ClientRegister::create(client.clone(), address);
+    /// mutable_register.change_permissions(PermissionChange::AddWriter(writer))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn change_permissions(&mut self, change: PermissionChange) -> Result<()> {
+        // the continuations share the permissions of the Register
+        for register in std::iter::once(&mut self.register).chain(self.continuations.iter_mut()) {
+            let op = register.change_permissions(change.clone(), self.client.signer())?;
+            self.ops.push_front(RegisterCmd::ChangePermissions(op));
+        }
+        Ok(())
+    }
+
     // ********* Online methods  *********
 
     /// Sync this Register with the replicas on the network.
@@ -552,6 +802,17 @@ impl ClientRegister {
             }
         };
         self.register.merge(&remote_replica)?;
+        self.merge_continuations_from_network().await?;
+
+        let (continuations_storage_cost, continuations_royalties_fees) = self
+            .publish_continuations(wallet_client, verify_store)
+            .await?;
+        let storage_cost = storage_cost
+            .checked_add(continuations_storage_cost)
+            .ok_or(Error::TotalPriceTooHigh)?;
+        let royalties_fees = royalties_fees
+            .checked_add(continuations_royalties_fees)
+            .ok_or(Error::TotalPriceTooHigh)?;
         self.push(verify_store).await?;
 
         Ok((storage_cost, royalties_fees))
@@ -585,8 +846,21 @@ impl ClientRegister {
             let address = *self.address();
             debug!("Pushing {ops_len} cached Register cmds at {address}!");
 
+            // a continuation has to be paid for, which is only done when syncing, so its
+            // creation and the cmds editing it are kept queued until then
+            let mut queued = LinkedList::new();
+            let mut pending_creations = HashSet::new();
+
             // TODO: send them all concurrently
             while let Some(cmd) = self.ops.pop_back() {
+                if let RegisterCmd::Create { register, .. } = &cmd {
+                    let _ = pending_creations.insert(*register.address());
+                }
+                if pending_creations.contains(&cmd.dst()) {
+                    queued.push_front(cmd);
+                    continue;
+                }
+
                 // We don't need to send the payment proofs here since
                 // these are all Register mutation cmds which don't require payment.
                 let result =
@@ -597,11 +871,20 @@ impl ClientRegister {
                     warn!("Did not push Register cmd on all nodes in the close group!: {err}");
                     // We keep the cmd for next sync to retry
                     self.ops.push_back(cmd);
+                    self.ops.append(&mut queued);
                     return Err(err);
                 }
             }
 
-            debug!("Successfully pushed {ops_len} Register cmds at {address}!");
+            let queued_len = queued.len();
+            self.ops.append(&mut queued);
+            if queued_len > 0 {
+                info!("{queued_len} Register cmds at {address} are queued behind the creation of a continuation, sync the Register to store it");
+            }
+            debug!(
+                "Successfully pushed {} Register cmds at {address}!",
+                ops_len - queued_len
+            );
         }
 
         Ok(())
@@ -715,6 +998,41 @@ impl ClientRegister {
         self.push(verify_store).await
     }
 
+    /// Change the permissions of the Register, i.e. add or remove a writer, or transfer its ownership,
+    /// and push the change to the network.
+    ///
+    /// # Arguments
+    /// * 'change' - [PermissionChange]
+    /// * 'verify_store' - Boolean
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sn_client::{Client, ClientRegister, Error};
+    /// # use bls::SecretKey;
+    /// # use sn_registers::PermissionChange;
+    /// # use xor_name::XorName;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(),Error>{
+    /// let mut rng = rand::thread_rng();
+    /// let address = XorName::random(&mut rng);
+    /// let client = Client::new(SecretKey::random(), None, None, None).await?;
+    /// let new_owner = SecretKey::random().public_key();
+    /// let mut binding = ClientRegister::create(client, address);
+    /// binding
+    ///     .change_permissions_online(PermissionChange::TransferOwnership(new_owner), false)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn change_permissions_online(
+        &mut self,
+        change: PermissionChange,
+        verify_store: bool,
+    ) -> Result<()> {
+        self.change_permissions(change)?;
+        self.push(verify_store).await
+    }
+
     /// Access the underlying MerkleReg (e.g. for access to history)
     /// NOTE: This API is unstable and may be removed in the future
     pub fn merkle_reg(&self) -> &MerkleReg<Entry> {
@@ -723,6 +1041,141 @@ impl ClientRegister {
 
     // ********* Private helpers  *********
 
+    // The Register followed by its continuations
+    fn registers(&self) -> impl Iterator<Item = &Register> {
+        std::iter::once(&self.register).chain(self.continuations.iter())
+    }
+
+    fn linked_entries(&self) -> LinkedEntries {
+        LinkedEntries::new(self.registers())
+    }
+
+    fn check_entries_exist<'a>(
+        entries: &LinkedEntries,
+        hashes: impl IntoIterator<Item = &'a EntryHash>,
+    ) -> Result<()> {
+        for hash in hashes {
+            if !entries.contains(hash) {
+                return Err(RegisterError::NoSuchEntry(*hash))?;
+            }
+        }
+        Ok(())
+    }
+
+    // Merge the continuations stored on the network into the local ones.
+    // Only a full Register is continued, so the continuations are looked for as long as one of the
+    // previous level is full. Each level is looked for at the address of every user who can start
+    // a continuation, the ones not handed over to the current owner of the Register being ignored.
+    async fn merge_continuations_from_network(&mut self) -> Result<()> {
+        let creators = LinkedRegisters::continuation_creators(&self.register);
+        let current_owner = self.register.current_owner();
+        let mut previous_address = *self.register.address();
+        let mut previous_full = self.register.is_full();
+        // the number of local continuations in the levels looked at so far
+        let mut previous_len = 0;
+        while previous_full {
+            let level_meta = previous_address.continuation(current_owner).meta();
+            for creator in creators.iter() {
+                let address = previous_address.continuation(*creator);
+                let remote_replica = match Self::get_recent_register(&self.client, address).await {
+                    Ok(remote_replica) => remote_replica,
+                    Err(err) => {
+                        // it might only have been created locally so far
+                        debug!("Continuation Register not retrieved from {address:?}: {err:?}");
+                        continue;
+                    }
+                };
+                if remote_replica.current_owner() != current_owner {
+                    warn!("Ignoring continuation Register at {address:?} not owned by the owner of the Register");
+                    continue;
+                }
+                match self
+                    .continuations
+                    .iter_mut()
+                    .find(|register| register.address() == &address)
+                {
+                    Some(register) => register.merge(&remote_replica)?,
+                    None => {
+                        let level_end = previous_len
+                            + self.continuations[previous_len..]
+                                .iter()
+                                .take_while(|register| register.address().meta() == level_meta)
+                                .count();
+                        self.continuations.insert(level_end, remote_replica);
+                    }
+                }
+            }
+
+            let level: Vec<_> = self
+                .continuations
+                .iter()
+                .skip(previous_len)
+                .take_while(|register| register.address().meta() == level_meta)
+                .collect();
+            let Some(first) = level.first() else {
+                return Ok(());
+            };
+            previous_address = *first.address();
+            previous_full = level.iter().any(|register| register.is_full());
+            previous_len += level.len();
+        }
+        Ok(())
+    }
+
+    // Pay for and store the continuations created locally, returning the storage cost and royalties paid.
+    async fn publish_continuations(
+        &mut self,
+        wallet_client: &mut WalletClient,
+        verify_store: bool,
+    ) -> Result<(NanoTokens, NanoTokens)> {
+        let (mut creations, edits): (LinkedList<_>, LinkedList<_>) = std::mem::take(&mut self.ops)
+            .into_iter()
+            .partition(|cmd| matches!(cmd, RegisterCmd::Create { .. }));
+        self.ops = edits;
+
+        let mut storage_cost = NanoTokens::zero();
+        let mut royalties_fees = NanoTokens::zero();
+        while let Some(cmd) = creations.pop_back() {
+            match self
+                .publish_continuation(wallet_client, cmd.clone(), verify_store)
+                .await
+            {
+                Ok(payment_result) => {
+                    storage_cost = storage_cost
+                        .checked_add(payment_result.storage_cost)
+                        .ok_or(Error::TotalPriceTooHigh)?;
+                    royalties_fees = royalties_fees
+                        .checked_add(payment_result.royalty_fees)
+                        .ok_or(Error::TotalPriceTooHigh)?;
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to store continuation Register {:?}: {err}",
+                        cmd.dst()
+                    );
+                    // We keep the creations for next sync to retry, before the cmds editing them
+                    creations.push_back(cmd);
+                    self.ops.append(&mut creations);
+                    return Err(err);
+                }
+            }
+        }
+        Ok((storage_cost, royalties_fees))
+    }
+
+    async fn publish_continuation(
+        &self,
+        wallet_client: &mut WalletClient,
+        cmd: RegisterCmd,
+        verify_store: bool,
+    ) -> Result<StoragePaymentResult> {
+        let net_addr = NetworkAddress::RegisterAddress(cmd.dst());
+        let payment_result = self.make_payment(wallet_client, &net_addr).await?;
+        let payment_info = wallet_client.get_recent_payment_for_addr(&net_addr)?;
+        Self::publish_register(self.client.clone(), cmd, Some(payment_info), verify_store).await?;
+        Ok(payment_result)
+    }
+
     // Make a storage payment for the provided network address
     async fn make_payment(
         &self,
@@ -791,6 +1244,11 @@ impl ClientRegister {
                 reg.add_op(op)?;
                 reg
             }
+            RegisterCmd::ChangePermissions(op) => {
+                let mut reg = network_reg?;
+                reg.add_permission_op(op)?;
+                reg
+            }
         };
 
         let network_address = NetworkAddress::from_register_address(*register.address());
@@ -870,4 +1328,32 @@ impl ClientRegister {
         reg.verify_with_address(address)?;
         Ok(reg.register()?)
     }
+
+    /// Retrieve a `Register` from the record cache if it got fetched recently enough,
+    /// otherwise from the Network, caching it.
+    async fn get_recent_register(client: &Client, address: RegisterAddress) -> Result<Register> {
+        let Some(cache) = &client.record_cache else {
+            return Self::get_register_from_network(client, address).await;
+        };
+        let key = NetworkAddress::from_register_address(address).to_record_key();
+
+        if let Some(record) = cache.get(&key, Some(cache.register_freshness())) {
+            match get_register_from_record(&record) {
+                Ok(reg) if reg.verify_with_address(address).is_ok() => {
+                    debug!("Got Register {address} from the record cache");
+                    return Ok(reg.register()?);
+                }
+                _ => warn!("Ignoring the invalid Register cached at {address}"),
+            }
+        }
+
+        debug!("Retrieving Register from: {address}");
+        let reg = client
+            .get_signed_register_from_network(address, false)
+            .await?;
+        reg.verify_with_address(address)?;
+        let record_value = try_serialize_record(&reg, RecordKind::Register)?;
+        cache.put(&Record::new(key, record_value.to_vec()));
+        Ok(reg.register()?)
+    }
 }
@@ -0,0 +1,501 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Result;
+use bls::{PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+use sn_protocol::messages::RegisterCmd;
+use sn_registers::{
+    Entry, EntryHash, Error as RegisterError, PermissionChange, Permissions, Register,
+    MAX_REG_ENTRY_SIZE,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The format of the entries of a continuation register, i.e. a register holding the entries
+/// written once the registers before it got full.
+/// The entries of the previous registers an entry is written atop of can't be referenced by the
+/// CRDT of the continuation register, so they are kept alongside the value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct ContinuationEntry {
+    pub(super) value: Entry,
+    pub(super) previous_children: BTreeSet<EntryHash>,
+}
+
+impl ContinuationEntry {
+    pub(super) fn to_entry(&self) -> Result<Entry> {
+        Ok(rmp_serde::to_vec(self)?)
+    }
+
+    pub(super) fn from_entry(entry: &Entry) -> Result<Self> {
+        Ok(rmp_serde::from_slice(entry)?)
+    }
+
+    // Serialise the entry, checking it fits in a Register entry.
+    // The size error reports the largest value which fits along the previous children.
+    fn to_checked_entry(&self) -> Result<Entry> {
+        let entry = self.to_entry()?;
+        if entry.len() > MAX_REG_ENTRY_SIZE {
+            let overhead = entry.len() - self.value.len();
+            return Err(RegisterError::EntryTooBig {
+                size: self.value.len(),
+                max: MAX_REG_ENTRY_SIZE.saturating_sub(overhead),
+            })?;
+        }
+        Ok(entry)
+    }
+}
+
+/// A Register and its continuations, as written to by `signer`.
+///
+/// The current owner or any writer of the Register can start a continuation once the last one is
+/// full. It is created at the address derived for the signer and its ownership handed over to
+/// the current owner of the Register, so that the later permission changes apply to it as well.
+/// All the continuations of a level share the same meta, the new entries of the signer going to
+/// its own continuation if it has one.
+pub(super) struct LinkedRegisters<'a> {
+    pub(super) register: &'a mut Register,
+    /// The continuations, ordered by level.
+    pub(super) continuations: &'a mut Vec<Register>,
+    pub(super) signer: &'a SecretKey,
+}
+
+impl LinkedRegisters<'_> {
+    /// The users who can start a continuation of the Register: its current owner, then its
+    /// writers. A continuation created by a writer removed since is not looked for anymore.
+    pub(super) fn continuation_creators(register: &Register) -> Vec<PublicKey> {
+        let current_owner = register.current_owner();
+        let mut creators = vec![current_owner];
+        if let Permissions::Writers(writers) = register.permissions() {
+            creators.extend(
+                writers
+                    .into_iter()
+                    .filter(|writer| *writer != current_owner),
+            );
+        }
+        creators
+    }
+
+    /// Write the entry atop of the provided children to the last level of the Register, starting
+    /// a continuation if it is full. Returns the cmds to send to the network, in order.
+    pub(super) fn write_atop(
+        &mut self,
+        entry: Entry,
+        children: &BTreeSet<EntryHash>,
+    ) -> Result<(EntryHash, Vec<RegisterCmd>)> {
+        // check permissions first
+        let last_register = self.last_register();
+        last_register.check_user_permissions(self.signer.public_key())?;
+
+        let mut cmds = vec![];
+        if last_register.is_full() {
+            // none of the children are in the continuation yet
+            let continuation_entry = ContinuationEntry {
+                value: entry.clone(),
+                previous_children: children.clone(),
+            };
+            let _ = continuation_entry.to_checked_entry()?;
+            cmds = self.create_continuation()?;
+        }
+
+        let signer = self.signer;
+        let (entry_hash, op) = match self.last_index() {
+            None => self.register.write(entry, children, signer)?,
+            Some(index) => {
+                let register = &mut self.continuations[index];
+                // the children from the previous registers are kept in the entry itself
+                let (local_children, previous_children): (BTreeSet<_>, BTreeSet<_>) = children
+                    .iter()
+                    .copied()
+                    .partition(|hash| register.get(*hash).is_ok());
+                let continuation_entry = ContinuationEntry {
+                    value: entry,
+                    previous_children,
+                };
+                register.write(
+                    continuation_entry.to_checked_entry()?,
+                    &local_children,
+                    signer,
+                )?
+            }
+        };
+        cmds.push(RegisterCmd::Edit(op));
+
+        Ok((entry_hash, cmds))
+    }
+
+    // The index of the continuation new entries get written to, none for the Register itself.
+    // It is the one of the signer in the last level if any, else the first one of that level.
+    fn last_index(&self) -> Option<usize> {
+        let last_meta = self.continuations.last()?.address().meta();
+        let signer_pk = self.signer.public_key();
+        let last_level = || {
+            self.continuations
+                .iter()
+                .enumerate()
+                .filter(move |(_, register)| register.address().meta() == last_meta)
+        };
+        last_level()
+            .find(|(_, register)| register.address().owner() == signer_pk)
+            .or_else(|| last_level().next())
+            .map(|(index, _)| index)
+    }
+
+    // The Register new entries get written to
+    fn last_register(&self) -> &Register {
+        match self.last_index() {
+            Some(index) => &self.continuations[index],
+            None => self.register,
+        }
+    }
+
+    // Start a Register continuing the last one, which is full, with the same permissions.
+    fn create_continuation(&mut self) -> Result<Vec<RegisterCmd>> {
+        let creator = self.signer.public_key();
+        if !Self::continuation_creators(self.register).contains(&creator) {
+            return Err(RegisterError::AccessDenied(creator))?;
+        }
+
+        let last_register = self.last_register();
+        let address = last_register.address().continuation(creator);
+        let mut continuation = Register::new(creator, address.meta(), last_register.permissions());
+        debug!("Register is full, continuing it at {address:?}");
+
+        let mut cmds = vec![RegisterCmd::Create {
+            register: continuation.clone(),
+            signature: self.signer.sign(continuation.bytes()?),
+        }];
+        let current_owner = self.register.current_owner();
+        if creator != current_owner {
+            let op = continuation.change_permissions(
+                PermissionChange::TransferOwnership(current_owner),
+                self.signer,
+            )?;
+            cmds.push(RegisterCmd::ChangePermissions(op));
+        }
+        self.continuations.push(continuation);
+        Ok(cmds)
+    }
+}
+
+/// The entries of a register and its continuations, seen as the entries of a single register.
+pub(super) struct LinkedEntries {
+    /// The value and children of every entry
+    entries: BTreeMap<EntryHash, (Entry, BTreeSet<EntryHash>)>,
+    /// The entries ordered from the first written, an entry always coming after its children
+    order: Vec<EntryHash>,
+}
+
+impl LinkedEntries {
+    /// Gathers the entries of the registers, the first one being the register continued by the
+    /// others, in order.
+    pub(super) fn new<'a>(registers: impl IntoIterator<Item = &'a Register>) -> Self {
+        let mut entries: BTreeMap<EntryHash, (Entry, BTreeSet<EntryHash>)> = BTreeMap::new();
+        let mut order = vec![];
+        for (index, register) in registers.into_iter().enumerate() {
+            for (hash, entry) in register.history() {
+                let mut children: BTreeSet<_> = register
+                    .children(&hash)
+                    .into_iter()
+                    .map(|(child, _)| child)
+                    .collect();
+                let value = if index == 0 {
+                    entry
+                } else {
+                    match ContinuationEntry::from_entry(&entry) {
+                        Ok(continuation_entry) => {
+                            children.extend(continuation_entry.previous_children);
+                            continuation_entry.value
+                        }
+                        Err(err) => {
+                            let address = register.address();
+                            warn!("Invalid continuation entry {hash:?} in {address:?}: {err:?}");
+                            entry
+                        }
+                    }
+                };
+
+                // the same entry written into two registers is the same entry
+                if let Some((_, known_children)) = entries.get_mut(&hash) {
+                    known_children.extend(children);
+                } else {
+                    let _ = entries.insert(hash, (value, children));
+                    order.push(hash);
+                }
+            }
+        }
+        Self { entries, order }
+    }
+
+    pub(super) fn contains(&self, hash: &EntryHash) -> bool {
+        self.entries.contains_key(hash)
+    }
+
+    pub(super) fn get(&self, hash: &EntryHash) -> Option<&Entry> {
+        self.entries.get(hash).map(|(value, _)| value)
+    }
+
+    /// The entries no other entry was written atop of.
+    pub(super) fn read(&self) -> BTreeSet<(EntryHash, Entry)> {
+        let children: BTreeSet<_> = self
+            .entries
+            .values()
+            .flat_map(|(_, children)| children)
+            .collect();
+        self.entries
+            .iter()
+            .filter(|(hash, _)| !children.contains(hash))
+            .map(|(hash, (value, _))| (*hash, value.clone()))
+            .collect()
+    }
+
+    pub(super) fn history(&self) -> Vec<(EntryHash, Entry)> {
+        self.ordered(&self.order.iter().copied().collect())
+    }
+
+    pub(super) fn ancestors(&self, hash: &EntryHash) -> Vec<(EntryHash, Entry)> {
+        let mut ancestors = self.reachable_from([*hash].into_iter().collect());
+        let _ = ancestors.remove(hash);
+        self.ordered(&ancestors)
+    }
+
+    pub(super) fn descendants(&self, hash: &EntryHash) -> Vec<(EntryHash, Entry)> {
+        let mut descendants = BTreeSet::new();
+        // the order lists the descendants of an entry after it
+        for current in self.order.iter() {
+            let Some((_, children)) = self.entries.get(current) else {
+                continue;
+            };
+            if children
+                .iter()
+                .any(|child| child == hash || descendants.contains(child))
+            {
+                let _ = descendants.insert(*current);
+            }
+        }
+        self.ordered(&descendants)
+    }
+
+    pub(super) fn diff(
+        &self,
+        from: &BTreeSet<EntryHash>,
+        to: &BTreeSet<EntryHash>,
+    ) -> Vec<(EntryHash, Entry)> {
+        let known_from = self.reachable_from(from.clone());
+        let known_to = self.reachable_from(to.clone());
+        self.ordered(&known_to.difference(&known_from).copied().collect())
+    }
+
+    /// Returns the provided entries and all the entries they were written atop of.
+    fn reachable_from(&self, hashes: BTreeSet<EntryHash>) -> BTreeSet<EntryHash> {
+        let mut reachable = BTreeSet::new();
+        let mut to_visit: Vec<_> = hashes.into_iter().collect();
+        while let Some(current) = to_visit.pop() {
+            let Some((_, children)) = self.entries.get(&current) else {
+                continue;
+            };
+            if reachable.insert(current) {
+                to_visit.extend(children.iter().copied());
+            }
+        }
+        reachable
+    }
+
+    fn ordered(&self, hashes: &BTreeSet<EntryHash>) -> Vec<(EntryHash, Entry)> {
+        self.order
+            .iter()
+            .filter(|hash| hashes.contains(hash))
+            .filter_map(|hash| {
+                self.entries
+                    .get(hash)
+                    .map(|(value, _)| (*hash, value.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::SecretKey;
+    use eyre::Result;
+    use sn_registers::MAX_REG_NUM_ENTRIES;
+    use xor_name::XorName;
+
+    // Create a Register, adding the provided writer, and fill it with entries of the owner
+    fn full_register(owner_sk: &SecretKey, writer: PublicKey) -> Result<Register> {
+        let meta = XorName::random(&mut rand::thread_rng());
+        let mut register = Register::new(owner_sk.public_key(), meta, Permissions::default());
+        let _ = register.change_permissions(PermissionChange::AddWriter(writer), owner_sk)?;
+        let mut children = BTreeSet::new();
+        for i in 0..MAX_REG_NUM_ENTRIES {
+            let (hash, _) = register.write(i.to_be_bytes().to_vec(), &children, owner_sk)?;
+            children = [hash].into_iter().collect();
+        }
+        assert!(register.is_full());
+        Ok(register)
+    }
+
+    #[test]
+    fn entries_are_linked_across_continuation_registers() -> Result<()> {
+        let owner_sk = SecretKey::random();
+        let meta = XorName::random(&mut rand::thread_rng());
+        let mut register = Register::new(owner_sk.public_key(), meta, Permissions::default());
+        let continuation_address = register.address().continuation(owner_sk.public_key());
+        let mut continuation = Register::new(
+            owner_sk.public_key(),
+            continuation_address.meta(),
+            Permissions::default(),
+        );
+
+        let (first_hash, _) = register.write(vec![1], &BTreeSet::new(), &owner_sk)?;
+        let (second_hash, _) = register.write(vec![2], &BTreeSet::new(), &owner_sk)?;
+        // the third entry replaces the first one from the continuation register
+        let third_entry = ContinuationEntry {
+            value: vec![3],
+            previous_children: [first_hash].into_iter().collect(),
+        };
+        let (third_hash, _) =
+            continuation.write(third_entry.to_entry()?, &BTreeSet::new(), &owner_sk)?;
+
+        let entries = LinkedEntries::new([&register, &continuation]);
+        assert_eq!(entries.get(&third_hash), Some(&vec![3]));
+        assert_eq!(
+            entries.read(),
+            [(second_hash, vec![2]), (third_hash, vec![3])]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(entries.ancestors(&third_hash), vec![(first_hash, vec![1])]);
+        assert_eq!(
+            entries.descendants(&first_hash),
+            vec![(third_hash, vec![3])]
+        );
+        assert_eq!(
+            entries.diff(
+                &[second_hash].into_iter().collect(),
+                &[third_hash].into_iter().collect()
+            ),
+            vec![(first_hash, vec![1]), (third_hash, vec![3])]
+        );
+        let history = entries.history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.last(), Some(&(third_hash, vec![3])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn writing_past_a_full_register_continues_it() -> Result<()> {
+        let owner_sk = SecretKey::random();
+        let writer_sk = SecretKey::random();
+        let mut register = full_register(&owner_sk, writer_sk.public_key())?;
+        let mut continuations = vec![];
+        let latest: BTreeSet<_> = register.read().into_iter().map(|(hash, _)| hash).collect();
+
+        let mut linked = LinkedRegisters {
+            register: &mut register,
+            continuations: &mut continuations,
+            signer: &owner_sk,
+        };
+        let (first_hash, cmds) = linked.write_atop(vec![1], &latest)?;
+        assert!(matches!(
+            cmds.as_slice(),
+            [RegisterCmd::Create { .. }, RegisterCmd::Edit(_)]
+        ));
+        // the continuation of the owner is owned by them from the start
+        let (second_hash, cmds) =
+            linked.write_atop(vec![2], &[first_hash].into_iter().collect())?;
+        assert!(matches!(cmds.as_slice(), [RegisterCmd::Edit(_)]));
+
+        assert_eq!(continuations.len(), 1);
+        assert_eq!(
+            continuations[0].address(),
+            &register.address().continuation(owner_sk.public_key())
+        );
+        let entries = LinkedEntries::new(std::iter::once(&register).chain(&continuations));
+        assert_eq!(
+            entries.read(),
+            [(second_hash, vec![2])].into_iter().collect()
+        );
+        assert_eq!(
+            entries.ancestors(&first_hash).len(),
+            usize::from(MAX_REG_NUM_ENTRIES)
+        );
+        assert_eq!(
+            entries.history().len(),
+            usize::from(MAX_REG_NUM_ENTRIES) + 2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn writer_continues_a_full_register_owned_by_the_owner() -> Result<()> {
+        let owner_sk = SecretKey::random();
+        let writer_sk = SecretKey::random();
+        let mut register = full_register(&owner_sk, writer_sk.public_key())?;
+        let mut continuations = vec![];
+
+        let (_, cmds) = LinkedRegisters {
+            register: &mut register,
+            continuations: &mut continuations,
+            signer: &writer_sk,
+        }
+        .write_atop(vec![1], &BTreeSet::new())?;
+        assert!(matches!(
+            cmds.as_slice(),
+            [
+                RegisterCmd::Create { .. },
+                RegisterCmd::ChangePermissions(_),
+                RegisterCmd::Edit(_)
+            ]
+        ));
+
+        let continuation = &continuations[0];
+        assert_eq!(
+            continuation.address(),
+            &register.address().continuation(writer_sk.public_key())
+        );
+        // the later permission changes of the owner apply to the continuation
+        assert_eq!(continuation.current_owner(), owner_sk.public_key());
+        assert!(continuation
+            .clone()
+            .change_permissions(
+                PermissionChange::RemoveWriter(writer_sk.public_key()),
+                &owner_sk
+            )
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn entries_too_big_for_a_continuation_are_rejected_before_continuing() -> Result<()> {
+        let owner_sk = SecretKey::random();
+        let mut register = full_register(&owner_sk, SecretKey::random().public_key())?;
+        let mut continuations = vec![];
+        let latest: BTreeSet<_> = register.read().into_iter().map(|(hash, _)| hash).collect();
+
+        let result = LinkedRegisters {
+            register: &mut register,
+            continuations: &mut continuations,
+            signer: &owner_sk,
+        }
+        .write_atop(vec![0; MAX_REG_ENTRY_SIZE], &latest);
+        match result {
+            Err(crate::Error::Register(RegisterError::EntryTooBig { size, max })) => {
+                assert_eq!(size, MAX_REG_ENTRY_SIZE);
+                assert!(max < MAX_REG_ENTRY_SIZE);
+            }
+            other => panic!("Expected an EntryTooBig error, got {other:?}"),
+        }
+        assert!(continuations.is_empty());
+
+        Ok(())
+    }
+}
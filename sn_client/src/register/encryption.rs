@@ -0,0 +1,143 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{Error, Result};
+use aes_gcm_siv::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256GcmSiv, Key, Nonce,
+};
+use bls::{Ciphertext, PublicKey, SecretKey};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use sn_registers::{Entry, EntryHash, RegisterAddress};
+
+/// Prepended to the name of a Register to derive its encryption key from the owner's key.
+const ENCRYPTION_KEY_DOMAIN: &[u8] = b"safe_network register encryption key";
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// The number of bytes an encrypted entry takes on top of the plain one: a random nonce and the
+/// authentication tag. An encrypted Register thus holds entries of up to
+/// `MAX_REG_ENTRY_SIZE - ENCRYPTION_OVERHEAD`, i.e. 996 bytes.
+const ENCRYPTION_OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+/// The symmetric key the entries of an encrypted Register are encrypted with.
+#[derive(Clone, PartialEq, Eq)]
+pub(super) struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Derives the key of a Register from the secret key of its owner, who can derive it again
+    /// any time. It is the output of a one-way KDF, so sharing it with the writers doesn't
+    /// disclose anything about the owner's key.
+    pub(super) fn derive(owner_sk: &SecretKey, address: &RegisterAddress) -> Self {
+        let hkdf = Hkdf::<Sha256>::new(Some(ENCRYPTION_KEY_DOMAIN), &owner_sk.to_bytes());
+        let mut key = [0u8; 32];
+        // the output length is way below the HKDF limit of 255 hashes
+        let _ = hkdf.expand(&address.xorname().0, &mut key);
+        Self(key)
+    }
+
+    pub(super) fn encrypt_entry(&self, entry: &[u8]) -> Result<Entry> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), entry)
+            .map_err(|_| Error::RegisterEntryEncryption)?;
+
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(ciphertext);
+        Ok(encrypted)
+    }
+
+    pub(super) fn decrypt_entry(&self, hash: EntryHash, entry: &Entry) -> Result<Entry> {
+        if entry.len() < ENCRYPTION_OVERHEAD {
+            return Err(Error::RegisterEntryDecryption(hash));
+        }
+        let (nonce, ciphertext) = entry.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::RegisterEntryDecryption(hash))
+    }
+
+    /// Encrypts the key to the writer's key, for them to be able to read and write entries.
+    pub(super) fn share(&self, writer: &PublicKey) -> Ciphertext {
+        writer.encrypt(self.0)
+    }
+
+    /// Decrypts a key shared by the owner of the Register.
+    /// A key shared with another writer decrypts to a wrong key, which then fails to decrypt the entries.
+    pub(super) fn from_shared(shared_key: &Ciphertext, writer_sk: &SecretKey) -> Result<Self> {
+        let bytes = writer_sk
+            .decrypt(shared_key)
+            .ok_or(Error::InvalidRegisterEncryptionKey)?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| Error::InvalidRegisterEncryptionKey)?;
+        Ok(Self(key))
+    }
+
+    // Private helper
+    fn cipher(&self) -> Aes256GcmSiv {
+        Aes256GcmSiv::new(Key::<Aes256GcmSiv>::from_slice(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sn_registers::MAX_REG_ENTRY_SIZE;
+    use xor_name::XorName;
+
+    #[test]
+    fn shared_encryption_key_decrypts_the_entries() -> eyre::Result<()> {
+        let owner_sk = SecretKey::random();
+        let writer_sk = SecretKey::random();
+        let address = RegisterAddress::new(
+            XorName::random(&mut rand::thread_rng()),
+            owner_sk.public_key(),
+        );
+
+        let encryption_key = EncryptionKey::derive(&owner_sk, &address);
+        assert!(encryption_key == EncryptionKey::derive(&owner_sk, &address));
+
+        let entry = encryption_key.encrypt_entry(b"sensitive pointer")?;
+        assert_ne!(entry, b"sensitive pointer".to_vec());
+        assert_eq!(
+            entry.len(),
+            b"sensitive pointer".len() + ENCRYPTION_OVERHEAD
+        );
+        assert_eq!(
+            encryption_key
+                .encrypt_entry(&[0; MAX_REG_ENTRY_SIZE - ENCRYPTION_OVERHEAD])?
+                .len(),
+            MAX_REG_ENTRY_SIZE
+        );
+
+        let shared_key = encryption_key.share(&writer_sk.public_key());
+        let writer_key = EncryptionKey::from_shared(&shared_key, &writer_sk)?;
+        assert_eq!(
+            writer_key.decrypt_entry(EntryHash::default(), &entry)?,
+            b"sensitive pointer".to_vec()
+        );
+
+        // nobody else can decrypt the entries
+        let other_key = EncryptionKey::derive(&writer_sk, &address);
+        assert!(other_key
+            .decrypt_entry(EntryHash::default(), &entry)
+            .is_err());
+        let other_writer_key = EncryptionKey::from_shared(&shared_key, &SecretKey::random())?;
+        assert!(other_writer_key
+            .decrypt_entry(EntryHash::default(), &entry)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -37,6 +37,7 @@ pub struct UploadCfg {
     pub retry_strategy: RetryStrategy,
     pub max_repayments_for_failed_data: usize, // we want people to specify an explicit limit here.
     pub collect_registers: bool,
+    pub skip_existing_chunks: bool,
 }
 
 impl Default for UploadCfg {
@@ -48,6 +49,7 @@ impl Default for UploadCfg {
             retry_strategy: RetryStrategy::Balanced,
             max_repayments_for_failed_data: MAX_REPAYMENTS_PER_FAILED_ITEM,
             collect_registers: false,
+            skip_existing_chunks: false,
         }
     }
 }
@@ -62,6 +64,9 @@ pub struct UploadSummary {
     pub uploaded_registers: BTreeMap<RegisterAddress, ClientRegister>,
     pub uploaded_count: usize,
     pub skipped_count: usize,
+    /// The number of chunks found to be already stored on the network by the existence checks,
+    /// which were not paid for. These are also counted in `skipped_count`.
+    pub already_stored_count: usize,
 }
 
 impl UploadSummary {
@@ -87,6 +92,7 @@ impl UploadSummary {
             uploaded_registers: self.uploaded_registers,
             uploaded_count: self.uploaded_count + other.uploaded_count,
             skipped_count: self.skipped_count + other.skipped_count,
+            already_stored_count: self.already_stored_count + other.already_stored_count,
         };
         Ok(summary)
     }
@@ -232,6 +238,17 @@ impl Uploader {
             .set_collect_registers(collect_registers);
     }
 
+    /// Sets the option to check if the chunks are already stored on the network before getting their store
+    /// cost, in batches. The chunks that are found are neither paid for nor uploaded again.
+    ///
+    /// By default, this option is set to False
+    pub fn set_skip_existing_chunks(&mut self, skip_existing_chunks: bool) {
+        self.inner
+            .as_mut()
+            .expect("Uploader::new makes sure inner is present")
+            .set_skip_existing_chunks(skip_existing_chunks);
+    }
+
     /// Returns a receiver for UploadEvent.
     /// This method is optional and the upload process can be performed without it.
     pub fn get_event_receiver(&mut self) -> mpsc::Receiver<UploadEvent> {
@@ -287,6 +304,13 @@ trait UploaderInterface: Send + Sync {
         task_result_sender: mpsc::Sender<TaskResult>,
     );
 
+    fn submit_check_chunk_existence_task(
+        &mut self,
+        client: Client,
+        upload_item: UploadItem,
+        task_result_sender: mpsc::Sender<TaskResult>,
+    );
+
     #[allow(clippy::too_many_arguments)]
     fn submit_get_store_cost_task(
         &mut self,
@@ -346,6 +370,10 @@ impl InnerUploader {
         self.cfg.collect_registers = collect_registers;
     }
 
+    pub(super) fn set_skip_existing_chunks(&mut self, skip_existing_chunks: bool) {
+        self.cfg.skip_existing_chunks = skip_existing_chunks;
+    }
+
     pub(super) fn get_event_receiver(&mut self) -> mpsc::Receiver<UploadEvent> {
         let (tx, rx) = mpsc::channel(100);
         self.event_sender = Some(tx);
@@ -427,6 +455,10 @@ enum TaskResult {
         updated_register: ClientRegister,
     },
     PushRegisterErr(XorName),
+    CheckChunkExistenceOk {
+        xorname: XorName,
+        exists: bool,
+    },
     GetStoreCostOk {
         xorname: XorName,
         quote: Box<PayeeQuote>,
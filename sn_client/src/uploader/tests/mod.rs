@@ -144,6 +144,46 @@ async fn register_should_be_paid_and_uploaded_if_it_does_not_exists() -> Result<
     Ok(())
 }
 
+/// 5. Chunk: if the chunk exists during the existence checks, then skip its payment.
+#[tokio::test]
+async fn chunks_found_by_the_existence_checks_should_not_be_paid_for() -> Result<()> {
+    let _log_guards = LogBuilder::init_single_threaded_tokio_test("uploader", true);
+    let temp_dir = tempdir()?;
+    let (mut inner_uploader, task_result_rx) = get_inner_uploader(temp_dir.path().to_path_buf())?;
+
+    // cfg
+    inner_uploader.set_batch_size(1);
+    inner_uploader.set_skip_existing_chunks(true);
+    inner_uploader.insert_chunk_paths(get_dummy_chunk_paths(2, temp_dir.path().to_path_buf()));
+
+    // the path to test
+    let steps = vec![
+        TestSteps::CheckChunkExistenceOk { exists: true },
+        TestSteps::CheckChunkExistenceOk { exists: false },
+        TestSteps::GetStoreCostOk {
+            trigger_zero_cost: false,
+            assert_select_different_payee: false,
+        },
+        TestSteps::MakePaymentOk,
+        TestSteps::UploadItemOk,
+    ];
+
+    let (upload_handle, events_handle) =
+        start_uploading_with_steps(inner_uploader, VecDeque::from(steps), task_result_rx);
+
+    let summary = upload_handle.await??;
+    let events = events_handle.await?;
+
+    assert_eq!(summary.already_stored_count, 1);
+    assert_eq!(summary.skipped_count, 1);
+    assert_eq!(summary.uploaded_count, 1);
+    assert_eq!(events.len(), 3);
+    assert_matches!(events[0], UploadEvent::ChunkAlreadyExistsInNetwork(_));
+    assert_matches!(events[1], UploadEvent::PaymentMade { .. });
+    assert_matches!(events[2], UploadEvent::ChunkUploaded(..));
+    Ok(())
+}
+
 // ===== REPAYMENTS ======
 
 /// 1. Chunks: if upload task fails > threshold, then get store cost should be triggered with SelectDifferentStrategy
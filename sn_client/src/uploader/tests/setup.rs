@@ -133,6 +133,37 @@ impl UploaderInterface for TestUploader {
         }
     }
 
+    fn submit_check_chunk_existence_task(
+        &mut self,
+        _client: Client,
+        upload_item: UploadItem,
+        _task_result_sender: mpsc::Sender<TaskResult>,
+    ) {
+        let xorname = upload_item.xorname();
+        let step = self
+            .test_steps
+            .pop_front()
+            .expect("TestSteps are empty. Expected a CheckChunkExistence step.");
+        let handle = Handle::current();
+        let task_result_sender = self.task_result_sender.clone();
+
+        println!("spawn_check_chunk_existence called for: {xorname:?}. Step to execute: {step:?}");
+        info!(
+            "TEST: spawn_check_chunk_existence called for: {xorname:?}. Step to execute: {step:?}"
+        );
+        match step {
+            TestSteps::CheckChunkExistenceOk { exists } => {
+                handle.spawn(async move {
+                    task_result_sender
+                        .send(TaskResult::CheckChunkExistenceOk { xorname, exists })
+                        .await
+                        .expect("Failed to send task result");
+                });
+            }
+            con => panic!("Test failed: Expected CheckChunkExistence step. Got: {con:?}"),
+        }
+    }
+
     fn submit_get_store_cost_task(
         &mut self,
         _client: Client,
@@ -363,6 +394,9 @@ pub enum TestSteps {
     GetRegisterErr,
     PushRegisterOk,
     PushRegisterErr,
+    CheckChunkExistenceOk {
+        exists: bool,
+    },
     GetStoreCostOk {
         trigger_zero_cost: bool,
         assert_select_different_payee: bool,
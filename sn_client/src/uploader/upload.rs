@@ -85,18 +85,24 @@ pub(super) async fn start_upload(
         uploader.cfg.batch_size,
     )?;
 
-    // chunks can be pushed to pending_get_store_cost directly
-    uploader.pending_to_get_store_cost = uploader
+    // chunks can be pushed to pending_get_store_cost directly, unless we check if they exist first.
+    let chunk_xornames = uploader
         .all_upload_items
         .iter()
         .filter_map(|(xorname, item)| {
             if let UploadItem::Chunk { .. } = item {
-                Some((*xorname, GetStoreCostStrategy::Cheapest))
+                Some(*xorname)
             } else {
                 None
             }
-        })
-        .collect();
+        });
+    if uploader.cfg.skip_existing_chunks {
+        uploader.pending_to_check_existence = chunk_xornames.collect();
+    } else {
+        uploader.pending_to_get_store_cost = chunk_xornames
+            .map(|xorname| (xorname, GetStoreCostStrategy::Cheapest))
+            .collect();
+    }
 
     // registers have to be verified + merged with remote replica, so we have to fetch it first.
     uploader.pending_to_get_register = uploader
@@ -129,6 +135,7 @@ pub(super) async fn start_upload(
                 uploaded_addresses: uploader.uploaded_addresses,
                 uploaded_count: uploader.uploaded_count,
                 skipped_count: uploader.skipped_count,
+                already_stored_count: uploader.already_stored_count,
                 uploaded_registers: uploader.uploaded_registers,
             };
 
@@ -183,6 +190,26 @@ pub(super) async fn start_upload(
             );
         }
 
+        // try to check if a chunk already exists in the network if we have enough buffer.
+        // The chunks that do not exist are pushed to `pending_to_get_store_cost`.
+        while !uploader.pending_to_check_existence.is_empty()
+            && uploader.on_going_check_existence.len() < uploader.cfg.batch_size
+        {
+            let upload_item = uploader.pop_item_for_check_existence()?;
+            trace!(
+                "Conditions met for check chunk existence {:?}",
+                upload_item.xorname()
+            );
+            let _ = uploader
+                .on_going_check_existence
+                .insert(upload_item.xorname());
+            interface.submit_check_chunk_existence_task(
+                uploader.client.clone(),
+                upload_item,
+                task_result_sender.clone(),
+            );
+        }
+
         // try to get store cost for an item if pending_to_pay needs items & if we have enough buffer.
         while !uploader.pending_to_get_store_cost.is_empty()
             && uploader.on_going_get_cost.len() < uploader.cfg.batch_size
@@ -244,6 +271,8 @@ pub(super) async fn start_upload(
         // to fill up the buffer.
         if uploader.pending_to_get_store_cost.is_empty()
             && uploader.on_going_get_cost.is_empty()
+            && uploader.pending_to_check_existence.is_empty()
+            && uploader.on_going_check_existence.is_empty()
             && !uploader.on_going_payments.is_empty()
             && uploader.on_going_payments.len() < uploader.cfg.batch_size
         {
@@ -322,6 +351,30 @@ pub(super) async fn start_upload(
                     return Err(ClientError::SequentialNetworkErrors);
                 }
             }
+            TaskResult::CheckChunkExistenceOk { xorname, exists } => {
+                let _ = uploader.on_going_check_existence.remove(&xorname);
+
+                if exists {
+                    // remove the item since it is already stored, no payment is needed.
+                    let removed_item = uploader
+                        .all_upload_items
+                        .remove(&xorname)
+                        .ok_or(ClientError::UploadableItemNotFound(xorname))?;
+                    let _ = uploader.uploaded_addresses.insert(removed_item.address());
+                    trace!("{xorname:?} already exists on the network, skipping its payment");
+                    uploader.skipped_count += 1;
+                    uploader.already_stored_count += 1;
+
+                    if let UploadItem::Chunk { address, .. } = removed_item {
+                        uploader
+                            .emit_upload_event(UploadEvent::ChunkAlreadyExistsInNetwork(address));
+                    }
+                } else {
+                    uploader
+                        .pending_to_get_store_cost
+                        .push((xorname, GetStoreCostStrategy::Cheapest));
+                }
+            }
             TaskResult::GetStoreCostOk { xorname, quote } => {
                 let _ = uploader.on_going_get_cost.remove(&xorname);
                 uploader.get_store_cost_errors = 0; // reset error if Ok. We only throw error after 'n' sequential errors
@@ -578,6 +631,32 @@ impl UploaderInterface for Uploader {
         });
     }
 
+    fn submit_check_chunk_existence_task(
+        &mut self,
+        client: Client,
+        upload_item: UploadItem,
+        task_result_sender: mpsc::Sender<TaskResult>,
+    ) {
+        let xorname = upload_item.xorname();
+        trace!("Spawning check_chunk_existence for {xorname:?}");
+        let _handle = tokio::spawn(async move {
+            let exists = match InnerUploader::check_chunk_existence(client, upload_item).await {
+                Ok(()) => {
+                    debug!("Chunk {xorname:?} already exists in the network");
+                    true
+                }
+                Err(err) => {
+                    // the chunk is uploaded as usual if we could not get a proof for it
+                    trace!("Chunk {xorname:?} not found in the network: {err:?}");
+                    false
+                }
+            };
+            let _ = task_result_sender
+                .send(TaskResult::CheckChunkExistenceOk { xorname, exists })
+                .await;
+        });
+    }
+
     fn submit_push_register_task(
         &mut self,
         upload_item: UploadItem,
@@ -667,6 +746,7 @@ pub(super) struct InnerUploader {
     pub(super) all_upload_items: HashMap<XorName, UploadItem>,
     pub(super) pending_to_get_register: Vec<RegisterAddress>,
     pub(super) pending_to_push_register: Vec<XorName>,
+    pub(super) pending_to_check_existence: Vec<XorName>,
     pub(super) pending_to_get_store_cost: Vec<(XorName, GetStoreCostStrategy)>,
     pub(super) pending_to_pay: Vec<(XorName, Box<PayeeQuote>)>,
     pub(super) pending_to_upload: Vec<XorName>,
@@ -674,6 +754,7 @@ pub(super) struct InnerUploader {
     // trackers
     pub(super) on_going_get_register: BTreeSet<XorName>,
     pub(super) on_going_push_register: BTreeSet<XorName>,
+    pub(super) on_going_check_existence: BTreeSet<XorName>,
     pub(super) on_going_get_cost: BTreeSet<XorName>,
     pub(super) on_going_payments: BTreeSet<XorName>,
     pub(super) on_going_uploads: BTreeSet<XorName>,
@@ -693,6 +774,7 @@ pub(super) struct InnerUploader {
     pub(super) uploaded_registers: BTreeMap<RegisterAddress, ClientRegister>,
     pub(super) uploaded_count: usize,
     pub(super) skipped_count: usize,
+    pub(super) already_stored_count: usize,
 
     // Task channels for testing. Not used in actual code.
     pub(super) testing_task_channels:
@@ -716,12 +798,14 @@ impl InnerUploader {
             all_upload_items: Default::default(),
             pending_to_get_register: Default::default(),
             pending_to_push_register: Default::default(),
+            pending_to_check_existence: Default::default(),
             pending_to_get_store_cost: Default::default(),
             pending_to_pay: Default::default(),
             pending_to_upload: Default::default(),
 
             on_going_get_register: Default::default(),
             on_going_push_register: Default::default(),
+            on_going_check_existence: Default::default(),
             on_going_get_cost: Default::default(),
             on_going_payments: Default::default(),
             on_going_uploads: Default::default(),
@@ -739,6 +823,7 @@ impl InnerUploader {
             uploaded_registers: Default::default(),
             uploaded_count: Default::default(),
             skipped_count: Default::default(),
+            already_stored_count: Default::default(),
 
             testing_task_channels: None,
             logged_event_sender_absence: Default::default(),
@@ -762,6 +847,20 @@ impl InnerUploader {
         }
     }
 
+    fn pop_item_for_check_existence(&mut self) -> Result<UploadItem> {
+        if let Some(name) = self.pending_to_check_existence.pop() {
+            let upload_item = self
+                .all_upload_items
+                .get(&name)
+                .cloned()
+                .ok_or(ClientError::UploadableItemNotFound(name))?;
+            Ok(upload_item)
+        } else {
+            // the caller will be making sure this does not happen.
+            Err(ClientError::UploadStateTrackerIsEmpty)
+        }
+    }
+
     fn pop_item_for_get_store_cost(
         &mut self,
     ) -> Result<(XorName, NetworkAddress, GetStoreCostStrategy)> {
@@ -939,6 +1038,21 @@ impl InnerUploader {
         Ok(reg)
     }
 
+    async fn check_chunk_existence(client: Client, upload_item: UploadItem) -> Result<()> {
+        let chunk = match upload_item {
+            UploadItem::Chunk {
+                chunk: Either::Left(chunk),
+                ..
+            } => chunk,
+            UploadItem::Chunk {
+                chunk: Either::Right(path),
+                ..
+            } => Chunk::new(Bytes::from(std::fs::read(path)?)),
+            UploadItem::Register { .. } => return Err(ClientError::InvalidUploadItemFound),
+        };
+        client.quickly_check_if_chunk_stored(&chunk).await
+    }
+
     async fn get_store_cost(
         client: Client,
         wallet_api: WalletApi,
@@ -338,6 +338,37 @@ impl WalletClient {
         }
     }
 
+    /// Send a vesting grant to a given public key, as one CashNote per tranche which the recipient
+    /// cannot spend before its unlock time, in seconds since the Unix epoch.
+    /// Can optionally verify the store has been successful (this will attempt to GET the cash_note from the network)
+    pub async fn send_vesting_grant(
+        &mut self,
+        to: MainPubkey,
+        tranches: Vec<(NanoTokens, u64)>,
+        verify_store: bool,
+    ) -> WalletResult<Vec<CashNote>> {
+        let created_cash_notes = self.wallet.local_send_vesting_grant(to, tranches, None)?;
+
+        // send to network
+        if let Err(error) = self
+            .client
+            .send_spends(
+                self.wallet.unconfirmed_spend_requests().iter(),
+                verify_store,
+            )
+            .await
+        {
+            return Err(WalletError::CouldNotSendMoney(format!(
+                "The transfer was not successfully registered in the network: {error:?}"
+            )));
+        } else {
+            // clear unconfirmed txs
+            self.wallet.clear_confirmed_spend_requests();
+        }
+
+        Ok(created_cash_notes)
+    }
+
     /// Send signed spends to another wallet.
     /// Can optionally verify if the store has been successful.
     /// Verification will be attempted via GET request through a Spend on the network.
@@ -18,12 +18,15 @@ use faucet_server::{restart_faucet_server, run_faucet_server};
 use indicatif::ProgressBar;
 use sn_client::{
     acc_packet::load_account_wallet_or_create_with_mnemonic, fund_faucet_from_genesis_wallet, send,
-    Client, ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver,
+    Client, ClientEvent, ClientEventsBroadcaster, ClientEventsReceiver, WalletClient,
 };
 use sn_logging::{Level, LogBuilder, LogOutputDest};
 use sn_peers_acquisition::PeersArgs;
 use sn_transfers::{get_faucet_data_dir, HotWallet, MainPubkey, NanoTokens, Transfer};
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
 use tracing::{debug, error, info};
 
@@ -185,6 +188,23 @@ enum SubCmd {
         #[clap(name = "to")]
         to: String,
     },
+    /// Send a vesting grant, split in tranches unlocking one after the other.
+    /// The recipient cannot spend a tranche before its unlock time.
+    VestingGrant {
+        /// This shall be the number of nanos to grant in total.
+        #[clap(name = "amount")]
+        amount: String,
+        /// This must be a hex-encoded `MainPubkey`.
+        #[clap(name = "to")]
+        to: String,
+        /// The number of tranches the grant is split in.
+        #[clap(long, default_value = "4")]
+        tranches: u64,
+        /// The number of seconds between the unlocking of two tranches, the first one
+        /// unlocking one interval from now. Defaults to 30 days.
+        #[clap(long, default_value = "2592000")]
+        interval: u64,
+    },
     /// Starts an http server that will send tokens to anyone who requests them.
     /// curl http://localhost:8000/your-hex-encoded-wallet-public-address
     Server,
@@ -207,6 +227,14 @@ async fn faucet_cmds(cmds: SubCmd, client: &Client, funded_wallet: HotWallet) ->
         SubCmd::Send { amount, to } => {
             send_tokens(client, funded_wallet, &amount, &to).await?;
         }
+        SubCmd::VestingGrant {
+            amount,
+            to,
+            tranches,
+            interval,
+        } => {
+            send_vesting_grant(client, funded_wallet, &amount, &to, tranches, interval).await?;
+        }
         SubCmd::Server => {
             // shouldn't return except on error
             run_faucet_server(client).await?;
@@ -251,6 +279,58 @@ async fn send_tokens(client: &Client, from: HotWallet, amount: &str, to: &str) -
     Ok(transfer_hex)
 }
 
+/// returns the hex-encoded transfers, one per tranche
+async fn send_vesting_grant(
+    client: &Client,
+    from: HotWallet,
+    amount: &str,
+    to: &str,
+    tranches: u64,
+    interval: u64,
+) -> Result<Vec<String>> {
+    let to = MainPubkey::from_hex(to)?;
+    use std::str::FromStr;
+    let amount = NanoTokens::from_str(amount)?.as_nano();
+    if amount == 0 || tranches == 0 || tranches > amount {
+        return Err(eyre!(
+            "Invalid amount or number of tranches passed in. Nothing sent."
+        ));
+    }
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    // the remainder of the split goes to the last tranche
+    let tranche_amount = amount / tranches;
+    let schedule = (1..=tranches)
+        .map(|i| {
+            let tranche = if i == tranches {
+                amount - tranche_amount * (tranches - 1)
+            } else {
+                tranche_amount
+            };
+            let unlock_time = i
+                .checked_mul(interval)
+                .and_then(|delay| now.checked_add(delay))
+                .ok_or_else(|| {
+                    eyre!("The unlock time of tranche {i} is out of range. Nothing sent.")
+                })?;
+            Ok((NanoTokens::from(tranche), unlock_time))
+        })
+        .collect::<Result<_>>()?;
+
+    let mut wallet_client = WalletClient::new(client.clone(), from);
+    let cash_notes = wallet_client.send_vesting_grant(to, schedule, true).await?;
+    let mut transfers = vec![];
+    for cash_note in cash_notes {
+        let transfer_hex = Transfer::transfer_from_cash_note(&cash_note)?.to_hex()?;
+        println!("{transfer_hex}");
+        transfers.push(transfer_hex);
+    }
+
+    Ok(transfers)
+}
+
 fn parse_log_output(val: &str) -> Result<LogOutputDest> {
     match val {
         "stdout" => Ok(LogOutputDest::Stdout),
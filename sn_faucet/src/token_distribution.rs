@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::send_tokens;
+use crate::{send_tokens, send_vesting_grant};
 #[cfg(feature = "distribution")]
 use base64::Engine;
 use color_eyre::eyre::{eyre, Result};
@@ -23,6 +23,13 @@ const SNAPSHOT_URL: &str = "https://api.omniexplorer.info/ask.aspx?api=getproper
 const CLAIMS_URL: &str =
     "https://github.com/maidsafe/safe_network/raw/main/sn_faucet/maid_address_claims.csv";
 const HTTP_STATUS_OK: i32 = 200;
+/// Environment variable setting the number of tranches the distributions are vested in.
+/// The distributions are sent at once when it is not set.
+const VESTING_TRANCHES_ENV: &str = "DISTRIBUTION_VESTING_TRANCHES";
+/// Environment variable setting the number of seconds between the unlocking of two tranches.
+const VESTING_INTERVAL_ENV: &str = "DISTRIBUTION_VESTING_INTERVAL";
+/// Defaults to 30 days.
+const DEFAULT_VESTING_INTERVAL: u64 = 30 * 24 * 60 * 60;
 
 type MaidAddress = String; // base58 encoded
 type Snapshot = HashMap<MaidAddress, NanoTokens>;
@@ -449,24 +456,36 @@ async fn create_distribution(
 
     let faucet_dir = get_faucet_data_dir();
     let faucet_wallet = load_account_wallet_or_create_with_mnemonic(&faucet_dir, None)?;
-    // create a transfer to the claim wallet
-    let transfer_hex =
-        match send_tokens(client, faucet_wallet, &amount.to_string(), &claim.wallet).await {
-            Ok(t) => t,
-            Err(err) => {
-                let msg = format!("Failed send for {0}: {err}", claim.address);
-                info!(msg);
-                return Err(eyre!(msg));
-            }
-        };
-    let _ = match hex::decode(transfer_hex.clone()) {
+    // create a transfer to the claim wallet, or one per tranche if the distributions are vested
+    let amount = amount.to_string();
+    let sent = match vesting_schedule()? {
+        Some((tranches, interval)) => send_vesting_grant(
+            client,
+            faucet_wallet,
+            &amount,
+            &claim.wallet,
+            tranches,
+            interval,
+        )
+        .await
+        .map(|transfers| transfers.join("\n")),
+        None => send_tokens(client, faucet_wallet, &amount, &claim.wallet).await,
+    };
+    let transfer_hex = match sent {
         Ok(t) => t,
         Err(err) => {
-            let msg = format!("Failed to decode transfer for {0}: {err}", claim.address);
+            let msg = format!("Failed send for {0}: {err}", claim.address);
             info!(msg);
             return Err(eyre!(msg));
         }
     };
+    for transfer in transfer_hex.lines() {
+        if let Err(err) = hex::decode(transfer) {
+            let msg = format!("Failed to decode transfer for {0}: {err}", claim.address);
+            info!(msg);
+            return Err(eyre!(msg));
+        }
+    }
     // save the transfer
     match std::fs::write(dist_path.clone(), transfer_hex.clone()) {
         Ok(_) => {}
@@ -485,6 +504,19 @@ async fn create_distribution(
     Ok(transfer_hex)
 }
 
+/// The number of tranches and the interval between them the distributions are vested in, if any.
+fn vesting_schedule() -> Result<Option<(u64, u64)>> {
+    let tranches = match std::env::var(VESTING_TRANCHES_ENV) {
+        Ok(tranches) => tranches.parse::<u64>()?,
+        Err(_) => return Ok(None),
+    };
+    let interval = match std::env::var(VESTING_INTERVAL_ENV) {
+        Ok(interval) => interval.parse::<u64>()?,
+        Err(_) => DEFAULT_VESTING_INTERVAL,
+    };
+    Ok(Some((tranches, interval)))
+}
+
 #[cfg(all(test, feature = "distribution"))]
 mod tests {
     use super::*;
@@ -0,0 +1,190 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    persist::persist_in_background,
+    target_arch::{SystemTime, UNIX_EPOCH},
+};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The file the address book is persisted to, under the root dir of the node.
+pub(crate) const ADDRESS_BOOK_FILENAME: &str = "address_book";
+
+/// Interval over which the peers of the routing table are snapshotted into the address book.
+pub(crate) const ADDRESS_BOOK_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The peers not seen for longer than this are unlikely to still be reachable at their addresses.
+const ADDRESS_BOOK_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The address book keeps the most recently seen peers only, beyond this many.
+const MAX_ADDRESS_BOOK_PEERS: usize = 500;
+
+/// The number of cached peers handed out to rejoin the network, the most recently seen first.
+const MAX_CACHED_PEERS: usize = 50;
+
+/// The known-good peers along with their addresses and when they were last seen.
+pub(crate) type AddressBook = HashMap<PeerId, (Vec<Multiaddr>, SystemTime)>;
+
+#[derive(Serialize, Deserialize)]
+struct PersistedPeer {
+    peer_id: Vec<u8>,
+    addrs: Vec<Vec<u8>>,
+    /// The seconds since the UNIX epoch the peer was last seen at
+    last_seen: u64,
+}
+
+fn is_expired(last_seen: SystemTime) -> bool {
+    SystemTime::now()
+        .duration_since(last_seen)
+        .unwrap_or_default()
+        > ADDRESS_BOOK_EXPIRY
+}
+
+/// Leaves out the expired peers, then the least recently seen ones beyond the capacity.
+pub(crate) fn prune_address_book(address_book: &mut AddressBook) {
+    address_book.retain(|_, (addrs, last_seen)| !addrs.is_empty() && !is_expired(*last_seen));
+
+    if address_book.len() > MAX_ADDRESS_BOOK_PEERS {
+        let mut last_seen_times: Vec<_> = address_book
+            .values()
+            .map(|(_, last_seen)| *last_seen)
+            .collect();
+        last_seen_times.sort_unstable_by(|a, b| b.cmp(a));
+        let oldest_kept = last_seen_times[MAX_ADDRESS_BOOK_PEERS - 1];
+        address_book.retain(|_, (_, last_seen)| *last_seen >= oldest_kept);
+    }
+}
+
+/// Restores the address book persisted by a previous run, leaving out the expired peers.
+pub(crate) fn load_address_book(file_path: &Path) -> AddressBook {
+    let Ok(file) = fs::File::open(file_path) else {
+        return Default::default();
+    };
+    let persisted: Vec<PersistedPeer> = match rmp_serde::from_read(&file) {
+        Ok(persisted) => persisted,
+        Err(err) => {
+            warn!("Failed to deserialize the address book at {file_path:?}: {err:?}");
+            return Default::default();
+        }
+    };
+
+    let mut address_book: AddressBook = persisted
+        .into_iter()
+        .filter_map(|peer| {
+            let peer_id = PeerId::from_bytes(&peer.peer_id).ok()?;
+            let addrs = peer
+                .addrs
+                .into_iter()
+                .filter_map(|addr| Multiaddr::try_from(addr).ok())
+                .collect();
+            let last_seen = UNIX_EPOCH + Duration::from_secs(peer.last_seen);
+            Some((peer_id, (addrs, last_seen)))
+        })
+        .collect();
+    prune_address_book(&mut address_book);
+    info!(
+        "Restored {} peers from the address book at {file_path:?}",
+        address_book.len()
+    );
+    address_book
+}
+
+/// Writes the address book to the disk in the background.
+pub(crate) fn persist_address_book(file_path: PathBuf, address_book: &AddressBook) {
+    let persisted: Vec<_> = address_book
+        .iter()
+        .map(|(peer_id, (addrs, last_seen))| PersistedPeer {
+            peer_id: peer_id.to_bytes(),
+            addrs: addrs.iter().map(|addr| addr.to_vec()).collect(),
+            last_seen: last_seen
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+        .collect();
+
+    persist_in_background(file_path, persisted);
+}
+
+/// The addresses of the peers cached by a previous run of the node at `root_dir`, to rejoin the
+/// network through. The most recently seen peers come first.
+pub fn read_cached_peers(root_dir: &Path) -> Vec<Multiaddr> {
+    let address_book = load_address_book(&root_dir.join(ADDRESS_BOOK_FILENAME));
+
+    let mut peers: Vec<_> = address_book.into_iter().collect();
+    peers.sort_by(|(_, (_, a)), (_, (_, b))| b.cmp(a));
+    peers
+        .into_iter()
+        .take(MAX_CACHED_PEERS)
+        .flat_map(|(peer_id, (addrs, _))| {
+            addrs
+                .into_iter()
+                .map(move |addr| with_peer_id(addr, peer_id))
+        })
+        .collect()
+}
+
+/// The peer id has to be part of the address for the peer to be dialed and added to the RT.
+fn with_peer_id(addr: Multiaddr, peer_id: PeerId) -> Multiaddr {
+    match addr.iter().last() {
+        Some(Protocol::P2p(_)) => addr,
+        _ => addr.with(Protocol::P2p(peer_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persist::snapshots_written;
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn cached_peers_are_restored_most_recently_seen_first() -> Result<()> {
+        let root_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&root_dir)?;
+        let file_path = root_dir.join(ADDRESS_BOOK_FILENAME);
+
+        let recent_peer = PeerId::random();
+        let older_peer = PeerId::random();
+        let expired_peer = PeerId::random();
+        let addr: Multiaddr = "/ip4/1.2.3.4/udp/1200/quic-v1".parse()?;
+        let now = SystemTime::now();
+        let mut address_book = AddressBook::new();
+        let _ = address_book.insert(
+            older_peer,
+            (vec![addr.clone()], now - Duration::from_secs(60)),
+        );
+        let _ = address_book.insert(recent_peer, (vec![addr.clone()], now));
+        let _ = address_book.insert(
+            expired_peer,
+            (vec![addr.clone()], now - ADDRESS_BOOK_EXPIRY * 2),
+        );
+
+        persist_address_book(file_path.clone(), &address_book);
+        snapshots_written(&file_path).await;
+
+        let cached_peers = read_cached_peers(&root_dir);
+        assert_eq!(
+            cached_peers,
+            vec![
+                addr.clone().with(Protocol::P2p(recent_peer)),
+                addr.with(Protocol::P2p(older_peer)),
+            ]
+        );
+
+        fs::remove_dir_all(root_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    cmd::NodeIssue,
+    driver::BadNodes,
+    persist::persist_in_background,
+    target_arch::{SystemTime, UNIX_EPOCH},
+};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The file the bad nodes are persisted to, under the root dir of the node.
+pub(crate) const BAD_NODES_FILENAME: &str = "bad_nodes";
+
+/// The issues of a node older than this are forgotten, unless it got considered as bad.
+pub(crate) const NODE_ISSUE_DECAY: Duration = Duration::from_secs(300);
+
+/// How long a node is considered as bad after its last issue, before it gets another chance.
+pub(crate) const BAD_NODE_EXPIRY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The issues recorded against a peer, and whether it is considered as bad because of them.
+#[derive(Clone, Debug)]
+pub struct BadNodeInfo {
+    pub peer_id: PeerId,
+    /// The issues along with the time they were recorded, the oldest first
+    pub issues: Vec<(NodeIssue, SystemTime)>,
+    pub is_bad: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedBadNode {
+    peer_id: Vec<u8>,
+    /// The issues along with the seconds since the UNIX epoch they were recorded at
+    issues: Vec<(NodeIssue, u64)>,
+    is_bad: bool,
+}
+
+pub(crate) fn elapsed_since(timestamp: SystemTime) -> Duration {
+    SystemTime::now()
+        .duration_since(timestamp)
+        .unwrap_or_default()
+}
+
+/// A node is forgotten once its last issue has decayed, or once it has been bad for long enough.
+pub(crate) fn is_expired(issues: &[(NodeIssue, SystemTime)], is_bad: bool) -> bool {
+    let Some((_, last_issue)) = issues.last() else {
+        return !is_bad;
+    };
+    let ttl = if is_bad {
+        BAD_NODE_EXPIRY
+    } else {
+        NODE_ISSUE_DECAY
+    };
+    elapsed_since(*last_issue) > ttl
+}
+
+pub(crate) fn bad_nodes_info(bad_nodes: &BadNodes) -> Vec<BadNodeInfo> {
+    bad_nodes
+        .iter()
+        .map(|(peer_id, (issues, is_bad))| BadNodeInfo {
+            peer_id: *peer_id,
+            issues: issues.clone(),
+            is_bad: *is_bad,
+        })
+        .collect()
+}
+
+/// Restores the bad nodes persisted by a previous run, leaving out the expired ones.
+pub(crate) fn load_bad_nodes(file_path: &Path) -> BadNodes {
+    let Ok(file) = fs::File::open(file_path) else {
+        return Default::default();
+    };
+    let persisted: Vec<PersistedBadNode> = match rmp_serde::from_read(&file) {
+        Ok(persisted) => persisted,
+        Err(err) => {
+            warn!("Failed to deserialize the bad nodes at {file_path:?}: {err:?}");
+            return Default::default();
+        }
+    };
+
+    let bad_nodes: BadNodes = persisted
+        .into_iter()
+        .filter_map(|node| {
+            let peer_id = PeerId::from_bytes(&node.peer_id).ok()?;
+            let issues: Vec<_> = node
+                .issues
+                .into_iter()
+                .map(|(issue, secs)| (issue, UNIX_EPOCH + Duration::from_secs(secs)))
+                .collect();
+            if is_expired(&issues, node.is_bad) {
+                return None;
+            }
+            Some((peer_id, (issues, node.is_bad)))
+        })
+        .collect();
+    info!(
+        "Restored {} bad nodes from {file_path:?}",
+        bad_nodes.values().filter(|(_, is_bad)| *is_bad).count()
+    );
+    bad_nodes
+}
+
+/// Writes the bad nodes to the disk in the background.
+pub(crate) fn persist_bad_nodes(file_path: PathBuf, bad_nodes: &BadNodes) {
+    let persisted: Vec<_> = bad_nodes
+        .iter()
+        .map(|(peer_id, (issues, is_bad))| PersistedBadNode {
+            peer_id: peer_id.to_bytes(),
+            issues: issues
+                .iter()
+                .map(|(issue, timestamp)| {
+                    let secs = timestamp
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    (issue.clone(), secs)
+                })
+                .collect(),
+            is_bad: *is_bad,
+        })
+        .collect();
+
+    persist_in_background(file_path, persisted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persist::snapshots_written;
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn bad_nodes_are_restored_without_the_expired_ones() -> Result<()> {
+        let root_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&root_dir)?;
+        let file_path = root_dir.join(BAD_NODES_FILENAME);
+
+        let bad_peer = PeerId::random();
+        let expired_bad_peer = PeerId::random();
+        let decayed_peer = PeerId::random();
+        let now = SystemTime::now();
+        let mut bad_nodes = BadNodes::new();
+        let _ = bad_nodes.insert(bad_peer, (vec![(NodeIssue::BadQuoting, now)], true));
+        let _ = bad_nodes.insert(
+            expired_bad_peer,
+            (
+                vec![(NodeIssue::BadQuoting, now - BAD_NODE_EXPIRY * 2)],
+                true,
+            ),
+        );
+        let _ = bad_nodes.insert(
+            decayed_peer,
+            (
+                vec![(NodeIssue::ConnectionIssue, now - NODE_ISSUE_DECAY * 2)],
+                false,
+            ),
+        );
+
+        persist_bad_nodes(file_path.clone(), &bad_nodes);
+        snapshots_written(&file_path).await;
+
+        let restored = load_bad_nodes(&file_path);
+        assert_eq!(restored.len(), 1);
+        let (issues, is_bad) = restored.get(&bad_peer).expect("bad peer to be restored");
+        assert!(is_bad);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, NodeIssue::BadQuoting);
+
+        fs::remove_dir_all(root_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::target_arch::Instant;
+use futures::{ready, AsyncRead, AsyncWrite, Future};
+use libp2p::{
+    core::{
+        muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent, SubstreamBox},
+        transport::Boxed,
+    },
+    PeerId, Transport as _,
+};
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep, Sleep};
+
+/// The throttled substreams wait for at least this many bytes to be allowed,
+/// instead of waking up for every single byte.
+const MIN_THROTTLED_CHUNK: u64 = 1024;
+
+/// The current rates are averaged over this long.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// The bandwidth used by the node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandwidthUsage {
+    /// Bytes sent since the node started
+    pub bytes_sent: u64,
+    /// Bytes received since the node started
+    pub bytes_received: u64,
+    /// Bytes sent per second, over the last few seconds
+    pub upload_rate: u64,
+    /// Bytes received per second, over the last few seconds
+    pub download_rate: u64,
+}
+
+/// Accounts for the bandwidth used by all the connections of the node,
+/// throttling them to stay within the upload and download caps, if any.
+///
+/// It sits above the stream muxer, so only the payloads of the substreams are accounted and
+/// throttled. The framing and keep-alives of the muxer, the transport handshakes and the QUIC
+/// packets overhead are not, so the bandwidth really used is somewhat above the caps.
+#[derive(Debug)]
+pub(crate) struct BandwidthLimiter {
+    upload: Mutex<Direction>,
+    download: Mutex<Direction>,
+}
+
+impl BandwidthLimiter {
+    /// The caps are in bytes per second, no cap is applied when None.
+    pub(crate) fn new(max_upload: Option<u64>, max_download: Option<u64>) -> Self {
+        Self {
+            upload: Mutex::new(Direction::new(max_upload)),
+            download: Mutex::new(Direction::new(max_download)),
+        }
+    }
+
+    pub(crate) fn usage(&self) -> BandwidthUsage {
+        let mut upload = lock(&self.upload);
+        let mut download = lock(&self.download);
+        BandwidthUsage {
+            bytes_sent: upload.total,
+            bytes_received: download.total,
+            upload_rate: upload.current_rate(),
+            download_rate: download.current_rate(),
+        }
+    }
+
+    /// Wraps the muxer of every connection of the transport, so that all their substreams
+    /// get accounted and throttled.
+    pub(crate) fn throttle(
+        self: &Arc<Self>,
+        transport: Boxed<(PeerId, StreamMuxerBox)>,
+    ) -> Boxed<(PeerId, StreamMuxerBox)> {
+        let limiter = Arc::clone(self);
+        transport
+            .map(move |(peer_id, muxer), _| {
+                let muxer = ThrottledMuxer {
+                    inner: muxer,
+                    limiter: Arc::clone(&limiter),
+                };
+                (peer_id, StreamMuxerBox::new(muxer))
+            })
+            .boxed()
+    }
+}
+
+fn lock(direction: &Mutex<Direction>) -> MutexGuard<'_, Direction> {
+    // The counters stay consistent even if a panic happened while holding the lock.
+    direction
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The accounting of one direction of the traffic.
+#[derive(Debug)]
+struct Direction {
+    bucket: Option<TokenBucket>,
+    total: u64,
+    window_start: Instant,
+    window_bytes: u64,
+    rate: u64,
+}
+
+impl Direction {
+    fn new(max_rate: Option<u64>) -> Self {
+        Self {
+            bucket: max_rate.filter(|rate| *rate > 0).map(TokenBucket::new),
+            total: 0,
+            window_start: Instant::now(),
+            window_bytes: 0,
+            rate: 0,
+        }
+    }
+
+    /// How many of the `wanted` bytes can go through now.
+    /// Returns how long to wait instead if none can.
+    fn allowance(&mut self, wanted: usize) -> Result<usize, Duration> {
+        let Some(bucket) = self.bucket.as_mut() else {
+            return Ok(wanted);
+        };
+        let chunk = (wanted as u64).min(MIN_THROTTLED_CHUNK).min(bucket.rate);
+        let available = bucket.available();
+        if available >= chunk {
+            Ok(wanted.min(available as usize))
+        } else {
+            Err(bucket.time_to_refill(chunk))
+        }
+    }
+
+    fn record(&mut self, bytes: usize) {
+        if let Some(bucket) = self.bucket.as_mut() {
+            bucket.consume(bytes as u64);
+        }
+        self.total = self.total.saturating_add(bytes as u64);
+        self.window_bytes = self.window_bytes.saturating_add(bytes as u64);
+        self.roll_window();
+    }
+
+    fn current_rate(&mut self) -> u64 {
+        self.roll_window();
+        self.rate
+    }
+
+    fn roll_window(&mut self) {
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= RATE_WINDOW {
+            self.rate = (self.window_bytes as f64 / elapsed.as_secs_f64()) as u64;
+            self.window_bytes = 0;
+            self.window_start = Instant::now();
+        }
+    }
+}
+
+/// Refilled at `rate` bytes per second, holding up to a second worth of bytes.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn available(&mut self) -> u64 {
+        let now = Instant::now();
+        let refill = now.duration_since(self.last_refill).as_secs_f64() * self.rate as f64;
+        self.tokens = (self.tokens + refill).min(self.rate as f64);
+        self.last_refill = now;
+        self.tokens.max(0.0) as u64
+    }
+
+    /// The tokens can go negative when concurrent substreams consumed the same allowance,
+    /// which is then paid back before any more bytes go through.
+    fn consume(&mut self, bytes: u64) {
+        self.tokens -= bytes as f64;
+    }
+
+    fn time_to_refill(&self, bytes: u64) -> Duration {
+        let missing = bytes as f64 - self.tokens;
+        Duration::from_secs_f64(missing.max(0.0) / self.rate as f64)
+    }
+}
+
+struct ThrottledMuxer {
+    inner: StreamMuxerBox,
+    limiter: Arc<BandwidthLimiter>,
+}
+
+impl ThrottledMuxer {
+    fn throttled(&self, substream: SubstreamBox) -> ThrottledSubstream {
+        ThrottledSubstream {
+            inner: substream,
+            limiter: Arc::clone(&self.limiter),
+            read_delay: None,
+            write_delay: None,
+        }
+    }
+}
+
+impl StreamMuxer for ThrottledMuxer {
+    type Substream = ThrottledSubstream;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        let substream = ready!(Pin::new(&mut this.inner).poll_inbound(cx))?;
+        Poll::Ready(Ok(this.throttled(substream)))
+    }
+
+    fn poll_outbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        let substream = ready!(Pin::new(&mut this.inner).poll_outbound(cx))?;
+        Poll::Ready(Ok(this.throttled(substream)))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll(cx)
+    }
+}
+
+struct ThrottledSubstream {
+    inner: SubstreamBox,
+    limiter: Arc<BandwidthLimiter>,
+    read_delay: Option<Pin<Box<Sleep>>>,
+    write_delay: Option<Pin<Box<Sleep>>>,
+}
+
+/// Waits for the direction to allow some of the `wanted` bytes through.
+fn poll_allowance(
+    direction: &Mutex<Direction>,
+    delay: &mut Option<Pin<Box<Sleep>>>,
+    cx: &mut Context<'_>,
+    wanted: usize,
+) -> Poll<usize> {
+    loop {
+        if let Some(sleeping) = delay.as_mut() {
+            ready!(sleeping.as_mut().poll(cx));
+            *delay = None;
+        }
+        match lock(direction).allowance(wanted) {
+            Ok(allowed) => return Poll::Ready(allowed),
+            Err(wait) => *delay = Some(Box::pin(sleep(wait))),
+        }
+    }
+}
+
+impl AsyncRead for ThrottledSubstream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let allowed = ready!(poll_allowance(
+            &this.limiter.download,
+            &mut this.read_delay,
+            cx,
+            buf.len()
+        ));
+        let read = ready!(Pin::new(&mut this.inner).poll_read(cx, &mut buf[..allowed]))?;
+        lock(&this.limiter.download).record(read);
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncWrite for ThrottledSubstream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let allowed = ready!(poll_allowance(
+            &this.limiter.upload,
+            &mut this.write_delay,
+            cx,
+            buf.len()
+        ));
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..allowed]))?;
+        lock(&this.limiter.upload).record(written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capped_direction_only_allows_its_rate() {
+        let mut direction = Direction::new(Some(4096));
+
+        // a second worth of bytes is allowed straight away
+        assert_eq!(direction.allowance(10_000), Ok(4096));
+        direction.record(4096);
+
+        // then the substreams have to wait for the bucket to refill
+        let wait = direction
+            .allowance(10_000)
+            .expect_err("the bucket should be empty");
+        assert!(wait > Duration::from_millis(200) && wait <= Duration::from_millis(250));
+        assert_eq!(direction.total, 4096);
+
+        let mut uncapped = Direction::new(None);
+        assert_eq!(uncapped.allowance(10_000), Ok(10_000));
+        uncapped.record(10_000);
+        assert_eq!(uncapped.total, 10_000);
+    }
+}
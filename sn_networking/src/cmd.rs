@@ -7,10 +7,17 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
+    bad_nodes::{
+        bad_nodes_info, elapsed_since, is_expired, persist_bad_nodes, BadNodeInfo, NODE_ISSUE_DECAY,
+    },
+    bandwidth::BandwidthUsage,
     driver::{PendingGetClosestType, SwarmDriver},
     error::{NetworkError, Result},
     event::TerminateNodeReason,
-    multiaddr_pop_p2p, GetRecordCfg, GetRecordError, MsgResponder, NetworkEvent, CLOSE_GROUP_SIZE,
+    multiaddr_pop_p2p,
+    reputation::{NodeCredit, BAD_NODE_SCORE},
+    routing_health::RoutingHealth,
+    GetRecordCfg, GetRecordError, MsgResponder, NetworkEvent, CLOSE_GROUP_SIZE,
     REPLICATION_PEERS_COUNT,
 };
 use libp2p::{
@@ -21,8 +28,9 @@ use libp2p::{
     swarm::dial_opts::DialOpts,
     Multiaddr, PeerId,
 };
+use serde::{Deserialize, Serialize};
 use sn_protocol::{
-    messages::{Cmd, Request, Response},
+    messages::{ReplicationDigest, Request, Response},
     storage::{RecordHeader, RecordKind, RecordType},
     NetworkAddress, PrettyPrintRecordKey,
 };
@@ -35,14 +43,14 @@ use std::{
 use tokio::sync::oneshot;
 use xor_name::XorName;
 
-use crate::target_arch::Instant;
+use crate::target_arch::{Instant, SystemTime};
 
 const MAX_CONTINUOUS_HDD_WRITE_ERROR: usize = 5;
 
 // Shall be synced with `sn_node::PERIODIC_REPLICATION_INTERVAL_MAX_S`
 const REPLICATION_TIMEOUT: Duration = Duration::from_secs(45);
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum NodeIssue {
     /// Connection issues observed
     ConnectionIssue,
@@ -93,6 +101,14 @@ pub enum SwarmCmd {
         sender: oneshot::Sender<Vec<PeerId>>,
     },
     GetSwarmLocalState(oneshot::Sender<SwarmLocalState>),
+    // Get the peers we have recorded issues against, along with those issues
+    GetBadNodes {
+        sender: oneshot::Sender<Vec<BadNodeInfo>>,
+    },
+    // Get a summary of the state of the routing table
+    GetRoutingHealth {
+        sender: oneshot::Sender<RoutingHealth>,
+    },
     // Send Request to the PeerId.
     SendRequest {
         req: Request,
@@ -172,6 +188,11 @@ pub enum SwarmCmd {
         peer_id: PeerId,
         issue: NodeIssue,
     },
+    /// Notify of a good behaviour of the peer
+    RecordNodeCredit {
+        peer_id: PeerId,
+        credit: NodeCredit,
+    },
     // Whether peer is considered as `in trouble` by self
     IsPeerShunned {
         target: NetworkAddress,
@@ -275,6 +296,12 @@ impl Debug for SwarmCmd {
             SwarmCmd::GetSwarmLocalState { .. } => {
                 write!(f, "SwarmCmd::GetSwarmLocalState")
             }
+            SwarmCmd::GetBadNodes { .. } => {
+                write!(f, "SwarmCmd::GetBadNodes")
+            }
+            SwarmCmd::GetRoutingHealth { .. } => {
+                write!(f, "SwarmCmd::GetRoutingHealth")
+            }
             SwarmCmd::RecordStoreHasKey { key, .. } => {
                 write!(
                     f,
@@ -294,6 +321,12 @@ impl Debug for SwarmCmd {
                     "SwarmCmd::SendNodeStatus peer {peer_id:?}, issue: {issue:?}"
                 )
             }
+            SwarmCmd::RecordNodeCredit { peer_id, credit } => {
+                write!(
+                    f,
+                    "SwarmCmd::RecordNodeCredit peer {peer_id:?}, credit: {credit:?}"
+                )
+            }
             SwarmCmd::IsPeerShunned { target, .. } => {
                 write!(f, "SwarmCmd::IsPeerInTrouble target: {target:?}")
             }
@@ -317,6 +350,10 @@ pub struct SwarmLocalState {
     pub connected_peers: Vec<PeerId>,
     /// List of addresses the node is currently listening on
     pub listeners: Vec<Multiaddr>,
+    /// Number of currently established connections
+    pub connections: u32,
+    /// Bandwidth used by the connections
+    pub bandwidth: BandwidthUsage,
 }
 
 impl SwarmDriver {
@@ -503,13 +540,24 @@ impl SwarmDriver {
                     }
                 }
 
+                // Back off from fetching once the record store gets nearly full.
+                let store_load = self.swarm.behaviour_mut().kademlia.store_mut().fill_ratio();
+                self.replication_fetcher.set_store_load(store_load);
+
                 // No matter storing the record succeeded or not,
                 // the entry shall be removed from the `replication_fetcher`.
                 // In case of local store error, re-attempt will be carried out
                 // within the next replication round.
-                let new_keys_to_fetch = self
-                    .replication_fetcher
-                    .notify_about_new_put(key.clone(), record_type);
+                if let Some(holder) = self.replication_fetcher.on_going_fetch_holder(&key) {
+                    let _ = self
+                        .reputations
+                        .record_credit(holder, &NodeCredit::ReplicationFetched);
+                }
+                let new_keys_to_fetch = self.replication_fetcher.notify_about_new_put(
+                    key.clone(),
+                    record_type,
+                    &self.reputations,
+                );
                 if !new_keys_to_fetch.is_empty() {
                     self.send_event(NetworkEvent::KeysToFetchForReplication(new_keys_to_fetch));
                 }
@@ -725,6 +773,12 @@ impl SwarmDriver {
                 let current_state = SwarmLocalState {
                     connected_peers: self.swarm.connected_peers().cloned().collect(),
                     listeners: self.swarm.listeners().cloned().collect(),
+                    connections: self
+                        .swarm
+                        .network_info()
+                        .connection_counters()
+                        .num_established(),
+                    bandwidth: self.bandwidth.usage(),
                 };
 
                 sender
@@ -732,11 +786,24 @@ impl SwarmDriver {
                     .map_err(|_| NetworkError::InternalMsgChannelDropped)?;
             }
 
+            SwarmCmd::GetBadNodes { sender } => {
+                cmd_string = "GetBadNodes";
+                let _ = sender.send(bad_nodes_info(&self.bad_nodes));
+            }
+            SwarmCmd::GetRoutingHealth { sender } => {
+                cmd_string = "GetRoutingHealth";
+                let _ = sender.send(self.routing_health());
+            }
             SwarmCmd::RecordNodeIssue { peer_id, issue } => {
                 cmd_string = "RecordNodeIssues";
                 let _ = self.bad_nodes_ongoing_verifications.remove(&peer_id);
                 self.record_node_issue(peer_id, issue);
             }
+            SwarmCmd::RecordNodeCredit { peer_id, credit } => {
+                cmd_string = "RecordNodeCredit";
+                let score = self.reputations.record_credit(peer_id, &credit);
+                trace!("Peer {peer_id:?} credited for {credit:?}, now scored {score:.2}");
+            }
             SwarmCmd::IsPeerShunned { target, sender } => {
                 cmd_string = "IsPeerInTrouble";
                 let is_bad = if let Some(peer_id) = target.as_peer_id() {
@@ -768,7 +835,9 @@ impl SwarmDriver {
                     PrettyPrintRecordKey::from(&key)
                 );
                 cmd_string = "FetchCompleted";
-                let new_keys_to_fetch = self.replication_fetcher.notify_fetch_early_completed(key);
+                let new_keys_to_fetch = self
+                    .replication_fetcher
+                    .notify_fetch_early_completed(key, &self.reputations);
                 if !new_keys_to_fetch.is_empty() {
                     self.send_event(NetworkEvent::KeysToFetchForReplication(new_keys_to_fetch));
                 }
@@ -782,6 +851,9 @@ impl SwarmDriver {
 
     fn record_node_issue(&mut self, peer_id: PeerId, issue: NodeIssue) {
         info!("Peer {peer_id:?} is reported as having issue {issue:?}");
+        // forget the nodes whose issues have decayed, giving the long time bad ones another chance
+        self.bad_nodes
+            .retain(|_, (issue_vec, is_bad)| !is_expired(issue_vec, *is_bad));
         let (issue_vec, is_bad) = self.bad_nodes.entry(peer_id).or_default();
 
         let mut is_new_bad = false;
@@ -790,7 +862,7 @@ impl SwarmDriver {
         // If being considered as bad already, skip certain operations
         if !(*is_bad) {
             // Remove outdated entries
-            issue_vec.retain(|(_, timestamp)| timestamp.elapsed().as_secs() < 300);
+            issue_vec.retain(|(_, timestamp)| elapsed_since(*timestamp) < NODE_ISSUE_DECAY);
 
             // check if vec is already 10 long, if so, remove the oldest issue
             // we only track 10 issues to avoid mem leaks
@@ -801,30 +873,22 @@ impl SwarmDriver {
             // To avoid being too sensitive, only consider as a new issue
             // when after certain while since the last one
             let is_new_issue = if let Some((_issue, timestamp)) = issue_vec.last() {
-                timestamp.elapsed().as_secs() > 10
+                elapsed_since(*timestamp).as_secs() > 10
             } else {
                 true
             };
 
             if is_new_issue {
-                issue_vec.push((issue, Instant::now()));
-            }
-
-            // Only consider candidate as a bad node when:
-            //   accumulated THREE same kind issues within certain period
-            for (issue, _timestamp) in issue_vec.iter() {
-                let issue_counts = issue_vec
-                    .iter()
-                    .filter(|(i, _timestamp)| *issue == *i)
-                    .count();
-                if issue_counts >= 3 {
+                let score = self.reputations.record_issue(peer_id, &issue);
+                // Only consider candidate as a bad node once its reputation dropped too low,
+                // i.e. accumulated issues within certain period not offset by good behaviours
+                if score < BAD_NODE_SCORE {
                     *is_bad = true;
                     is_new_bad = true;
                     bad_behaviour = format!("{issue:?}");
-                    info!("Peer {peer_id:?} accumulated {issue_counts} times of issue {issue:?}. Consider it as a bad node now.");
-                    // Once a bad behaviour detected, no point to continue
-                    break;
+                    info!("Peer {peer_id:?} score dropped to {score:.2} on issue {issue:?}. Consider it as a bad node now.");
                 }
+                issue_vec.push((issue, SystemTime::now()));
             }
         }
 
@@ -836,6 +900,9 @@ impl SwarmDriver {
             }
 
             if is_new_bad {
+                if let Some(path) = &self.bad_nodes_path {
+                    persist_bad_nodes(path.clone(), &self.bad_nodes);
+                }
                 self.send_event(NetworkEvent::PeerConsideredAsBad {
                     detected_by: self.self_peer_id,
                     bad_peer: peer_id,
@@ -853,6 +920,10 @@ impl SwarmDriver {
                 return;
             }
 
+            let _ = self
+                .reputations
+                .record_credit(peer_id, &NodeCredit::HonestQuote);
+
             if history_quote.is_newer_than(&quote) {
                 return;
             }
@@ -888,40 +959,53 @@ impl SwarmDriver {
             return Ok(());
         }
 
-        let all_records: Vec<_> = self
+        // Only a digest of the records is sent, the peers then asking for the keys of the
+        // buckets they hold differently.
+        let distance_range = self
             .swarm
             .behaviour_mut()
             .kademlia
             .store_mut()
-            .record_addresses_ref()
-            .values()
-            .cloned()
-            .collect();
+            .get_farthest_replication_distance_bucket();
+        for peer_id in replicate_targets {
+            let keys =
+                self.replication_keys_within(&NetworkAddress::from_peer(peer_id), distance_range);
+            if keys.is_empty() {
+                continue;
+            }
 
-        if !all_records.is_empty() {
             trace!(
-                "Sending a replication list of {} keys to {replicate_targets:?} ",
-                all_records.len()
+                "Sending a replication digest of {} keys to {peer_id:?}",
+                keys.len()
             );
-            let request = Request::Cmd(Cmd::Replicate {
-                holder: NetworkAddress::from_peer(self.self_peer_id),
-                keys: all_records,
-            });
-            for peer_id in replicate_targets {
-                let request_id = self
-                    .swarm
-                    .behaviour_mut()
-                    .request_response
-                    .send_request(&peer_id, request.clone());
-                trace!("Sending request {request_id:?} to peer {peer_id:?}");
-                let _ = self.pending_requests.insert(request_id, None);
-                let _ = self
-                    .replication_targets
-                    .insert(peer_id, now + REPLICATION_TIMEOUT);
-            }
-            trace!("Pending Requests now: {:?}", self.pending_requests.len());
+            self.send_replication_digest(peer_id, distance_range, ReplicationDigest::new(&keys));
+            let _ = self
+                .replication_targets
+                .insert(peer_id, now + REPLICATION_TIMEOUT);
         }
+        trace!("Pending Requests now: {:?}", self.pending_requests.len());
 
         Ok(())
     }
+
+    /// The keys of the records we hold within the distance range of the target,
+    /// all of them when there is no range.
+    pub(crate) fn replication_keys_within(
+        &mut self,
+        target: &NetworkAddress,
+        distance_range: Option<u32>,
+    ) -> Vec<(NetworkAddress, RecordType)> {
+        self.swarm
+            .behaviour_mut()
+            .kademlia
+            .store_mut()
+            .record_addresses_ref()
+            .values()
+            .filter(|(addr, _)| {
+                distance_range
+                    .is_none_or(|range| target.distance(addr).ilog2().unwrap_or(0) <= range)
+            })
+            .cloned()
+            .collect()
+    }
 }
@@ -11,6 +11,12 @@ use crate::metrics::NetworkMetrics;
 #[cfg(feature = "open-metrics")]
 use crate::metrics_service::run_metrics_server;
 use crate::{
+    address_book::{
+        load_address_book, persist_address_book, prune_address_book, AddressBook,
+        ADDRESS_BOOK_FILENAME, ADDRESS_BOOK_SNAPSHOT_INTERVAL,
+    },
+    bad_nodes::{load_bad_nodes, BAD_NODES_FILENAME},
+    bandwidth::BandwidthLimiter,
     bootstrap::{ContinuousBootstrap, BOOTSTRAP_INTERVAL},
     circular_vec::CircularVec,
     cmd::SwarmCmd,
@@ -18,20 +24,27 @@ use crate::{
     event::{NetworkEvent, NodeEvent},
     multiaddr_pop_p2p,
     network_discovery::NetworkDiscovery,
+    record_encryption::RECORD_ENCRYPTION_SEED_DOMAIN,
     record_store::{ClientRecordStore, NodeRecordStore, NodeRecordStoreConfig},
     record_store_api::UnifiedRecordStore,
+    record_store_backend::RecordStorageBackendKind,
     relay_manager::RelayManager,
     replication_fetcher::ReplicationFetcher,
-    target_arch::{interval, spawn, Instant},
+    reputation::Reputations,
+    target_arch::{interval, spawn, Instant, SystemTime},
     version::{
         IDENTIFY_CLIENT_VERSION_STR, IDENTIFY_NODE_VERSION_STR, IDENTIFY_PROTOCOL_STR,
         REQ_RESPONSE_VERSION_STR,
     },
     GetRecordError, Network, CLOSE_GROUP_SIZE,
 };
-use crate::{transport, NodeIssue};
+use crate::{
+    transport::{self, ListenTransport},
+    NodeIssue,
+};
 use futures::future::Either;
 use futures::StreamExt;
+use itertools::Itertools;
 #[cfg(feature = "local-discovery")]
 use libp2p::mdns;
 use libp2p::Transport as _;
@@ -39,7 +52,6 @@ use libp2p::{core::muxing::StreamMuxerBox, relay};
 use libp2p::{
     identity::Keypair,
     kad::{self, QueryId, Quorum, Record, K_VALUE},
-    multiaddr::Protocol,
     request_response::{self, Config as RequestResponseConfig, OutboundRequestId, ProtocolSupport},
     swarm::{
         dial_opts::{DialOpts, PeerCondition},
@@ -50,7 +62,7 @@ use libp2p::{
 #[cfg(feature = "open-metrics")]
 use prometheus_client::registry::Registry;
 use sn_protocol::{
-    messages::{ChunkProof, Nonce, Request, Response},
+    messages::{ChunkProof, DigestBucket, Nonce, Request, Response},
     storage::RetryStrategy,
     NetworkAddress, PrettyPrintKBucketKey, PrettyPrintRecordKey,
 };
@@ -61,6 +73,7 @@ use std::{
     net::SocketAddr,
     num::NonZeroUsize,
     path::PathBuf,
+    sync::Arc,
 };
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::Duration;
@@ -97,7 +110,8 @@ pub(crate) type PendingGetRecord = HashMap<
 
 /// 10 is the max number of issues per node we track to avoid mem leaks
 /// The boolean flag to indicate whether the node is considered as bad or not
-pub(crate) type BadNodes = BTreeMap<PeerId, (Vec<(NodeIssue, Instant)>, bool)>;
+/// The issues are timestamped with the wall-clock time, for them to be persisted across restarts.
+pub(crate) type BadNodes = BTreeMap<PeerId, (Vec<(NodeIssue, SystemTime)>, bool)>;
 
 /// What is the largest packet to send over the network.
 /// Records larger than this will be rejected.
@@ -198,6 +212,7 @@ pub(super) struct NodeBehaviour {
     pub(super) dcutr: libp2p::dcutr::Behaviour,
     pub(super) relay_client: libp2p::relay::client::Behaviour,
     pub(super) relay_server: libp2p::relay::Behaviour,
+    pub(super) connection_limits: libp2p::connection_limits::Behaviour,
 }
 
 #[derive(Debug)]
@@ -207,9 +222,17 @@ pub struct NetworkBuilder {
     local: bool,
     root_dir: PathBuf,
     listen_addr: Option<SocketAddr>,
+    listen_transports: Vec<ListenTransport>,
     request_timeout: Option<Duration>,
     concurrency_limit: Option<usize>,
+    max_connections: Option<u32>,
+    max_connections_per_peer: Option<u32>,
+    max_upload_bytes_per_sec: Option<u64>,
+    max_download_bytes_per_sec: Option<u64>,
     initial_peers: Vec<Multiaddr>,
+    rotate_record_encryption_key: bool,
+    record_storage_backend: RecordStorageBackendKind,
+    max_storage_bytes: Option<u64>,
     #[cfg(feature = "open-metrics")]
     metrics_registry: Option<Registry>,
     #[cfg(feature = "open-metrics")]
@@ -227,9 +250,17 @@ impl NetworkBuilder {
             local,
             root_dir,
             listen_addr: None,
+            listen_transports: ListenTransport::defaults(),
             request_timeout: None,
             concurrency_limit: None,
+            max_connections: None,
+            max_connections_per_peer: None,
+            max_upload_bytes_per_sec: None,
+            max_download_bytes_per_sec: None,
             initial_peers: Default::default(),
+            rotate_record_encryption_key: false,
+            record_storage_backend: Default::default(),
+            max_storage_bytes: None,
             #[cfg(feature = "open-metrics")]
             metrics_registry: None,
             #[cfg(feature = "open-metrics")]
@@ -247,6 +278,14 @@ impl NetworkBuilder {
         self.listen_addr = Some(listen_addr);
     }
 
+    /// Select the transports to listen on, all of them on the port of the listen addr.
+    /// The defaults are kept if empty.
+    pub fn listen_transports(&mut self, listen_transports: Vec<ListenTransport>) {
+        if !listen_transports.is_empty() {
+            self.listen_transports = listen_transports;
+        }
+    }
+
     pub fn request_timeout(&mut self, request_timeout: Duration) {
         self.request_timeout = Some(request_timeout);
     }
@@ -255,10 +294,47 @@ impl NetworkBuilder {
         self.concurrency_limit = Some(concurrency_limit);
     }
 
+    /// Cap the number of established connections, inbound and outbound.
+    pub fn max_connections(&mut self, max_connections: u32) {
+        self.max_connections = Some(max_connections);
+    }
+
+    /// Cap the number of established connections with any single peer.
+    pub fn max_connections_per_peer(&mut self, max_connections_per_peer: u32) {
+        self.max_connections_per_peer = Some(max_connections_per_peer);
+    }
+
+    /// Cap the bytes sent per second, over all the connections.
+    pub fn max_upload_bytes_per_sec(&mut self, max_upload_bytes_per_sec: u64) {
+        self.max_upload_bytes_per_sec = Some(max_upload_bytes_per_sec);
+    }
+
+    /// Cap the bytes received per second, over all the connections.
+    pub fn max_download_bytes_per_sec(&mut self, max_download_bytes_per_sec: u64) {
+        self.max_download_bytes_per_sec = Some(max_download_bytes_per_sec);
+    }
+
     pub fn initial_peers(&mut self, initial_peers: Vec<Multiaddr>) {
         self.initial_peers = initial_peers;
     }
 
+    /// Rotate the key used to encrypt the records at rest when the node starts,
+    /// re-encrypting all the existing records with the new key.
+    pub fn rotate_record_encryption_key(&mut self, enable: bool) {
+        self.rotate_record_encryption_key = enable;
+    }
+
+    /// Select the backend used to store the records on disk.
+    pub fn record_storage_backend(&mut self, backend: RecordStorageBackendKind) {
+        self.record_storage_backend = backend;
+    }
+
+    /// Limit the disk space the records can take, instead of the number of records stored.
+    /// Records farthest from us are pruned to stay within the limit.
+    pub fn max_storage_bytes(&mut self, max_storage_bytes: u64) {
+        self.max_storage_bytes = Some(max_storage_bytes);
+    }
+
     #[cfg(feature = "open-metrics")]
     pub fn metrics_registry(&mut self, metrics_registry: Option<Registry>) {
         self.metrics_registry = metrics_registry;
@@ -325,15 +401,40 @@ impl NetworkBuilder {
                     source: error,
                 });
             }
+            // Seal the record encryption key with a secret derived from our keypair,
+            // so that the records can still be decrypted after a restart.
+            let encryption_seed = self
+                .keypair
+                .derive_secret(RECORD_ENCRYPTION_SEED_DOMAIN)
+                .ok_or(NetworkError::FailedToDeriveRecordEncryptionSeed)?;
             NodeRecordStoreConfig {
                 max_value_bytes: MAX_PACKET_SIZE, // TODO, does this need to be _less_ than MAX_PACKET_SIZE
                 storage_dir: storage_dir_path,
                 historic_quote_dir: self.root_dir.clone(),
+                encryption_seed,
+                rotate_encryption_key: self.rotate_record_encryption_key,
+                storage_backend: self.record_storage_backend,
+                max_storage_bytes: self.max_storage_bytes,
                 ..Default::default()
             }
         };
 
-        let listen_addr = self.listen_addr;
+        // Listen on the provided address
+        let listen_socket_addr = self
+            .listen_addr
+            .ok_or(NetworkError::ListenAddressNotProvided)?;
+        let listen_transports = self.listen_transports.clone();
+
+        // TCP and WebSocket both listen over TCP, hence can't share the same port
+        if listen_socket_addr.port() != 0
+            && listen_transports.contains(&ListenTransport::Tcp)
+            && listen_transports.contains(&ListenTransport::WebSocket)
+        {
+            return Err(NetworkError::ConflictingListenTransports(
+                listen_socket_addr.port(),
+            ));
+        }
+
         #[cfg(feature = "upnp")]
         let upnp = self.upnp;
 
@@ -347,25 +448,12 @@ impl NetworkBuilder {
             upnp,
         )?;
 
-        // Listen on the provided address
-        let listen_socket_addr = listen_addr.ok_or(NetworkError::ListenAddressNotProvided)?;
-
-        // Listen on QUIC
-        let addr_quic = Multiaddr::from(listen_socket_addr.ip())
-            .with(Protocol::Udp(listen_socket_addr.port()))
-            .with(Protocol::QuicV1);
-        swarm_driver
-            .listen_on(addr_quic)
-            .expect("Multiaddr should be supported by our configured transports");
-
-        // Listen on WebSocket
-        #[cfg(any(feature = "websockets", target_arch = "wasm32"))]
-        {
-            let addr_ws = Multiaddr::from(listen_socket_addr.ip())
-                .with(Protocol::Tcp(listen_socket_addr.port()))
-                .with(Protocol::Ws("/".into()));
+        // Listen on each of the selected transports
+        for listen_transport in listen_transports.iter().unique() {
+            let addr = listen_transport.listen_addr(listen_socket_addr);
+            info!("Listening on {listen_transport} at {addr:?}");
             swarm_driver
-                .listen_on(addr_ws)
+                .listen_on(addr)
                 .expect("Multiaddr should be supported by our configured transports");
         }
 
@@ -465,7 +553,7 @@ impl NetworkBuilder {
                         store_cfg,
                         network_event_sender.clone(),
                         swarm_cmd_sender.clone(),
-                    );
+                    )?;
                     #[cfg(feature = "open-metrics")]
                     let mut node_record_store = node_record_store;
                     #[cfg(feature = "open-metrics")]
@@ -548,6 +636,19 @@ impl NetworkBuilder {
             })
             .boxed();
 
+        // Account for, and cap, the bandwidth used by all the connections
+        let bandwidth = Arc::new(BandwidthLimiter::new(
+            self.max_upload_bytes_per_sec,
+            self.max_download_bytes_per_sec,
+        ));
+        let transport = bandwidth.throttle(transport);
+
+        let connection_limits = libp2p::connection_limits::Behaviour::new(
+            libp2p::connection_limits::ConnectionLimits::default()
+                .with_max_established(self.max_connections)
+                .with_max_established_per_peer(self.max_connections_per_peer),
+        );
+
         let relay_server = {
             let relay_server_cfg = relay::Config::default();
             libp2p::relay::Behaviour::new(peer_id, relay_server_cfg)
@@ -564,6 +665,7 @@ impl NetworkBuilder {
             #[cfg(feature = "local-discovery")]
             mdns,
             dcutr: libp2p::dcutr::Behaviour::new(peer_id),
+            connection_limits,
         };
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -576,12 +678,31 @@ impl NetworkBuilder {
         let swarm = Swarm::new(transport, behaviour, peer_id, swarm_config);
 
         let bootstrap = ContinuousBootstrap::new();
-        let replication_fetcher = ReplicationFetcher::new(peer_id, network_event_sender.clone());
+        #[allow(unused_mut)]
+        let mut replication_fetcher =
+            ReplicationFetcher::new(peer_id, network_event_sender.clone());
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics) = &network_metrics {
+            replication_fetcher.set_metrics(metrics.replication.clone());
+        }
         let mut relay_manager = RelayManager::new(self.initial_peers, peer_id);
         if !is_client {
             relay_manager.enable_hole_punching(self.is_behind_home_network);
         }
 
+        // only the nodes keep their bad nodes across restarts
+        let bad_nodes_path = (!is_client).then(|| self.root_dir.join(BAD_NODES_FILENAME));
+        let bad_nodes = bad_nodes_path
+            .as_deref()
+            .map(load_bad_nodes)
+            .unwrap_or_default();
+        // as well as the peers they can rejoin the network through
+        let address_book_path = (!is_client).then(|| self.root_dir.join(ADDRESS_BOOK_FILENAME));
+        let address_book = address_book_path
+            .as_deref()
+            .map(load_address_book)
+            .unwrap_or_default();
+
         let swarm_driver = SwarmDriver {
             swarm,
             self_peer_id: peer_id,
@@ -600,6 +721,7 @@ impl NetworkBuilder {
             event_sender: network_event_sender,
             pending_get_closest_peers: Default::default(),
             pending_requests: Default::default(),
+            pending_replication_digests: Default::default(),
             pending_get_record: Default::default(),
             // We use 255 here which allows covering a network larger than 64k without any rotating.
             // This is based on the libp2p kad::kBuckets peers distribution.
@@ -610,10 +732,16 @@ impl NetworkBuilder {
             handling_statistics: Default::default(),
             handled_times: 0,
             hard_disk_write_error: 0,
-            bad_nodes: Default::default(),
+            bad_nodes,
+            bad_nodes_path,
             bad_nodes_ongoing_verifications: Default::default(),
+            address_book,
+            address_book_path,
+            reputations: Default::default(),
             quotes_history: Default::default(),
             replication_targets: Default::default(),
+            bandwidth,
+            last_successful_queries: Default::default(),
         };
 
         let network = Network::new(swarm_cmd_sender, peer_id, self.root_dir, self.keypair);
@@ -647,6 +775,10 @@ pub struct SwarmDriver {
     pub(crate) pending_get_closest_peers: PendingGetClosest,
     pub(crate) pending_requests:
         HashMap<OutboundRequestId, Option<oneshot::Sender<Result<Response>>>>,
+    /// The distance range and scope of the replication digests sent, to send the keys they cover
+    /// instead to the peers failing to handle them.
+    pub(crate) pending_replication_digests:
+        HashMap<OutboundRequestId, (Option<u32>, Vec<DigestBucket>)>,
     pub(crate) pending_get_record: PendingGetRecord,
     /// A list of the most recent peers we have dialed ourselves. Old dialed peers are evicted once the vec fills up.
     pub(crate) dialed_peers: CircularVec<PeerId>,
@@ -662,9 +794,20 @@ pub struct SwarmDriver {
     handled_times: usize,
     pub(crate) hard_disk_write_error: usize,
     pub(crate) bad_nodes: BadNodes,
+    /// Where the bad nodes are persisted, None for the clients
+    pub(crate) bad_nodes_path: Option<PathBuf>,
     pub(crate) bad_nodes_ongoing_verifications: BTreeSet<PeerId>,
+    /// The known-good peers, snapshotted from the RT
+    pub(crate) address_book: AddressBook,
+    /// Where the address book is persisted, None for the clients
+    pub(crate) address_book_path: Option<PathBuf>,
+    pub(crate) reputations: Reputations,
     pub(crate) quotes_history: BTreeMap<PeerId, PaymentQuote>,
     pub(crate) replication_targets: BTreeMap<PeerId, Instant>,
+    /// Accounts for the bandwidth used by all the connections
+    pub(crate) bandwidth: Arc<BandwidthLimiter>,
+    /// When a peer of each bucket, by ilog2 distance, last answered a request from us
+    pub(crate) last_successful_queries: BTreeMap<u32, SystemTime>,
 }
 
 impl SwarmDriver {
@@ -679,6 +822,9 @@ impl SwarmDriver {
         let mut bootstrap_interval = interval(BOOTSTRAP_INTERVAL);
         let mut set_farthest_record_interval = interval(CLOSET_RECORD_CHECK_INTERVAL);
         let mut relay_manager_reservation_interval = interval(RELAY_MANAGER_RESERVATION_INTERVAL);
+        let mut address_book_snapshot_interval = interval(ADDRESS_BOOK_SNAPSHOT_INTERVAL);
+        // first tick completes immediately, before the RT got populated
+        address_book_snapshot_interval.tick().await;
 
         loop {
             tokio::select! {
@@ -720,7 +866,8 @@ impl SwarmDriver {
                         }
                     }
                 }
-                _ = relay_manager_reservation_interval.tick() => self.relay_manager.try_connecting_to_relay(&mut self.swarm, &self.bad_nodes),
+                _ = relay_manager_reservation_interval.tick() => self.relay_manager.try_connecting_to_relay(&mut self.swarm, &self.bad_nodes, &self.reputations),
+                _ = address_book_snapshot_interval.tick() => self.snapshot_address_book(),
             }
         }
     }
@@ -786,6 +933,47 @@ impl SwarmDriver {
         all_peers
     }
 
+    /// Records the peers of our RT into the address book and persists it, for a restart to rejoin
+    /// the network through them. The bad nodes are left out.
+    fn snapshot_address_book(&mut self) {
+        let Some(path) = self.address_book_path.clone() else {
+            return;
+        };
+
+        let mut rt_peers = vec![];
+        for kbucket in self.swarm.behaviour_mut().kademlia.kbuckets() {
+            for entry in kbucket.iter() {
+                let addrs: Vec<_> = entry.node.value.iter().cloned().collect();
+                rt_peers.push((*entry.node.key.preimage(), addrs));
+            }
+        }
+
+        let now = SystemTime::now();
+        for (peer_id, addrs) in rt_peers {
+            if self
+                .bad_nodes
+                .get(&peer_id)
+                .is_some_and(|(_, is_bad)| *is_bad)
+            {
+                let _ = self.address_book.remove(&peer_id);
+                continue;
+            }
+            // peers we are not connected to keep their last seen time, if they have any
+            let last_seen = match self.address_book.get(&peer_id) {
+                Some((_, last_seen)) if !self.swarm.is_connected(&peer_id) => *last_seen,
+                _ => now,
+            };
+            let _ = self.address_book.insert(peer_id, (addrs, last_seen));
+        }
+        prune_address_book(&mut self.address_book);
+
+        debug!(
+            "Snapshotted {} peers into the address book",
+            self.address_book.len()
+        );
+        persist_address_book(path, &self.address_book);
+    }
+
     /// get closest k_value the peers from our local RoutingTable. Contains self.
     /// Is sorted for closeness to self.
     pub(crate) fn get_closest_k_value_local_peers(&mut self) -> Vec<PeerId> {
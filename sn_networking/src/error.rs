@@ -151,6 +151,9 @@ pub enum NetworkError {
         source: std::io::Error,
     },
 
+    #[error("Could not derive the record encryption seed from the keypair")]
+    FailedToDeriveRecordEncryptionSeed,
+
     // ---------- Internal Network Errors
     #[error("Could not get enough peers ({required}) to satisfy the request, found {found}")]
     NotEnoughPeers { found: usize, required: usize },
@@ -161,6 +164,11 @@ pub enum NetworkError {
     #[error("Node Listen Address was not provided during construction")]
     ListenAddressNotProvided,
 
+    #[error(
+        "TCP and WebSocket can't both listen on the same port {0}, use port 0 or pick one of them"
+    )]
+    ConflictingListenTransports(u16),
+
     #[cfg(feature = "open-metrics")]
     #[error("Network Metric error")]
     NetworkMetricError,
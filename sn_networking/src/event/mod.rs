@@ -94,6 +94,13 @@ impl From<libp2p::relay::Event> for NodeEvent {
     }
 }
 
+// The connection limits behaviour never emits any event.
+impl From<void::Void> for NodeEvent {
+    fn from(event: void::Void) -> Self {
+        void::unreachable(event)
+    }
+}
+
 #[derive(CustomDebug)]
 /// Channel to send the `Response` through.
 pub enum MsgResponder {
@@ -347,7 +354,7 @@ impl SwarmDriver {
     }
 
     /// Estimate the number of nodes in the network
-    fn estimate_network_size(
+    pub(crate) fn estimate_network_size(
         peers_in_non_full_buckets: usize,
         num_of_full_buckets: usize,
     ) -> usize {
@@ -17,10 +17,17 @@ use libp2p::{
 };
 use rand::{rngs::OsRng, Rng};
 use sn_protocol::{
-    messages::{CmdResponse, Request, Response},
+    messages::{
+        Cmd, CmdResponse, DigestBucket, ReplicationDigest, Request, Response, MAX_DIGEST_DEPTH,
+    },
     storage::RecordType,
     NetworkAddress,
 };
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The differing buckets of a replication digest holding more keys than this are split further
+/// rather than replicated.
+const MAX_REPLICATED_BUCKET_KEYS: usize = 64;
 
 impl SwarmDriver {
     /// Forwards `Request` to the upper layers using `Sender<NetworkEvent>`. Sends `Response` to the peers
@@ -53,6 +60,32 @@ impl SwarmDriver {
 
                             self.add_keys_to_replication_fetcher(holder, keys);
                         }
+                        Request::Cmd(sn_protocol::messages::Cmd::ReplicateDigest {
+                            holder,
+                            distance_range,
+                            digest,
+                        }) => {
+                            // the holder is checked on the digest of all its keys only
+                            let is_whole_digest = digest.scope() == [DigestBucket::new()];
+                            let differing_buckets = self.select_differing_replication_buckets(
+                                &holder,
+                                distance_range,
+                                &digest,
+                            );
+                            let response =
+                                Response::Cmd(sn_protocol::messages::CmdResponse::ReplicateDigest(
+                                    Ok(differing_buckets.clone().unwrap_or_default()),
+                                ));
+                            self.swarm
+                                .behaviour_mut()
+                                .request_response
+                                .send_response(channel, response)
+                                .map_err(|_| NetworkError::InternalMsgChannelDropped)?;
+
+                            if differing_buckets.is_some() && is_whole_digest {
+                                self.verify_replication_holder(holder);
+                            }
+                        }
                         Request::Cmd(sn_protocol::messages::Cmd::QuoteVerification {
                             quotes,
                             ..
@@ -113,6 +146,8 @@ impl SwarmDriver {
                     response,
                 } => {
                     trace!("Got response {request_id:?} from peer {peer:?}, res: {response}.");
+                    self.record_successful_query(&peer);
+                    let digest_request = self.pending_replication_digests.remove(&request_id);
                     if let Some(sender) = self.pending_requests.remove(&request_id) {
                         // The sender will be provided if the caller (Requester) is awaiting for a response
                         // at the call site.
@@ -122,19 +157,29 @@ impl SwarmDriver {
                             Some(sender) => sender
                                 .send(Ok(response))
                                 .map_err(|_| NetworkError::InternalMsgChannelDropped)?,
-                            None => {
-                                if let Response::Cmd(CmdResponse::Replicate(Ok(()))) = response {
+                            None => match response {
+                                Response::Cmd(CmdResponse::Replicate(Ok(()))) => {
                                     // Nothing to do, response was fine
                                     // This only exists to ensure we dont drop the handle and
                                     // exit early, potentially logging false connection woes
-                                } else {
+                                }
+                                Response::Cmd(CmdResponse::ReplicateDigest(Ok(buckets))) => {
+                                    if let Some((distance_range, _)) = digest_request {
+                                        self.replicate_differing_buckets(
+                                            peer,
+                                            distance_range,
+                                            buckets,
+                                        );
+                                    }
+                                }
+                                response => {
                                     // responses that are not awaited at the call site must be handled
                                     // separately
                                     self.send_event(NetworkEvent::ResponseReceived {
                                         res: response,
                                     });
                                 }
-                            }
+                            },
                         }
                     } else {
                         warn!("Tried to remove a RequestId from pending_requests which was not inserted in the first place.
@@ -147,6 +192,15 @@ impl SwarmDriver {
                 error,
                 peer,
             } => {
+                if let Some((distance_range, scope)) =
+                    self.pending_replication_digests.remove(&request_id)
+                {
+                    // the peers of older versions can't decode the replication digests
+                    warn!("Replication digest {request_id:?} to {peer:?} failed with {error:?}, sending it the replication list instead");
+                    let _ = self.pending_requests.remove(&request_id);
+                    self.fall_back_to_replication_list(peer, distance_range, scope);
+                    return Ok(());
+                }
                 if let Some(sender) = self.pending_requests.remove(&request_id) {
                     match sender {
                         Some(sender) => {
@@ -208,6 +262,7 @@ impl SwarmDriver {
         //        fetch them if close enough to us
         //   2, For those keys that we have and supposed to be held by the sender as well:
         //        start chunk_proof check against a randomly selected chunk type record to the sender
+        //        (carried out on receiving the periodical replication digest)
         //   3, For those spends that we have that differ in the hash, we fetch the other version
         //         and update our local copy.
 
@@ -225,29 +280,183 @@ impl SwarmDriver {
                 .kademlia
                 .store_mut()
                 .record_addresses_ref();
-            let keys_to_fetch = self
-                .replication_fetcher
-                .add_keys(holder, keys_to_store, all_keys);
+            let keys_to_fetch = self.replication_fetcher.add_keys(
+                holder,
+                keys_to_store,
+                all_keys,
+                &self.reputations,
+            );
             if keys_to_fetch.is_empty() {
                 trace!("no waiting keys to fetch from the network");
             } else {
                 self.send_event(NetworkEvent::KeysToFetchForReplication(keys_to_fetch));
             }
         }
+    }
+
+    /// Compares the digest of the records held by a close peer with ours,
+    /// returning the buckets we hold differently.
+    /// Returns None if the sender is not a peer we replicate with.
+    fn select_differing_replication_buckets(
+        &mut self,
+        sender: &NetworkAddress,
+        distance_range: Option<u32>,
+        digest: &ReplicationDigest,
+    ) -> Option<Vec<DigestBucket>> {
+        let holder = if let Some(peer_id) = sender.as_peer_id() {
+            peer_id
+        } else {
+            warn!("Replication digest sender is not a peer_id {sender:?}");
+            return None;
+        };
+
+        let closest_k_peers = self.get_closest_k_value_local_peers();
+        if !closest_k_peers.contains(&holder) || holder == self.self_peer_id {
+            trace!("Holder {holder:?} is self or not in replication range.");
+            return None;
+        }
 
-        // Only trigger chunk_proof check when received a periodical replication request.
-        if incoming_keys.len() > 1 {
-            let keys_to_verify = self.select_verification_data_candidates(sender);
+        let self_address = NetworkAddress::from_peer(self.self_peer_id);
+        let our_keys = self.replication_keys_within(&self_address, distance_range);
+        let differing_buckets =
+            ReplicationDigest::within(&our_keys, digest.scope().to_vec()).differing_buckets(digest);
+        trace!(
+            "Replication digest from {holder:?} differs from our {} keys in {} buckets",
+            our_keys.len(),
+            differing_buckets.len()
+        );
+        Some(differing_buckets)
+    }
 
-            if keys_to_verify.is_empty() {
-                debug!("No valid candidate to be checked against peer {holder:?}");
+    /// Sends the keys within the small buckets the peer holds differently to it, and a digest
+    /// splitting the large ones further.
+    fn replicate_differing_buckets(
+        &mut self,
+        peer: PeerId,
+        distance_range: Option<u32>,
+        buckets: Vec<DigestBucket>,
+    ) {
+        let Some(depth) = buckets.first().map(Vec::len) else {
+            trace!("Peer {peer:?} holds the same records as us, nothing to replicate");
+            return;
+        };
+        if depth == 0 || depth > MAX_DIGEST_DEPTH || buckets.iter().any(|b| b.len() != depth) {
+            warn!("Peer {peer:?} responded with invalid replication digest buckets");
+            return;
+        }
+
+        let buckets: BTreeSet<_> = buckets.into_iter().collect();
+        let mut keys_per_bucket: BTreeMap<DigestBucket, Vec<_>> = BTreeMap::new();
+        for key in self.replication_keys_within(&NetworkAddress::from_peer(peer), distance_range) {
+            let bucket = ReplicationDigest::bucket_of(&key.0, depth);
+            if buckets.contains(&bucket) {
+                keys_per_bucket.entry(bucket).or_default().push(key);
+            }
+        }
+
+        let mut keys = vec![];
+        let mut buckets_to_split = vec![];
+        let mut keys_to_split = vec![];
+        for (bucket, bucket_keys) in keys_per_bucket {
+            if bucket_keys.len() > MAX_REPLICATED_BUCKET_KEYS && depth < MAX_DIGEST_DEPTH {
+                buckets_to_split.push(bucket);
+                keys_to_split.extend(bucket_keys);
             } else {
-                self.send_event(NetworkEvent::ChunkProofVerification {
-                    peer_id: holder,
-                    keys_to_verify,
-                });
+                keys.extend(bucket_keys);
             }
         }
+
+        if !buckets_to_split.is_empty() {
+            trace!(
+                "Splitting {} differing buckets of {} keys further for {peer:?}",
+                buckets_to_split.len(),
+                keys_to_split.len()
+            );
+            let digest = ReplicationDigest::within(&keys_to_split, buckets_to_split);
+            self.send_replication_digest(peer, distance_range, digest);
+        }
+        if !keys.is_empty() {
+            trace!(
+                "Sending a replication list of {} keys in differing buckets to {peer:?}",
+                keys.len()
+            );
+            self.send_replication_list(peer, keys);
+        }
+    }
+
+    /// Sends the digest of the keys within the distance range of the peer to it.
+    /// The keys it covers are sent instead if the peer fails to handle it.
+    pub(crate) fn send_replication_digest(
+        &mut self,
+        peer: PeerId,
+        distance_range: Option<u32>,
+        digest: ReplicationDigest,
+    ) {
+        let scope = digest.scope().to_vec();
+        let request = Request::Cmd(Cmd::ReplicateDigest {
+            holder: NetworkAddress::from_peer(self.self_peer_id),
+            distance_range,
+            digest,
+        });
+        let request_id = self
+            .swarm
+            .behaviour_mut()
+            .request_response
+            .send_request(&peer, request);
+        trace!("Sending request {request_id:?} to peer {peer:?}");
+        let _ = self.pending_requests.insert(request_id, None);
+        let _ = self
+            .pending_replication_digests
+            .insert(request_id, (distance_range, scope));
+    }
+
+    // Send the keys within the scope of a replication digest the peer failed to handle.
+    fn fall_back_to_replication_list(
+        &mut self,
+        peer: PeerId,
+        distance_range: Option<u32>,
+        scope: Vec<DigestBucket>,
+    ) {
+        let depth = scope.first().map_or(0, Vec::len);
+        let scope: BTreeSet<_> = scope.into_iter().collect();
+        let keys: Vec<_> = self
+            .replication_keys_within(&NetworkAddress::from_peer(peer), distance_range)
+            .into_iter()
+            .filter(|(addr, _)| scope.contains(&ReplicationDigest::bucket_of(addr, depth)))
+            .collect();
+        if !keys.is_empty() {
+            self.send_replication_list(peer, keys);
+        }
+    }
+
+    fn send_replication_list(&mut self, peer: PeerId, keys: Vec<(NetworkAddress, RecordType)>) {
+        let request = Request::Cmd(Cmd::Replicate {
+            holder: NetworkAddress::from_peer(self.self_peer_id),
+            keys,
+        });
+        let request_id = self
+            .swarm
+            .behaviour_mut()
+            .request_response
+            .send_request(&peer, request);
+        let _ = self.pending_requests.insert(request_id, None);
+    }
+
+    /// Triggers the chunk_proof check against a close peer, on its periodical replication.
+    fn verify_replication_holder(&mut self, sender: NetworkAddress) {
+        let Some(holder) = sender.as_peer_id() else {
+            return;
+        };
+        let keys_to_verify = self.select_verification_data_candidates(sender);
+
+        if keys_to_verify.is_empty() {
+            debug!("No valid candidate to be checked against peer {holder:?}");
+        } else {
+            self.send_event(NetworkEvent::ChunkProofVerification {
+                peer_id: holder,
+                keys_to_verify,
+            });
+        }
     }
 
     /// Checks suggested records against what we hold, so we only
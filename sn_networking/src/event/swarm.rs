@@ -519,10 +519,18 @@ impl SwarmDriver {
                         true
                     }
                     DialError::Denied { cause } => {
-                        // The peer denied our connection
-                        // cleanup
-                        error!("OutgoingConnectionError: Denied: {cause:?}");
-                        true
+                        if let Some(exceeded) =
+                            cause.downcast_ref::<libp2p::connection_limits::Exceeded>()
+                        {
+                            // We reached our own connection limits, the peer is not at fault
+                            warn!("OutgoingConnectionError: Denied as our connection limit is reached: {exceeded:?}");
+                            false
+                        } else {
+                            // The peer denied our connection
+                            // cleanup
+                            error!("OutgoingConnectionError: Denied: {cause:?}");
+                            true
+                        }
                     }
                 };
 
@@ -9,6 +9,9 @@
 #[macro_use]
 extern crate tracing;
 
+mod address_book;
+mod bad_nodes;
+mod bandwidth;
 mod bootstrap;
 mod circular_vec;
 mod cmd;
@@ -21,10 +24,15 @@ mod metrics;
 #[cfg(feature = "open-metrics")]
 mod metrics_service;
 mod network_discovery;
+mod persist;
+mod record_encryption;
 mod record_store;
 mod record_store_api;
+mod record_store_backend;
 mod relay_manager;
 mod replication_fetcher;
+mod reputation;
+mod routing_health;
 mod spends;
 pub mod target_arch;
 mod transfers;
@@ -35,6 +43,9 @@ pub mod version;
 pub use target_arch::{interval, sleep, spawn, Instant, Interval};
 
 pub use self::{
+    address_book::read_cached_peers,
+    bad_nodes::BadNodeInfo,
+    bandwidth::BandwidthUsage,
     cmd::{NodeIssue, SwarmLocalState},
     driver::{
         GetRecordCfg, NetworkBuilder, PutRecordCfg, SwarmDriver, VerificationKind, MAX_PACKET_SIZE,
@@ -42,8 +53,12 @@ pub use self::{
     error::{GetRecordError, NetworkError},
     event::{MsgResponder, NetworkEvent},
     record_store::{calculate_cost_for_records, NodeRecordStore},
+    record_store_backend::RecordStorageBackendKind,
+    reputation::NodeCredit,
+    routing_health::{BucketHealth, RoutingHealth},
     spends::SpendVerificationOk,
     transfers::{get_raw_signed_spends_from_record, get_signed_spend_from_record},
+    transport::ListenTransport,
 };
 
 use self::{cmd::SwarmCmd, error::Result};
@@ -393,6 +408,48 @@ impl Network {
         ))
     }
 
+    /// Ask each of the close nodes to the provided chunk address for a proof of holding the Chunk.
+    ///
+    /// Returns the peers which proved to hold it, then the ones which failed to.
+    pub async fn get_chunk_existence_proofs(
+        &self,
+        chunk_address: NetworkAddress,
+        nonce: Nonce,
+        expected_proof: ChunkProof,
+    ) -> Result<(Vec<PeerId>, Vec<PeerId>)> {
+        let close_nodes = self.get_closest_peers(&chunk_address, true).await?;
+        let request = Request::Query(Query::GetChunkExistenceProof {
+            key: chunk_address.clone(),
+            nonce,
+        });
+        let responses = self
+            .send_and_get_responses(&close_nodes, &request, true)
+            .await;
+
+        let mut holders = Vec::new();
+        let mut failures = Vec::new();
+        for peer in close_nodes {
+            match responses.get(&peer) {
+                Some(Ok(Response::Query(QueryResponse::GetChunkExistenceProof(Ok(proof)))))
+                    if expected_proof.verify(proof) =>
+                {
+                    holders.push(peer);
+                }
+                resp => {
+                    debug!("Did not get a valid ChunkProof for {chunk_address:?} from {peer:?}: {resp:?}");
+                    failures.push(peer);
+                }
+            }
+        }
+        info!(
+            "Got {} valid chunk existence proofs out of {} for {chunk_address:?}",
+            holders.len(),
+            holders.len() + failures.len()
+        );
+
+        Ok((holders, failures))
+    }
+
     /// Get the store costs from the majority of the closest peers to the provided RecordKey.
     /// Record already exists will have a cost of zero to be returned.
     ///
@@ -807,6 +864,22 @@ impl Network {
         Ok(state)
     }
 
+    /// Returns the peers we have recorded issues against, and whether they are considered as bad.
+    pub async fn get_bad_nodes(&self) -> Result<Vec<BadNodeInfo>> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_swarm_cmd(SwarmCmd::GetBadNodes { sender });
+        let bad_nodes = receiver.await?;
+        Ok(bad_nodes)
+    }
+
+    /// Returns a summary of the state of the routing table, to tell how well connected we are.
+    pub async fn get_routing_health(&self) -> Result<RoutingHealth> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_swarm_cmd(SwarmCmd::GetRoutingHealth { sender });
+        let routing_health = receiver.await?;
+        Ok(routing_health)
+    }
+
     pub fn trigger_interval_replication(&self) {
         self.send_swarm_cmd(SwarmCmd::TriggerIntervalReplication)
     }
@@ -815,6 +888,11 @@ impl Network {
         self.send_swarm_cmd(SwarmCmd::RecordNodeIssue { peer_id, issue });
     }
 
+    /// Credits the reputation of the peer for a good behaviour.
+    pub fn record_node_credit(&self, peer_id: PeerId, credit: NodeCredit) {
+        self.send_swarm_cmd(SwarmCmd::RecordNodeCredit { peer_id, credit });
+    }
+
     pub fn historical_verify_quotes(&self, quotes: Vec<(PeerId, PaymentQuote)>) {
         self.send_swarm_cmd(SwarmCmd::QuoteVerification { quotes });
     }
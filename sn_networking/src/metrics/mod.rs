@@ -10,7 +10,13 @@ use crate::target_arch::sleep;
 use libp2p::metrics::{Metrics as Libp2pMetrics, Recorder};
 #[cfg(feature = "upnp")]
 use prometheus_client::metrics::{counter::Counter, family::Family};
-use prometheus_client::{metrics::gauge::Gauge, registry::Registry};
+use prometheus_client::{
+    metrics::{
+        gauge::Gauge,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::Registry,
+};
 use sysinfo::{Pid, ProcessRefreshKind, System};
 use tokio::time::Duration;
 
@@ -36,6 +42,7 @@ pub(crate) struct NetworkMetrics {
     pub(crate) store_cost: Gauge,
     #[cfg(feature = "upnp")]
     pub(crate) upnp_events: Family<upnp::UpnpEventLabels, Counter>,
+    pub(crate) replication: ReplicationMetrics,
 
     // system info
     process_memory_used_mb: Gauge,
@@ -95,6 +102,28 @@ impl NetworkMetrics {
             upnp_events.clone(),
         );
 
+        let replication = ReplicationMetrics {
+            queue_depth: Gauge::default(),
+            on_going_fetches: Gauge::default(),
+            // from 0.1s up to about 50s, covering the fetch timeout
+            fetch_latency: Histogram::new(exponential_buckets(0.1, 2.0, 10)),
+        };
+        sub_registry.register(
+            "replication_queue_depth",
+            "The number of records waiting to be fetched for replication",
+            replication.queue_depth.clone(),
+        );
+        sub_registry.register(
+            "replication_on_going_fetches",
+            "The number of replication fetches currently undertaken",
+            replication.on_going_fetches.clone(),
+        );
+        sub_registry.register(
+            "replication_fetch_latency_seconds",
+            "The time taken by the holders to deliver the records fetched for replication",
+            replication.fetch_latency.clone(),
+        );
+
         let process_memory_used_mb = Gauge::default();
         sub_registry.register(
             "process_memory_used_mb",
@@ -119,6 +148,7 @@ impl NetworkMetrics {
             store_cost,
             #[cfg(feature = "upnp")]
             upnp_events,
+            replication,
             process_memory_used_mb,
             process_cpu_usage_percentage,
         };
@@ -157,6 +187,14 @@ impl NetworkMetrics {
     }
 }
 
+/// The metrics of the `ReplicationFetcher`, shared with it.
+#[derive(Clone, Debug)]
+pub(crate) struct ReplicationMetrics {
+    pub(crate) queue_depth: Gauge,
+    pub(crate) on_going_fetches: Gauge,
+    pub(crate) fetch_latency: Histogram,
+}
+
 /// Impl the Recorder traits again for our struct.
 
 impl Recorder<libp2p::kad::Event> for NetworkMetrics {
@@ -0,0 +1,113 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::target_arch::spawn_blocking;
+use serde::Serialize;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// The files a snapshot is being written to, along with the most recent snapshot of each handed
+/// over in the meantime, if any. It is only locked to hand over or take a snapshot, not while
+/// writing one.
+static PENDING_SNAPSHOTS: Mutex<BTreeMap<PathBuf, Option<Vec<u8>>>> = Mutex::new(BTreeMap::new());
+
+/// Writes a snapshot of some state of the node, e.g. its address book, to the disk in the
+/// background.
+///
+/// The file is replaced through a temp file renamed over it, so it is never left half written.
+/// The snapshots of a file are written one at a time, the ones handed over during a write being
+/// replaced by the most recent of them, which is written next.
+pub(crate) fn persist_in_background<T: Serialize>(file_path: PathBuf, snapshot: T) {
+    let bytes = match rmp_serde::to_vec(&snapshot) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!("Failed to serialise the snapshot of {file_path:?}: {err:?}");
+            return;
+        }
+    };
+
+    match pending_snapshots().entry(file_path.clone()) {
+        Entry::Occupied(mut next_snapshot) => {
+            debug!("A snapshot of {file_path:?} is being written, the new one will follow it");
+            let _ = next_snapshot.insert(Some(bytes));
+            return;
+        }
+        Entry::Vacant(entry) => {
+            let _ = entry.insert(None);
+        }
+    }
+
+    spawn_blocking(move || write_snapshots(&file_path, bytes));
+}
+
+/// Waits for the snapshots of the file handed over so far to be written.
+#[cfg(test)]
+pub(crate) async fn snapshots_written(file_path: &Path) {
+    while pending_snapshots().contains_key(file_path) {
+        crate::target_arch::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+// Writes the snapshot, then the ones handed over in the meantime until there are no more.
+fn write_snapshots(file_path: &Path, mut bytes: Vec<u8>) {
+    loop {
+        if let Err(err) = write_snapshot(file_path, &bytes) {
+            warn!("Failed to persist the snapshot to {file_path:?}: {err:?}");
+        }
+
+        let mut pending_snapshots = pending_snapshots();
+        match pending_snapshots.get_mut(file_path).and_then(Option::take) {
+            Some(next_snapshot) => bytes = next_snapshot,
+            None => {
+                let _ = pending_snapshots.remove(file_path);
+                return;
+            }
+        }
+    }
+}
+
+fn write_snapshot(file_path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp_path = file_path.with_extension("tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, file_path)
+}
+
+fn pending_snapshots() -> MutexGuard<'static, BTreeMap<PathBuf, Option<Vec<u8>>>> {
+    PENDING_SNAPSHOTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn the_most_recent_snapshot_is_written_last() -> Result<()> {
+        let root_dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&root_dir)?;
+        let file_path = root_dir.join("snapshot");
+
+        for snapshot in 0..10u8 {
+            persist_in_background(file_path.clone(), vec![snapshot]);
+        }
+        snapshots_written(&file_path).await;
+
+        let restored: Vec<u8> = rmp_serde::from_slice(&fs::read(&file_path)?)?;
+        assert_eq!(restored, vec![9]);
+        assert!(!file_path.with_extension("tmp").exists());
+
+        fs::remove_dir_all(root_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use aes_gcm_siv::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256GcmSiv, Nonce,
+};
+use libp2p::kad::RecordKey as Key;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sn_protocol::PrettyPrintRecordKey;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// File name of the sealed record encryption keys, stored under the node's root dir.
+const RECORD_ENCRYPTION_KEY_FILENAME: &str = "record_encryption_key";
+
+/// Domain used to derive the key sealing secret from the node's keypair.
+pub(crate) const RECORD_ENCRYPTION_SEED_DOMAIN: &[u8] = b"safe_network record encryption key";
+
+/// Length of the raw key material: a 256 bit AES key followed by a 4 byte nonce starter.
+const RAW_KEY_MATERIAL_LEN: usize = 32 + 4;
+
+/// Encryption cipher for the records, plus a 4 byte nonce starter
+#[derive(Clone)]
+pub(crate) struct EncryptionDetails {
+    cipher: Aes256GcmSiv,
+    nonce_starter: [u8; 4],
+}
+
+impl EncryptionDetails {
+    fn from_raw(raw: &[u8]) -> Option<Self> {
+        if raw.len() != RAW_KEY_MATERIAL_LEN {
+            return None;
+        }
+        let cipher = Aes256GcmSiv::new_from_slice(&raw[..32]).ok()?;
+        let mut nonce_starter = [0u8; 4];
+        nonce_starter.copy_from_slice(&raw[32..]);
+        Some(Self {
+            cipher,
+            nonce_starter,
+        })
+    }
+
+    /// Encrypt a record value, the nonce is bound to the record key.
+    pub(crate) fn encrypt(&self, key: &Key, value: &[u8]) -> Option<Vec<u8>> {
+        let nonce = generate_nonce_for_record(&self.nonce_starter, key);
+        match self.cipher.encrypt(&nonce, value) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                warn!(
+                    "Failed to encrypt record {:?} : {error:?}",
+                    PrettyPrintRecordKey::from(key),
+                );
+                None
+            }
+        }
+    }
+
+    /// Decrypt a record value previously encrypted with the same key material.
+    pub(crate) fn decrypt(&self, key: &Key, bytes: &[u8]) -> Option<Vec<u8>> {
+        let nonce = generate_nonce_for_record(&self.nonce_starter, key);
+        self.cipher.decrypt(&nonce, bytes).ok()
+    }
+}
+
+/// Generate an encryption nonce for a given record key and nonce_starter bytes.
+fn generate_nonce_for_record(nonce_starter: &[u8; 4], key: &Key) -> Nonce {
+    let mut nonce_bytes = nonce_starter.to_vec();
+    nonce_bytes.extend_from_slice(key.as_ref());
+    // Ensure the final nonce is exactly 96 bits long by padding or truncating as necessary
+    // https://crypto.stackexchange.com/questions/26790/how-bad-it-is-using-the-same-iv-twice-with-aes-gcm
+    nonce_bytes.resize(12, 0); // 12 (u8) * 8 = 96 bits
+    Nonce::from_iter(nonce_bytes)
+}
+
+/// Key material sealed with the secret derived from the node's keypair.
+#[derive(Clone, Serialize, Deserialize)]
+struct SealedKey {
+    /// Incremented on every rotation.
+    generation: u64,
+    created: SystemTime,
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+}
+
+/// On-disk layout of the record encryption key file.
+#[derive(Clone, Serialize, Deserialize)]
+struct SealedKeyFile {
+    current: SealedKey,
+    /// Only present while a rotation is in progress, i.e. some records may still
+    /// be encrypted with it.
+    previous: Option<SealedKey>,
+}
+
+/// Holds the record encryption keys of a node, persisted under its root dir.
+///
+/// The keys themselves are random, and are sealed with a secret derived from the node's
+/// keypair before being written to disk. Hence a node restarted with the same keypair is
+/// able to decrypt the records it stored previously.
+pub(crate) struct RecordEncryptionKeys {
+    path: PathBuf,
+    sealing_cipher: Aes256GcmSiv,
+    current: (EncryptionDetails, SealedKey),
+    previous: Option<(EncryptionDetails, SealedKey)>,
+}
+
+impl RecordEncryptionKeys {
+    /// Loads the existing keys from `dir`, or generates and persists a fresh one if there is none.
+    ///
+    /// Any other failure to load the existing file, e.g. it cannot be unsealed because the node's
+    /// keypair changed, is returned as an error rather than replacing the key, as the records
+    /// encrypted with it could no longer be read.
+    pub(crate) fn load_or_create(dir: &Path, sealing_seed: &[u8; 32]) -> io::Result<Self> {
+        let path = dir.join(RECORD_ENCRYPTION_KEY_FILENAME);
+        let sealing_cipher = Aes256GcmSiv::new_from_slice(sealing_seed)
+            .expect("A 32 bytes seed is a valid AES-256 key");
+
+        match Self::load(&path, &sealing_cipher) {
+            Ok(keys) => {
+                info!(
+                    "Loaded record encryption key of generation {}",
+                    keys.current.1.generation
+                );
+                return Ok(keys);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                info!("No record encryption key found at {path:?}, generating a new one");
+            }
+            Err(err) => {
+                error!("Failed to load the record encryption key from {path:?}: {err:?}");
+                return Err(err);
+            }
+        }
+
+        let current = Self::generate_key(&sealing_cipher, 0);
+        let keys = Self {
+            path,
+            sealing_cipher,
+            current,
+            previous: None,
+        };
+        keys.persist()?;
+        Ok(keys)
+    }
+
+    fn load(path: &Path, sealing_cipher: &Aes256GcmSiv) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        let sealed_file: SealedKeyFile = rmp_serde::from_read(&file).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to parse the record encryption key file: {err}"),
+            )
+        })?;
+
+        let current = Self::unseal(sealing_cipher, &sealed_file.current).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Failed to unseal the record encryption key, has the keypair changed?",
+            )
+        })?;
+        let previous = sealed_file.previous.and_then(|sealed| {
+            let details = Self::unseal(sealing_cipher, &sealed)?;
+            Some((details, sealed))
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            sealing_cipher: sealing_cipher.clone(),
+            current: (current, sealed_file.current),
+            previous,
+        })
+    }
+
+    fn generate_key(
+        sealing_cipher: &Aes256GcmSiv,
+        generation: u64,
+    ) -> (EncryptionDetails, SealedKey) {
+        let mut raw = [0u8; RAW_KEY_MATERIAL_LEN];
+        OsRng.fill_bytes(&mut raw);
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = sealing_cipher
+            .encrypt(Nonce::from_slice(&nonce), raw.as_ref())
+            .expect("Sealing a fixed size key material shall not fail");
+        let details =
+            EncryptionDetails::from_raw(&raw).expect("Raw key material has the correct length");
+
+        let sealed = SealedKey {
+            generation,
+            created: SystemTime::now(),
+            nonce,
+            ciphertext,
+        };
+        (details, sealed)
+    }
+
+    fn unseal(sealing_cipher: &Aes256GcmSiv, sealed: &SealedKey) -> Option<EncryptionDetails> {
+        let raw = sealing_cipher
+            .decrypt(Nonce::from_slice(&sealed.nonce), sealed.ciphertext.as_ref())
+            .ok()?;
+        EncryptionDetails::from_raw(&raw)
+    }
+
+    /// Writes the sealed keys to a temp file first, then moves it into place,
+    /// so that a crash never leaves a half written key file behind.
+    fn persist(&self) -> io::Result<()> {
+        let sealed_file = SealedKeyFile {
+            current: self.current.1.clone(),
+            previous: self.previous.as_ref().map(|(_, sealed)| sealed.clone()),
+        };
+        let bytes = rmp_serde::to_vec(&sealed_file)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, &self.path)
+    }
+
+    /// The key that all records shall be encrypted with.
+    pub(crate) fn current(&self) -> &EncryptionDetails {
+        &self.current.0
+    }
+
+    /// The key replaced by an unfinished rotation, if any.
+    pub(crate) fn previous(&self) -> Option<&EncryptionDetails> {
+        self.previous.as_ref().map(|(details, _)| details)
+    }
+
+    /// Generation of the current key, starting from 0 and incremented on each rotation.
+    pub(crate) fn generation(&self) -> u64 {
+        self.current.1.generation
+    }
+
+    /// Decrypt with the current key, falling back to the previous key if a rotation is ongoing.
+    /// The returned flag is true if the previous key had to be used.
+    pub(crate) fn decrypt(&self, key: &Key, bytes: &[u8]) -> Option<(Vec<u8>, bool)> {
+        if let Some(value) = self.current().decrypt(key, bytes) {
+            return Some((value, false));
+        }
+        self.previous()
+            .and_then(|previous| previous.decrypt(key, bytes))
+            .map(|value| (value, true))
+    }
+
+    /// Generates a new current key, keeping the existing one as `previous` until
+    /// `finish_rotation` is called, so the records can be re-encrypted in between.
+    ///
+    /// If an earlier rotation is still unfinished, it is carried on instead of starting a new one,
+    /// as some records could still be encrypted with its previous key.
+    pub(crate) fn begin_rotation(&mut self) -> io::Result<()> {
+        if self.previous.is_some() {
+            warn!("An earlier record encryption key rotation is unfinished, resuming it");
+            return Ok(());
+        }
+
+        let new_key = Self::generate_key(&self.sealing_cipher, self.generation().saturating_add(1));
+        let old_key = std::mem::replace(&mut self.current, new_key);
+        self.previous = Some(old_key);
+        self.persist()?;
+
+        info!(
+            "Began rotation of the record encryption key to generation {}",
+            self.generation()
+        );
+        Ok(())
+    }
+
+    /// Drops the previous key once all the records have been re-encrypted with the current one.
+    pub(crate) fn finish_rotation(&mut self) -> io::Result<()> {
+        if self.previous.take().is_none() {
+            return Ok(());
+        }
+        self.persist()?;
+
+        info!(
+            "Finished rotation of the record encryption key to generation {}",
+            self.generation()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::PeerId;
+    use sn_protocol::NetworkAddress;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&dir).expect("Failed to create directory");
+        dir
+    }
+
+    #[test]
+    fn keys_are_restored_with_the_same_seed() -> io::Result<()> {
+        let dir = temp_dir();
+        let seed = [7u8; 32];
+        let key = NetworkAddress::from_peer(PeerId::random()).to_record_key();
+
+        let keys = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        let encrypted = keys
+            .current()
+            .encrypt(&key, b"record value")
+            .expect("Failed to encrypt");
+
+        let restored = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        assert_eq!(
+            restored.decrypt(&key, &encrypted),
+            Some((b"record value".to_vec(), false))
+        );
+
+        // A different seed cannot unseal the key, which is kept rather than replaced.
+        let other = RecordEncryptionKeys::load_or_create(&dir, &[8u8; 32]);
+        assert!(matches!(other, Err(err) if err.kind() == io::ErrorKind::InvalidData));
+        let restored = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        assert!(restored.decrypt(&key, &encrypted).is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotation_keeps_previous_key_until_finished() -> io::Result<()> {
+        let dir = temp_dir();
+        let seed = [9u8; 32];
+        let key = NetworkAddress::from_peer(PeerId::random()).to_record_key();
+
+        let mut keys = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        let encrypted = keys
+            .current()
+            .encrypt(&key, b"record value")
+            .expect("Failed to encrypt");
+
+        keys.begin_rotation()?;
+        assert_eq!(keys.generation(), 1);
+
+        // An interrupted rotation is picked up again on restart.
+        let mut restored = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        assert_eq!(
+            restored.decrypt(&key, &encrypted),
+            Some((b"record value".to_vec(), true))
+        );
+
+        restored.finish_rotation()?;
+        let restored = RecordEncryptionKeys::load_or_create(&dir, &seed)?;
+        assert_eq!(restored.generation(), 1);
+        assert!(restored.previous().is_none());
+        assert!(restored.decrypt(&key, &encrypted).is_none());
+
+        Ok(())
+    }
+}
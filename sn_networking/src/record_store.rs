@@ -8,15 +8,14 @@
 #![allow(clippy::mutable_key_type)] // for the Bytes in NetworkAddress
 
 use crate::driver::MAX_PACKET_SIZE;
+use crate::record_encryption::{EncryptionDetails, RecordEncryptionKeys};
+use crate::record_store_backend::{
+    record_filename, RecordStorageBackend, RecordStorageBackendKind, StoredRecord,
+};
 use crate::target_arch::{spawn, Instant};
 use crate::CLOSE_GROUP_SIZE;
 use crate::{cmd::SwarmCmd, event::NetworkEvent, log_markers::Marker, send_swarm_cmd};
-use aes_gcm_siv::{
-    aead::{Aead, KeyInit, OsRng},
-    Aes256GcmSiv, Nonce,
-};
 
-use itertools::Itertools;
 use libp2p::{
     identity::PeerId,
     kad::{
@@ -26,7 +25,6 @@ use libp2p::{
 };
 #[cfg(feature = "open-metrics")]
 use prometheus_client::metrics::gauge::Gauge;
-use rand::RngCore;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use sn_protocol::{
@@ -37,13 +35,16 @@ use sn_transfers::{NanoTokens, QuotingMetrics, TOTAL_SUPPLY};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
-    fs,
+    fs, io,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::SystemTime,
     vec,
 };
 use tokio::sync::mpsc;
-use walkdir::{DirEntry, WalkDir};
 use xor_name::XorName;
 
 // A spend record is at the size of 4KB roughly.
@@ -54,6 +55,10 @@ use xor_name::XorName;
 // this shall allow around 4K records.
 const MAX_RECORDS_COUNT: usize = 4096;
 
+/// The average record size assumed when pricing against a storage capacity
+/// before any record got stored.
+const ASSUMED_AVERAGE_RECORD_SIZE: u64 = 256 * 1024;
+
 /// File name of the recorded historical quoting metrics.
 const HISTORICAL_QUOTING_METRICS_FILENAME: &str = "historic_quoting_metrics";
 
@@ -67,6 +72,12 @@ pub struct NodeRecordStore {
     config: NodeRecordStoreConfig,
     /// A set of keys, each corresponding to a data `Record` stored on disk.
     records: HashMap<Key, (NetworkAddress, RecordType)>,
+    /// The size of each record, including the ones still being written to disk.
+    record_sizes: HashMap<Key, u64>,
+    /// The sum of `record_sizes`.
+    used_bytes: u64,
+    /// The storage holding the bytes of the records.
+    backend: Arc<dyn RecordStorageBackend>,
     /// Send network events to the node layer.
     network_event_sender: mpsc::Sender<NetworkEvent>,
     /// Send cmds to the network layer. Used to interact with self in an async fashion.
@@ -80,9 +91,8 @@ pub struct NodeRecordStore {
     record_count_metric: Option<Gauge>,
     /// Counting how many times got paid
     received_payment_count: usize,
-    /// Encyption cipher for the records, randomly generated at node startup
-    /// Plus a 4 byte nonce starter
-    encryption_details: (Aes256GcmSiv, [u8; 4]),
+    /// Encyption keys for the records, persisted under the node's root dir
+    encryption_keys: RecordEncryptionKeys,
     /// Time that this record_store got started
     timestamp: SystemTime,
     /// Farthest record to self
@@ -96,35 +106,45 @@ pub struct NodeRecordStoreConfig {
     pub storage_dir: PathBuf,
     /// The directory where the historic quote to be stored
     /// (normally to be the parent dir of the storage_dir)
+    /// The sealed record encryption key is stored here as well.
     pub historic_quote_dir: PathBuf,
     /// The maximum number of records.
+    /// Ignored if `max_storage_bytes` is set.
     pub max_records: usize,
+    /// The maximum number of bytes the records can take on disk.
+    /// When set, the store is considered full based on the size of the records instead of
+    /// their count, and `max_records` is estimated from the average record size when pricing.
+    pub max_storage_bytes: Option<u64>,
     /// The maximum size of record values, in bytes.
     pub max_value_bytes: usize,
+    /// The secret used to seal the record encryption key on disk.
+    /// Normally derived from the node's keypair, so that the key survives restarts.
+    pub encryption_seed: [u8; 32],
+    /// Rotate the record encryption key on startup, re-encrypting all existing records.
+    pub rotate_encryption_key: bool,
+    /// The backend storing the records under the `storage_dir`.
+    pub storage_backend: RecordStorageBackendKind,
 }
 
 impl Default for NodeRecordStoreConfig {
+    /// Stores under a fresh dir in the system temp dir, as the random `encryption_seed`
+    /// cannot unseal the record encryption key persisted by any other store.
     fn default() -> Self {
-        let historic_quote_dir = std::env::temp_dir();
+        let historic_quote_dir =
+            std::env::temp_dir().join(format!("safe-record-store-{:016x}", rand::random::<u64>()));
         Self {
             storage_dir: historic_quote_dir.clone(),
             historic_quote_dir,
             max_records: MAX_RECORDS_COUNT,
+            max_storage_bytes: None,
             max_value_bytes: MAX_PACKET_SIZE,
+            encryption_seed: rand::random(),
+            rotate_encryption_key: false,
+            storage_backend: RecordStorageBackendKind::default(),
         }
     }
 }
 
-/// Generate an encryption nonce for a given record key and nonce_starter bytes.
-fn generate_nonce_for_record(nonce_starter: &[u8; 4], key: &Key) -> Nonce {
-    let mut nonce_bytes = nonce_starter.to_vec();
-    nonce_bytes.extend_from_slice(key.as_ref());
-    // Ensure the final nonce is exactly 96 bits long by padding or truncating as necessary
-    // https://crypto.stackexchange.com/questions/26790/how-bad-it-is-using-the-same-iv-twice-with-aes-gcm
-    nonce_bytes.resize(12, 0); // 12 (u8) * 8 = 96 bits
-    Nonce::from_iter(nonce_bytes)
-}
-
 #[derive(Clone, Serialize, Deserialize)]
 struct HistoricQuotingMetrics {
     received_payment_count: usize,
@@ -132,74 +152,136 @@ struct HistoricQuotingMetrics {
 }
 
 impl NodeRecordStore {
-    /// If a directory for our node already exists, repopulate the records from the files in the dir
+    /// If a storage for our node already exists, repopulate the records and their sizes from it.
+    ///
+    /// Records still encrypted with the previous key of an unfinished rotation are re-encrypted
+    /// with the current key. Also returns the number of records that failed to be re-encrypted.
+    #[allow(clippy::type_complexity)]
     fn update_records_from_an_existing_store(
-        config: &NodeRecordStoreConfig,
-        encryption_details: &(Aes256GcmSiv, [u8; 4]),
-    ) -> HashMap<Key, (NetworkAddress, RecordType)> {
-        let process_entry = |entry: &DirEntry| -> _ {
-            let path = entry.path();
-            if path.is_file() {
-                trace!("Existing record found: {path:?}");
-                // if we've got a file, lets try and read it
-                let filename = match path.file_name().and_then(|n| n.to_str()) {
-                    Some(file_name) => file_name,
-                    None => {
-                        // warn and remove this file as it's not a valid record
-                        warn!(
-                            "Found a file in the storage dir that is not a valid record: {:?}",
-                            path
-                        );
-                        if let Err(e) = fs::remove_file(path) {
-                            warn!(
-                                "Failed to remove invalid record file from storage dir: {:?}",
-                                e
-                            );
-                        }
-                        return None;
-                    }
-                };
-                // get the record key from the filename
-                let key = Self::get_data_from_filename(filename)?;
-                let record = match fs::read(path) {
-                    Ok(bytes) => {
-                        // and the stored record
-                        Self::get_record_from_bytes(bytes, &key, encryption_details)?
-                    }
-                    Err(err) => {
-                        error!("Error while reading file. filename: {filename}, error: {err:?}");
-                        return None;
-                    }
-                };
+        backend: &dyn RecordStorageBackend,
+        encryption_keys: &RecordEncryptionKeys,
+    ) -> (
+        HashMap<Key, (NetworkAddress, RecordType)>,
+        HashMap<Key, u64>,
+        usize,
+    ) {
+        let reencryption_failures = AtomicUsize::new(0);
+        // All the records have to be read if some are still encrypted with the previous key.
+        let rotating_encryption_key = encryption_keys.previous().is_some();
+
+        let process_entry = |stored_record: &StoredRecord| -> _ {
+            let StoredRecord {
+                key,
+                record_type: indexed_record_type,
+                size,
+            } = stored_record;
+            let address = NetworkAddress::from_record_key(key);
+            if let (Some(record_type), false) = (indexed_record_type, rotating_encryption_key) {
+                trace!(
+                    "Existing record indexed: {:?}",
+                    PrettyPrintRecordKey::from(key)
+                );
+                return Some((key.clone(), (address, record_type.clone()), *size));
+            }
 
-                let record_type = match RecordHeader::is_record_of_type_chunk(&record) {
-                    Ok(true) => RecordType::Chunk,
-                    Ok(false) => {
-                        let xorname_hash = XorName::from_content(&record.value);
-                        RecordType::NonChunk(xorname_hash)
-                    }
-                    Err(error) => {
-                        warn!("Failed to parse record type from record: {:?}", error);
-                        return None;
-                    }
-                };
+            let filename = record_filename(key);
+            let (record, encrypted_with_previous_key) = match backend.read(key) {
+                Ok(bytes) => {
+                    // and the stored record
+                    Self::get_record_from_bytes(bytes, key, encryption_keys)?
+                }
+                Err(err) => {
+                    error!("Error while reading file. filename: {filename}, error: {err:?}");
+                    return None;
+                }
+            };
+
+            let record_type = match RecordHeader::is_record_of_type_chunk(&record) {
+                Ok(true) => RecordType::Chunk,
+                Ok(false) => {
+                    let xorname_hash = XorName::from_content(&record.value);
+                    RecordType::NonChunk(xorname_hash)
+                }
+                Err(error) => {
+                    warn!("Failed to parse record type from record: {:?}", error);
+                    return None;
+                }
+            };
 
-                let address = NetworkAddress::from_record_key(&key);
-                info!("Existing record loaded: {path:?}");
-                return Some((key, (address, record_type)));
+            if encrypted_with_previous_key {
+                if !Self::reencrypt_record(
+                    backend,
+                    &record,
+                    &record_type,
+                    encryption_keys.current(),
+                ) {
+                    let _ = reencryption_failures.fetch_add(1, Ordering::Relaxed);
+                }
+            } else if indexed_record_type.is_none() {
+                backend.index_record_type(key, &record_type);
             }
-            None
+
+            info!("Existing record loaded: {filename}");
+            Some((key.clone(), (address, record_type), *size))
         };
 
         info!("Attempting to repopulate records from existing store...");
-        let records = WalkDir::new(&config.storage_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .collect_vec()
+        let loaded: Vec<_> = backend
+            .stored_records()
             .par_iter()
             .filter_map(process_entry)
             .collect();
-        records
+        if let Err(err) = backend.flush() {
+            warn!("Failed to flush the record storage backend: {err:?}");
+        }
+
+        let mut records = HashMap::with_capacity(loaded.len());
+        let mut record_sizes = HashMap::with_capacity(loaded.len());
+        for (key, entry, size) in loaded {
+            let _ = record_sizes.insert(key.clone(), size);
+            let _ = records.insert(key, entry);
+        }
+        (records, record_sizes, reencryption_failures.into_inner())
+    }
+
+    /// Overwrites the stored record with its value encrypted by the provided key.
+    /// Returns false if the record could not be re-encrypted.
+    fn reencrypt_record(
+        backend: &dyn RecordStorageBackend,
+        record: &Record,
+        record_type: &RecordType,
+        encryption_details: &EncryptionDetails,
+    ) -> bool {
+        let filename = record_filename(&record.key);
+        let Some(bytes) = encryption_details.encrypt(&record.key, &record.value) else {
+            return false;
+        };
+        match backend.write(&record.key, &bytes, record_type) {
+            Ok(_) => {
+                trace!("Re-encrypted record {filename} with the current key");
+                true
+            }
+            Err(err) => {
+                error!("Error while re-encrypting record {filename}: {err:?}");
+                false
+            }
+        }
+    }
+
+    /// Loads the record encryption keys, rotating them if configured to.
+    fn load_encryption_keys(config: &NodeRecordStoreConfig) -> io::Result<RecordEncryptionKeys> {
+        let mut encryption_keys = RecordEncryptionKeys::load_or_create(
+            &config.historic_quote_dir,
+            &config.encryption_seed,
+        )?;
+
+        if config.rotate_encryption_key && cfg!(feature = "encrypt-records") {
+            if let Err(err) = encryption_keys.begin_rotation() {
+                error!("Failed to rotate the record encryption key: {err:?}");
+            }
+        }
+
+        Ok(encryption_keys)
     }
 
     /// If quote_metrics file already exists, using the existing parameters.
@@ -240,13 +322,9 @@ impl NodeRecordStore {
         config: NodeRecordStoreConfig,
         network_event_sender: mpsc::Sender<NetworkEvent>,
         swarm_cmd_sender: mpsc::Sender<SwarmCmd>,
-    ) -> Self {
-        let key = Aes256GcmSiv::generate_key(&mut OsRng);
-        let cipher = Aes256GcmSiv::new(&key);
-        let mut nonce_starter = [0u8; 4];
-        OsRng.fill_bytes(&mut nonce_starter);
-
-        let encryption_details = (cipher, nonce_starter);
+    ) -> io::Result<Self> {
+        let backend = config.storage_backend.open(&config.storage_dir)?;
+        let mut encryption_keys = Self::load_encryption_keys(&config)?;
 
         // Recover the quoting_metrics first, as the historical file will be cleaned by
         // the later on update_records_from_an_existing_store function
@@ -261,19 +339,36 @@ impl NodeRecordStore {
             (0, SystemTime::now())
         };
 
-        let records = Self::update_records_from_an_existing_store(&config, &encryption_details);
+        let (records, record_sizes, reencryption_failures) =
+            Self::update_records_from_an_existing_store(backend.as_ref(), &encryption_keys);
+        let used_bytes = record_sizes.values().sum();
+
+        // All records are now encrypted with the current key, the previous one can be dropped.
+        if encryption_keys.previous().is_some() {
+            if reencryption_failures == 0 {
+                if let Err(err) = encryption_keys.finish_rotation() {
+                    error!("Failed to finish the record encryption key rotation: {err:?}");
+                }
+            } else {
+                warn!("{reencryption_failures} records failed to be re-encrypted, keeping the previous encryption key");
+            }
+        }
+
         let mut record_store = NodeRecordStore {
             local_key: KBucketKey::from(local_id),
             local_address: NetworkAddress::from_peer(local_id),
             config,
             records,
+            record_sizes,
+            used_bytes,
+            backend,
             network_event_sender,
             swarm_cmd_sender,
             responsible_distance_range: None,
             #[cfg(feature = "open-metrics")]
             record_count_metric: None,
             received_payment_count,
-            encryption_details,
+            encryption_keys,
             timestamp,
             farthest_record: None,
         };
@@ -282,7 +377,7 @@ impl NodeRecordStore {
 
         record_store.flush_historic_quoting_metrics();
 
-        record_store
+        Ok(record_store)
     }
 
     /// Set the record_count_metric to report the number of records stored to the metrics server
@@ -297,28 +392,14 @@ impl NodeRecordStore {
         self.responsible_distance_range
     }
 
-    // Converts a Key into a Hex string.
-    fn generate_filename(key: &Key) -> String {
-        hex::encode(key.as_ref())
-    }
-
-    // Converts a Hex string back into a Key.
-    fn get_data_from_filename(hex_str: &str) -> Option<Key> {
-        match hex::decode(hex_str) {
-            Ok(bytes) => Some(Key::from(bytes)),
-            Err(error) => {
-                error!("Error decoding hex string: {:?}", error);
-                None
-            }
-        }
-    }
-
     /// Upon read perform any data transformations required to return a `Record`.
+    /// The returned flag is true if the record was encrypted with the previous key
+    /// of an unfinished rotation.
     fn get_record_from_bytes<'a>(
         bytes: Vec<u8>,
         key: &Key,
-        encryption_details: &(Aes256GcmSiv, [u8; 4]),
-    ) -> Option<Cow<'a, Record>> {
+        encryption_keys: &RecordEncryptionKeys,
+    ) -> Option<(Cow<'a, Record>, bool)> {
         let mut record = Record {
             key: key.clone(),
             value: bytes,
@@ -328,35 +409,34 @@ impl NodeRecordStore {
 
         // if we're not encrypting, lets just return the record
         if !cfg!(feature = "encrypt-records") {
-            return Some(Cow::Owned(record));
+            return Some((Cow::Owned(record), false));
         }
 
-        let (cipher, nonce_starter) = encryption_details;
-        let nonce = generate_nonce_for_record(nonce_starter, key);
-
-        match cipher.decrypt(&nonce, record.value.as_ref()) {
-            Ok(value) => {
+        match encryption_keys.decrypt(key, record.value.as_ref()) {
+            Some((value, encrypted_with_previous_key)) => {
                 record.value = value;
-                return Some(Cow::Owned(record));
+                Some((Cow::Owned(record), encrypted_with_previous_key))
             }
-            Err(error) => {
-                error!("Error while decrypting record. key: {key:?}: {error:?}");
+            None => {
+                error!(
+                    "Error while decrypting record. key: {key:?}, key generation: {}",
+                    encryption_keys.generation()
+                );
                 None
             }
         }
     }
 
     fn read_from_disk<'a>(
-        encryption_details: &(Aes256GcmSiv, [u8; 4]),
+        encryption_keys: &RecordEncryptionKeys,
         key: &Key,
-        storage_dir: &Path,
+        backend: &dyn RecordStorageBackend,
     ) -> Option<Cow<'a, Record>> {
         let start = Instant::now();
-        let filename = Self::generate_filename(key);
-        let file_path = storage_dir.join(&filename);
+        let filename = record_filename(key);
 
         // we should only be reading if we know the record is written to disk properly
-        match fs::read(file_path) {
+        match backend.read(key) {
             Ok(bytes) => {
                 // vdash metric (if modified please notify at https://github.com/happybeing/vdash/issues):
                 info!(
@@ -364,7 +444,7 @@ impl NodeRecordStore {
                     start.elapsed()
                 );
 
-                Self::get_record_from_bytes(bytes, key, encryption_details)
+                Self::get_record_from_bytes(bytes, key, encryption_keys).map(|(record, _)| record)
             }
             Err(err) => {
                 error!("Error while reading file. filename: {filename}, error: {err:?}");
@@ -399,25 +479,78 @@ impl NodeRecordStore {
         }
     }
 
+    /// Returns true if there is no room left for the incoming record.
+    /// An existing record under the same key is replaced, so its size is not accounted for.
+    fn is_full_for(&self, incoming_record_key: &Key, incoming_record_size: u64) -> bool {
+        match self.config.max_storage_bytes {
+            Some(max_storage_bytes) => {
+                let replaced_size = self
+                    .record_sizes
+                    .get(incoming_record_key)
+                    .copied()
+                    .unwrap_or(0);
+                self.used_bytes - replaced_size + incoming_record_size > max_storage_bytes
+            }
+            None => self.records.len() >= self.config.max_records,
+        }
+    }
+
+    /// The maximum number of records the store can hold.
+    /// With a storage capacity, this is estimated from the average size of the stored records.
+    fn max_records(&self) -> usize {
+        let Some(max_storage_bytes) = self.config.max_storage_bytes else {
+            return self.config.max_records;
+        };
+
+        let average_record_size = if self.record_sizes.is_empty() {
+            ASSUMED_AVERAGE_RECORD_SIZE
+        } else {
+            (self.used_bytes / self.record_sizes.len() as u64).max(1)
+        };
+        ((max_storage_bytes / average_record_size) as usize).max(1)
+    }
+
+    /// How full the store is, from 0 to 1.
+    pub(crate) fn fill_ratio(&self) -> f64 {
+        match self.config.max_storage_bytes {
+            Some(max_storage_bytes) => self.used_bytes as f64 / max_storage_bytes.max(1) as f64,
+            None => self.records.len() as f64 / self.config.max_records.max(1) as f64,
+        }
+        .min(1.0)
+    }
+
     /// Prune the records in the store to ensure that we free up space
     /// for the incoming record.
     /// Returns Ok if the record can be stored because it is closer to the local peer
     /// or we are not full.
     ///
-    /// Err MaxRecords if we cannot store as it's farther than the farthest data we have
-    fn prune_records_if_needed(&mut self, incoming_record_key: &Key) -> Result<()> {
-        // we're not full, so we don't need to prune
-        if self.records.len() < self.config.max_records {
-            return Ok(());
+    /// Err MaxRecords if we cannot store as it's farther than the farthest data we have,
+    /// or if it is larger than the whole storage capacity.
+    fn prune_records_if_needed(
+        &mut self,
+        incoming_record_key: &Key,
+        incoming_record_size: u64,
+    ) -> Result<()> {
+        if let Some(max_storage_bytes) = self.config.max_storage_bytes {
+            if incoming_record_size > max_storage_bytes {
+                return Err(Error::MaxRecords);
+            }
         }
 
-        if let Some((farthest_record, farthest_record_distance)) = self.farthest_record.clone() {
+        let incoming_record_distance = self
+            .local_address
+            .distance(&NetworkAddress::from_record_key(incoming_record_key));
+
+        // keep pruning until there is enough room for the incoming record
+        while self.is_full_for(incoming_record_key, incoming_record_size) {
+            let Some((farthest_record, farthest_record_distance)) = self.farthest_record.clone()
+            else {
+                // the space is taken by records still being written, nothing to prune yet
+                return Err(Error::MaxRecords);
+            };
+
             // if the incoming record is farther than the farthest record, we can't store it
-            if farthest_record_distance
-                < self
-                    .local_address
-                    .distance(&NetworkAddress::from_record_key(incoming_record_key))
-            {
+            if farthest_record_distance < incoming_record_distance {
                 return Err(Error::MaxRecords);
             }
 
@@ -430,6 +563,14 @@ impl NodeRecordStore {
 
         Ok(())
     }
+
+    /// Accounts for the size of a record about to be written, replacing any previous size.
+    fn track_record_size(&mut self, key: Key, size: u64) {
+        if let Some(previous_size) = self.record_sizes.insert(key, size) {
+            self.used_bytes -= previous_size;
+        }
+        self.used_bytes += size;
+    }
 }
 
 impl NodeRecordStore {
@@ -476,25 +617,13 @@ impl NodeRecordStore {
     /// If feats are enabled, this will eg, encrypt the record for storage
     fn prepare_record_bytes(
         record: Record,
-        encryption_details: (Aes256GcmSiv, [u8; 4]),
+        encryption_details: EncryptionDetails,
     ) -> Option<Vec<u8>> {
         if !cfg!(feature = "encrypt-records") {
             return Some(record.value);
         }
 
-        let (cipher, nonce_starter) = encryption_details;
-        let nonce = generate_nonce_for_record(&nonce_starter, &record.key);
-
-        match cipher.encrypt(&nonce, record.value.as_ref()) {
-            Ok(value) => Some(value),
-            Err(error) => {
-                warn!(
-                    "Failed to encrypt record {:?} : {error:?}",
-                    PrettyPrintRecordKey::from(&record.key),
-                );
-                None
-            }
-        }
+        encryption_details.encrypt(&record.key, &record.value)
     }
 
     /// Warning: Write's a `Record` to disk without validation
@@ -506,22 +635,25 @@ impl NodeRecordStore {
         let record_key = PrettyPrintRecordKey::from(&r.key).into_owned();
         trace!("PUT a verified Record: {record_key:?}");
 
-        self.prune_records_if_needed(&r.key)?;
+        let record_size = r.value.len() as u64;
+        self.prune_records_if_needed(&r.key, record_size)?;
+        self.track_record_size(r.key.clone(), record_size);
 
-        let filename = Self::generate_filename(&r.key);
-        let file_path = self.config.storage_dir.join(&filename);
+        let filename = record_filename(&r.key);
 
         #[cfg(feature = "open-metrics")]
         if let Some(metric) = &self.record_count_metric {
             let _ = metric.set(self.records.len() as i64);
         }
 
-        let encryption_details = self.encryption_details.clone();
+        let encryption_details = self.encryption_keys.current().clone();
+        let backend = Arc::clone(&self.backend);
         let cloned_cmd_sender = self.swarm_cmd_sender.clone();
         spawn(async move {
             let key = r.key.clone();
-            if let Some(bytes) = Self::prepare_record_bytes(r, encryption_details) {
-                let cmd = match fs::write(&file_path, bytes) {
+            // the size of the record is tracked already, it has to be removed if not written
+            let cmd = match Self::prepare_record_bytes(r, encryption_details) {
+                Some(bytes) => match backend.write(&key, &bytes, &record_type) {
                     Ok(_) => {
                         // vdash metric (if modified please notify at https://github.com/happybeing/vdash/issues):
                         info!("Wrote record {record_key:?} to disk! filename: {filename}");
@@ -534,10 +666,14 @@ impl NodeRecordStore {
                     );
                         SwarmCmd::RemoveFailedLocalRecord { key }
                     }
-                };
+                },
+                None => {
+                    error!("Error preparing record {record_key:?} to be written to disk");
+                    SwarmCmd::RemoveFailedLocalRecord { key }
+                }
+            };
 
-                send_swarm_cmd(cloned_cmd_sender, cmd);
-            }
+            send_swarm_cmd(cloned_cmd_sender, cmd);
         });
 
         Ok(())
@@ -557,7 +693,7 @@ impl NodeRecordStore {
 
         let mut quoting_metrics = QuotingMetrics {
             close_records_stored: records_stored,
-            max_records: self.config.max_records,
+            max_records: self.max_records(),
             received_payment_count: self.received_payment_count,
             live_time,
         };
@@ -641,7 +777,7 @@ impl RecordStore for NodeRecordStore {
 
         debug!("GET request for Record key: {key}");
 
-        Self::read_from_disk(&self.encryption_details, k, &self.config.storage_dir)
+        Self::read_from_disk(&self.encryption_keys, k, self.backend.as_ref())
     }
 
     fn put(&mut self, record: Record) -> Result<()> {
@@ -708,6 +844,9 @@ impl RecordStore for NodeRecordStore {
 
     fn remove(&mut self, k: &Key) {
         let _ = self.records.remove(k);
+        if let Some(size) = self.record_sizes.remove(k) {
+            self.used_bytes -= size;
+        }
         #[cfg(feature = "open-metrics")]
         if let Some(metric) = &self.record_count_metric {
             let _ = metric.set(self.records.len() as i64);
@@ -719,11 +858,12 @@ impl RecordStore for NodeRecordStore {
             }
         }
 
-        let filename = Self::generate_filename(k);
-        let file_path = self.config.storage_dir.join(&filename);
+        let filename = record_filename(k);
+        let key = k.clone();
+        let backend = Arc::clone(&self.backend);
 
         let _handle = spawn(async move {
-            match fs::remove_file(file_path) {
+            match backend.remove(&key) {
                 Ok(_) => {
                     info!("Removed record from disk! filename: {filename}");
                 }
@@ -871,6 +1011,7 @@ mod tests {
     use crate::{close_group_majority, sort_peers_by_key, REPLICATION_PEERS_COUNT};
     use bytes::Bytes;
     use eyre::ContextCompat;
+    use itertools::Itertools;
     use libp2p::{core::multihash::Multihash, kad::RecordKey};
     use quickcheck::*;
     use sn_protocol::storage::{try_serialize_record, ChunkAddress};
@@ -1035,7 +1176,8 @@ mod tests {
             Default::default(),
             network_event_sender,
             swarm_cmd_sender,
-        );
+        )
+        .expect("Failed to open the record store");
 
         let store_cost_before = store.store_cost(&r.key);
         // An initial unverified put should not write to disk
@@ -1098,6 +1240,93 @@ mod tests {
         assert!(store.get(&r.key).is_none());
     }
 
+    #[tokio::test]
+    async fn pruning_on_full_storage_capacity() -> Result<()> {
+        let capacity_in_records = 10;
+
+        let temp_dir = std::env::temp_dir();
+        let unique_dir_name = uuid::Uuid::new_v4().to_string();
+        let storage_dir = temp_dir.join(unique_dir_name);
+        fs::create_dir_all(&storage_dir).expect("Failed to create directory");
+
+        let self_id = PeerId::random();
+        let self_address = NetworkAddress::from_peer(self_id);
+        let (network_event_sender, _) = mpsc::channel(1);
+        let (swarm_cmd_sender, _) = mpsc::channel(1);
+
+        let generate_record = || {
+            let value = match try_serialize_record(
+                &(0..50).map(|_| rand::random::<u8>()).collect::<Bytes>(),
+                RecordKind::Chunk,
+            ) {
+                Ok(value) => value.to_vec(),
+                Err(err) => panic!("Cannot generate record value {err:?}"),
+            };
+            Record {
+                key: NetworkAddress::from_peer(PeerId::random()).to_record_key(),
+                value,
+                publisher: None,
+                expires: None,
+            }
+        };
+        let record_size = generate_record().value.len() as u64;
+
+        // The count limit is left at its default, only the capacity in bytes shall apply.
+        let store_config = NodeRecordStoreConfig {
+            historic_quote_dir: storage_dir.clone(),
+            storage_dir,
+            max_storage_bytes: Some(capacity_in_records * record_size),
+            ..Default::default()
+        };
+        let mut store = NodeRecordStore::with_config(
+            self_id,
+            store_config,
+            network_event_sender,
+            swarm_cmd_sender,
+        )
+        .expect("Failed to open the record store");
+
+        let mut failed_records = vec![];
+        for _ in 0..capacity_in_records * 3 {
+            let record = generate_record();
+            let record_key = record.key.clone();
+            if store.put_verified(record, RecordType::Chunk).is_ok() {
+                store.mark_as_stored(record_key, RecordType::Chunk);
+            } else {
+                failed_records.push(record_key);
+            }
+        }
+
+        let stored_records = store.record_addresses();
+        assert_eq!(stored_records.len() as u64, capacity_in_records);
+        assert_eq!(store.used_bytes, capacity_in_records * record_size);
+
+        // The pricing shall be based on the capacity, estimated from the stored records.
+        let (_cost, quoting_metrics) = store.store_cost(&generate_record().key);
+        assert_eq!(quoting_metrics.max_records as u64, capacity_in_records);
+
+        // A record larger than the whole capacity can never be stored.
+        let mut oversized_record = generate_record();
+        oversized_record.value = vec![0; (capacity_in_records * record_size) as usize + 1];
+        assert!(store
+            .put_verified(oversized_record, RecordType::Chunk)
+            .is_err());
+
+        // All failed records shall be farther than the farthest stored record.
+        if let Some(farthest_stored) = stored_records
+            .keys()
+            .map(|addr| self_address.distance(addr))
+            .max()
+        {
+            for failed_record in failed_records {
+                let failed_addr = NetworkAddress::from_record_key(&failed_record);
+                assert!(self_address.distance(&failed_addr) > farthest_stored);
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn pruning_on_full() -> Result<()> {
         let max_iterations = 10;
@@ -1114,6 +1343,7 @@ mod tests {
         // check there is an expected pruning behaviour got carried out.
         let store_config = NodeRecordStoreConfig {
             max_records,
+            historic_quote_dir: storage_dir.clone(),
             storage_dir,
             ..Default::default()
         };
@@ -1126,7 +1356,8 @@ mod tests {
             store_config.clone(),
             network_event_sender,
             swarm_cmd_sender,
-        );
+        )
+        .expect("Failed to open the record store");
         // keep track of everything ever stored, to check missing at the end are further away
         let mut stored_records_at_some_point: Vec<RecordKey> = vec![];
         let self_address = NetworkAddress::from_peer(self_id);
@@ -1236,10 +1467,12 @@ mod tests {
         let temp_dir = std::env::temp_dir();
         let unique_dir_name = uuid::Uuid::new_v4().to_string();
         let storage_dir = temp_dir.join(unique_dir_name);
+        fs::create_dir_all(&storage_dir).expect("Failed to create directory");
 
         // setup the store
         let store_config = NodeRecordStoreConfig {
             max_records,
+            historic_quote_dir: storage_dir.clone(),
             storage_dir,
             ..Default::default()
         };
@@ -1251,7 +1484,8 @@ mod tests {
             store_config,
             network_event_sender,
             swarm_cmd_sender,
-        );
+        )
+        .expect("Failed to open the record store");
 
         let mut stored_records: Vec<RecordKey> = vec![];
         let self_address = NetworkAddress::from_peer(self_id);
@@ -1336,7 +1570,8 @@ mod tests {
             store_config.clone(),
             network_event_sender.clone(),
             swarm_cmd_sender.clone(),
-        );
+        )
+        .expect("Failed to open the record store");
 
         store.payment_received();
 
@@ -1348,7 +1583,8 @@ mod tests {
             store_config,
             network_event_sender,
             swarm_cmd_sender,
-        );
+        )
+        .expect("Failed to open the record store");
 
         assert_eq!(1, new_store.received_payment_count);
         assert_eq!(store.timestamp, new_store.timestamp);
@@ -1356,6 +1592,92 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn records_survive_restart_with_key_rotation() -> eyre::Result<()> {
+        restart_with_key_rotation(RecordStorageBackendKind::FlatFile).await
+    }
+
+    #[tokio::test]
+    async fn records_survive_restart_with_append_log_backend() -> eyre::Result<()> {
+        restart_with_key_rotation(RecordStorageBackendKind::AppendLog).await
+    }
+
+    async fn restart_with_key_rotation(
+        storage_backend: RecordStorageBackendKind,
+    ) -> eyre::Result<()> {
+        let temp_dir = std::env::temp_dir();
+        let unique_dir_name = uuid::Uuid::new_v4().to_string();
+        let root_dir = temp_dir.join(unique_dir_name);
+        let storage_dir = root_dir.join("record_store");
+        fs::create_dir_all(&storage_dir).expect("Failed to create directory");
+
+        let store_config = NodeRecordStoreConfig {
+            storage_dir,
+            historic_quote_dir: root_dir,
+            storage_backend,
+            ..Default::default()
+        };
+        let self_id = PeerId::random();
+        let (network_event_sender, _) = mpsc::channel(1);
+        let (swarm_cmd_sender, _) = mpsc::channel(1);
+
+        let mut store = NodeRecordStore::with_config(
+            self_id,
+            store_config.clone(),
+            network_event_sender.clone(),
+            swarm_cmd_sender.clone(),
+        )
+        .expect("Failed to open the record store");
+
+        let record_key = NetworkAddress::from_peer(PeerId::random()).to_record_key();
+        let value = match try_serialize_record(
+            &(0..50).map(|_| rand::random::<u8>()).collect::<Bytes>(),
+            RecordKind::Chunk,
+        ) {
+            Ok(value) => value.to_vec(),
+            Err(err) => panic!("Cannot generate record value {err:?}"),
+        };
+        let record = Record {
+            key: record_key.clone(),
+            value,
+            publisher: None,
+            expires: None,
+        };
+        assert!(store
+            .put_verified(record.clone(), RecordType::Chunk)
+            .is_ok());
+        store.mark_as_stored(record_key.clone(), RecordType::Chunk);
+
+        // Wait for a while to allow the file written to disk.
+        sleep(Duration::from_millis(1000)).await;
+
+        // Restart with the same seed, rotating the key on startup.
+        let new_store = NodeRecordStore::with_config(
+            self_id,
+            NodeRecordStoreConfig {
+                rotate_encryption_key: true,
+                ..store_config.clone()
+            },
+            network_event_sender.clone(),
+            swarm_cmd_sender.clone(),
+        )
+        .expect("Failed to open the record store");
+        assert_eq!(Some(Cow::Borrowed(&record)), new_store.get(&record_key));
+        assert!(new_store.encryption_keys.previous().is_none());
+
+        // And once more without rotation.
+        let new_store = NodeRecordStore::with_config(
+            self_id,
+            store_config,
+            network_event_sender,
+            swarm_cmd_sender,
+        )
+        .expect("Failed to open the record store");
+        assert_eq!(Some(Cow::Borrowed(&record)), new_store.get(&record_key));
+
+        Ok(())
+    }
+
     #[test]
     fn address_distribution_sim() {
         // Map of peers and correspondent stats of `(num_of_records, Nano_earned, received_payment_count)`.
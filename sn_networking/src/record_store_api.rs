@@ -160,6 +160,16 @@ impl UnifiedRecordStore {
         }
     }
 
+    pub(crate) fn fill_ratio(&self) -> f64 {
+        match self {
+            Self::Client(_store) => {
+                warn!("Calling fill_ratio at Client. This should not happen");
+                0.0
+            }
+            Self::Node(store) => store.fill_ratio(),
+        }
+    }
+
     /// Mark the record as stored in the store.
     /// This adds it to records set, so it can now be retrieved
     /// (to be done after writes are finalised)
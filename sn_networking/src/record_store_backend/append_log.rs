@@ -0,0 +1,817 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+#![allow(clippy::mutable_key_type)] // for the Bytes in RecordKey
+
+use super::{flat_file::FlatFileBackend, RecordStorageBackend, StoredRecord};
+use libp2p::kad::RecordKey as Key;
+use serde::{Deserialize, Serialize};
+use sn_protocol::{storage::RecordType, PrettyPrintRecordKey};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread::JoinHandle,
+};
+
+/// File name of the log holding all the records.
+const LOG_FILENAME: &str = "records.log";
+/// File name of the compacted index snapshot.
+const INDEX_FILENAME: &str = "records.idx";
+/// File name of the log being written during a compaction.
+const COMPACTING_LOG_FILENAME: &str = "records.log.compacting";
+/// File name of the index snapshot being written.
+const TMP_INDEX_FILENAME: &str = "records.idx.tmp";
+
+/// Persist the index snapshot after this many writes or removals.
+const SNAPSHOT_INTERVAL: usize = 256;
+/// Only compact once the log holds at least this many bytes of removed or replaced records.
+const MIN_DEAD_BYTES_FOR_COMPACTION: u64 = 64 * 1024 * 1024;
+
+/// Returns true if the file name belongs to the append log backend.
+pub(super) fn is_backend_file(filename: &str) -> bool {
+    filename.starts_with(LOG_FILENAME) || filename.starts_with(INDEX_FILENAME)
+}
+
+/// Header preceding every entry in the log.
+/// On disk, an entry is `[u32 LE header len][header][value]`.
+#[derive(Serialize, Deserialize)]
+struct LogEntryHeader {
+    key: Vec<u8>,
+    /// Not known for records migrated from the flat file layout.
+    record_type: Option<RecordType>,
+    value_len: u64,
+    /// Marks the removal of the record.
+    tombstone: bool,
+}
+
+/// Location of a live record within the log.
+#[derive(Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Offset of the value.
+    offset: u64,
+    value_len: u64,
+    /// The whole length of the entry, header included.
+    entry_len: u64,
+    record_type: Option<RecordType>,
+}
+
+/// The index as persisted to disk, valid for the first `log_len` bytes of the log.
+#[derive(Serialize, Deserialize)]
+struct IndexSnapshot {
+    log_len: u64,
+    dead_bytes: u64,
+    entries: Vec<(Vec<u8>, IndexEntry)>,
+}
+
+/// The live records of the log, along with a handle to read them.
+struct Index {
+    entries: HashMap<Key, IndexEntry>,
+    /// A handle of its own, so that reads neither move the cursor of the appends
+    /// nor wait for them.
+    reader: Arc<Mutex<File>>,
+}
+
+/// The state of the appends to the log, which only the writes wait for.
+struct Writer {
+    log: File,
+    log_len: u64,
+    /// Bytes of the log used by removed or replaced records, reclaimed on compaction.
+    dead_bytes: u64,
+    changes_since_snapshot: usize,
+    /// Set while a compaction is running in the background.
+    compacting: bool,
+}
+
+/// Stores all the records in a single append-only log, with an in-memory index of the live
+/// records. The index is periodically persisted, so that only the tail of the log written
+/// after the last snapshot has to be replayed on startup.
+///
+/// Appends are only synced to disk along with the index snapshot, i.e. every
+/// `SNAPSHOT_INTERVAL` changes, on `flush` and on drop. The records written since then may be
+/// lost on a power failure, to be fetched again through replication.
+///
+/// The writes, index snapshots and compactions hold the writer lock, while the reads only
+/// take the index lock, which is held no longer than to update the index or swap in the
+/// compacted log. Once enough space can be reclaimed, the log is compacted on a background
+/// thread.
+pub(crate) struct AppendLogBackend {
+    storage_dir: PathBuf,
+    index: Arc<RwLock<Index>>,
+    writer: Arc<Mutex<Writer>>,
+    compaction: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AppendLogBackend {
+    /// Opens the log under `storage_dir`, restoring the index from the snapshot and the log.
+    pub(crate) fn open(storage_dir: &Path) -> io::Result<Self> {
+        let log_path = storage_dir.join(LOG_FILENAME);
+        let index_path = storage_dir.join(INDEX_FILENAME);
+
+        // a compaction interrupted before the swap is simply started over
+        match fs::remove_file(storage_dir.join(COMPACTING_LOG_FILENAME)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&log_path)?;
+        let file_len = log.metadata()?.len();
+
+        let mut writer = Writer {
+            log: log.try_clone()?,
+            log_len: 0,
+            dead_bytes: 0,
+            changes_since_snapshot: 0,
+            compacting: false,
+        };
+        let mut entries = HashMap::new();
+        match Self::load_snapshot(&index_path) {
+            Some(snapshot) if snapshot.log_len <= file_len => {
+                writer.log_len = snapshot.log_len;
+                writer.dead_bytes = snapshot.dead_bytes;
+                entries = snapshot
+                    .entries
+                    .into_iter()
+                    .map(|(key, entry)| (Key::from(key), entry))
+                    .collect();
+            }
+            _ => info!("No valid record index snapshot found, replaying the whole log"),
+        }
+
+        let replayed = Self::replay(&mut log, &mut writer, &mut entries, file_len)?;
+        info!(
+            "Opened record log {log_path:?} with {} records, replayed {replayed} entries",
+            entries.len()
+        );
+
+        let index = RwLock::new(Index {
+            entries,
+            reader: Arc::new(Mutex::new(File::open(&log_path)?)),
+        });
+        if replayed > 0 {
+            Self::snapshot(storage_dir, &mut writer, &index)?;
+        }
+        Ok(Self {
+            storage_dir: storage_dir.to_path_buf(),
+            index: Arc::new(index),
+            writer: Arc::new(Mutex::new(writer)),
+            compaction: Mutex::new(None),
+        })
+    }
+
+    fn load_snapshot(index_path: &Path) -> Option<IndexSnapshot> {
+        let file = File::open(index_path).ok()?;
+        match rmp_serde::from_read(&file) {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                warn!("Failed to parse the record index snapshot {index_path:?}: {err:?}");
+                None
+            }
+        }
+    }
+
+    /// Applies the entries of the log written after `writer.log_len`.
+    ///
+    /// A partially written entry at the end of the log (e.g. due to a crash) is truncated.
+    /// As entries cannot be told apart without a valid header, a corrupted header truncates
+    /// the log as well, dropping all the entries written after it.
+    fn replay(
+        log: &mut File,
+        writer: &mut Writer,
+        entries: &mut HashMap<Key, IndexEntry>,
+        file_len: u64,
+    ) -> io::Result<usize> {
+        let mut replayed = 0;
+        let _ = log.seek(SeekFrom::Start(writer.log_len))?;
+        let mut reader = io::BufReader::new(log);
+
+        while writer.log_len < file_len {
+            let offset = writer.log_len;
+            let header = match Self::read_header(&mut reader, file_len - offset) {
+                Ok(header) => header,
+                Err(err) => {
+                    warn!("Truncating record log at {offset} due to a corrupted entry: {err:?}");
+                    reader.get_mut().set_len(offset)?;
+                    break;
+                }
+            };
+            let (header, header_len) = header;
+            let entry_len = header_len + header.value_len;
+            if offset + entry_len > file_len {
+                warn!("Truncating record log at {offset} due to a partially written entry");
+                reader.get_mut().set_len(offset)?;
+                break;
+            }
+            // skip over the value
+            reader.seek_relative(header.value_len as i64)?;
+
+            Self::apply(writer, entries, header, offset + header_len, entry_len);
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+
+    /// Reads an entry header, returning it along with the bytes it took in the log.
+    fn read_header(reader: &mut impl Read, remaining: u64) -> io::Result<(LogEntryHeader, u64)> {
+        let mut len_bytes = [0u8; 4];
+        reader.read_exact(&mut len_bytes)?;
+        let header_len = u32::from_le_bytes(len_bytes) as u64;
+        if header_len + 4 > remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "entry header exceeds the log",
+            ));
+        }
+
+        let mut header_bytes = vec![0u8; header_len as usize];
+        reader.read_exact(&mut header_bytes)?;
+        let header = rmp_serde::from_slice(&header_bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok((header, header_len + 4))
+    }
+
+    /// Updates the index with an entry of the log.
+    fn apply(
+        writer: &mut Writer,
+        entries: &mut HashMap<Key, IndexEntry>,
+        header: LogEntryHeader,
+        value_offset: u64,
+        entry_len: u64,
+    ) {
+        let key = Key::from(header.key);
+        let replaced = if header.tombstone {
+            // the tombstone itself is dead as soon as it is written
+            writer.dead_bytes += entry_len;
+            entries.remove(&key)
+        } else {
+            entries.insert(
+                key,
+                IndexEntry {
+                    offset: value_offset,
+                    value_len: header.value_len,
+                    entry_len,
+                    record_type: header.record_type,
+                },
+            )
+        };
+        if let Some(replaced) = replaced {
+            writer.dead_bytes += replaced.entry_len;
+        }
+        writer.log_len = value_offset + header.value_len;
+    }
+
+    fn encode_entry(header: &LogEntryHeader, value: &[u8]) -> io::Result<Vec<u8>> {
+        let header_bytes = rmp_serde::to_vec(header)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let header_len = u32::try_from(header_bytes.len())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let mut bytes = Vec::with_capacity(4 + header_bytes.len() + value.len());
+        bytes.extend_from_slice(&header_len.to_le_bytes());
+        bytes.extend_from_slice(&header_bytes);
+        bytes.extend_from_slice(value);
+        Ok(bytes)
+    }
+
+    /// Appends an entry to the log and applies it to the index.
+    fn append(&self, writer: &mut Writer, header: LogEntryHeader, value: &[u8]) -> io::Result<()> {
+        let bytes = Self::encode_entry(&header, value)?;
+        let offset = writer.log_len;
+        let _ = writer.log.seek(SeekFrom::Start(offset))?;
+        if let Err(err) = writer.log.write_all(&bytes) {
+            // drop whatever got partially written, so the log stays consistent
+            let _ = writer.log.set_len(offset);
+            return Err(err);
+        }
+
+        let entry_len = bytes.len() as u64;
+        let value_offset = offset + entry_len - header.value_len;
+        Self::apply(
+            writer,
+            &mut Self::write_index(&self.index).entries,
+            header,
+            value_offset,
+            entry_len,
+        );
+
+        writer.changes_since_snapshot += 1;
+        if writer.changes_since_snapshot >= SNAPSHOT_INTERVAL {
+            Self::snapshot(&self.storage_dir, writer, &self.index)?;
+        }
+        Ok(())
+    }
+
+    /// Persists the index, so it does not need to be rebuilt from the whole log on startup.
+    /// The index is only locked to be copied, not while the snapshot is written.
+    fn snapshot(storage_dir: &Path, writer: &mut Writer, index: &RwLock<Index>) -> io::Result<()> {
+        writer.log.sync_data()?;
+        let entries = Self::read_index(index)
+            .entries
+            .iter()
+            .map(|(key, entry)| (key.to_vec(), entry.clone()))
+            .collect();
+        let snapshot = IndexSnapshot {
+            log_len: writer.log_len,
+            dead_bytes: writer.dead_bytes,
+            entries,
+        };
+        let bytes = rmp_serde::to_vec(&snapshot)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let tmp_path = storage_dir.join(TMP_INDEX_FILENAME);
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, storage_dir.join(INDEX_FILENAME))?;
+        writer.changes_since_snapshot = 0;
+        Ok(())
+    }
+
+    /// Starts compacting the log on a background thread, once enough space can be reclaimed.
+    fn compact_if_needed(&self, writer: &mut Writer) {
+        let live_bytes = writer.log_len.saturating_sub(writer.dead_bytes);
+        if writer.compacting
+            || writer.dead_bytes < MIN_DEAD_BYTES_FOR_COMPACTION
+            || writer.dead_bytes < live_bytes
+        {
+            return;
+        }
+
+        info!(
+            "Compacting record log, reclaiming {} bytes out of {}",
+            writer.dead_bytes, writer.log_len
+        );
+        writer.compacting = true;
+        let storage_dir = self.storage_dir.clone();
+        let shared_writer = Arc::clone(&self.writer);
+        let index = Arc::clone(&self.index);
+        let handle = std::thread::spawn(move || {
+            if let Err(err) = Self::compact(&storage_dir, &shared_writer, &index) {
+                error!("Failed to compact the record log: {err:?}");
+            }
+            Self::lock_writer(&shared_writer).compacting = false;
+        });
+
+        let previous = self
+            .compaction
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .replace(handle);
+        // the previous compaction has already flagged itself as done
+        if let Some(previous) = previous {
+            let _ = previous.join();
+        }
+    }
+
+    /// Rewrites the log with the live records only.
+    ///
+    /// The records live when the compaction starts are copied without holding any lock.
+    /// Whatever got appended in the meantime is then copied over as is under the writer lock,
+    /// before the compacted log takes the place of the old one. The reads only wait for the
+    /// index of the compacted log to be swapped in.
+    fn compact(
+        storage_dir: &Path,
+        shared_writer: &Mutex<Writer>,
+        index: &RwLock<Index>,
+    ) -> io::Result<()> {
+        let log_path = storage_dir.join(LOG_FILENAME);
+        let tmp_path = storage_dir.join(COMPACTING_LOG_FILENAME);
+
+        let (live, start_len) = {
+            let writer = Self::lock_writer(shared_writer);
+            let live: Vec<_> = Self::read_index(index)
+                .entries
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.clone()))
+                .collect();
+            (live, writer.log_len)
+        };
+
+        // A separate handle, so that reading does not move the cursor of the appends.
+        let mut old_log = File::open(&log_path)?;
+        let mut compacted = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        let mut entries = HashMap::with_capacity(live.len());
+        let mut log_len = 0;
+
+        for (key, entry) in live {
+            let value = Self::read_value(&mut old_log, &entry)?;
+            let header = LogEntryHeader {
+                key: key.to_vec(),
+                record_type: entry.record_type.clone(),
+                value_len: entry.value_len,
+                tombstone: false,
+            };
+            let bytes = Self::encode_entry(&header, &value)?;
+            compacted.write_all(&bytes)?;
+
+            let entry_len = bytes.len() as u64;
+            let _ = entries.insert(
+                key,
+                IndexEntry {
+                    offset: log_len + entry_len - entry.value_len,
+                    value_len: entry.value_len,
+                    entry_len,
+                    record_type: entry.record_type,
+                },
+            );
+            log_len += entry_len;
+        }
+
+        let mut writer = Self::lock_writer(shared_writer);
+
+        // copy over the entries appended since the compaction started
+        let tail_len = writer.log_len - start_len;
+        let _ = old_log.seek(SeekFrom::Start(start_len))?;
+        let copied = io::copy(&mut (&mut old_log).take(tail_len), &mut compacted)?;
+        if copied != tail_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "record log is shorter than its index",
+            ));
+        }
+        compacted.sync_all()?;
+
+        let mut compacted_writer = Writer {
+            log: compacted.try_clone()?,
+            log_len,
+            dead_bytes: 0,
+            changes_since_snapshot: 0,
+            compacting: true,
+        };
+        let _ = Self::replay(
+            &mut compacted,
+            &mut compacted_writer,
+            &mut entries,
+            log_len + tail_len,
+        )?;
+        // record types learnt in memory while the compaction was running
+        {
+            let index = Self::read_index(index);
+            for (key, entry) in entries.iter_mut() {
+                if let Some(current) = index.entries.get(key) {
+                    entry.record_type.clone_from(&current.record_type);
+                }
+            }
+        }
+
+        // The snapshot refers to offsets in the old log, so it must be gone before the
+        // compacted log takes its place. The whole log is replayed if we crash in between.
+        match fs::remove_file(storage_dir.join(INDEX_FILENAME)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        fs::rename(&tmp_path, &log_path)?;
+
+        compacted_writer.log = OpenOptions::new().read(true).write(true).open(&log_path)?;
+        let reader = Arc::new(Mutex::new(File::open(&log_path)?));
+        *Self::write_index(index) = Index { entries, reader };
+        *writer = compacted_writer;
+        Self::snapshot(storage_dir, &mut writer, index)
+    }
+
+    /// Blocks until the running compaction, if any, is done.
+    fn wait_for_compaction(&self) {
+        let handle = self
+            .compaction
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(handle) = handle {
+            if handle.join().is_err() {
+                error!("The record log compaction panicked");
+            }
+        }
+    }
+
+    fn read_value(log: &mut File, entry: &IndexEntry) -> io::Result<Vec<u8>> {
+        let _ = log.seek(SeekFrom::Start(entry.offset))?;
+        let mut value = vec![0u8; entry.value_len as usize];
+        log.read_exact(&mut value)?;
+        Ok(value)
+    }
+
+    /// Moves the records stored in the flat file layout under `storage_dir` into the log.
+    /// Each file is removed once its record has been appended.
+    pub(crate) fn migrate_from_flat_files(&self, storage_dir: &Path) -> io::Result<()> {
+        let record_files = FlatFileBackend::record_files(storage_dir);
+        if record_files.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Migrating {} records from the flat file layout into the record log",
+            record_files.len()
+        );
+        let mut writer = Self::lock_writer(&self.writer);
+        let mut migrated = 0;
+        for (key, path) in record_files {
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    error!("Error while reading file {path:?} for migration: {err:?}");
+                    continue;
+                }
+            };
+            let header = LogEntryHeader {
+                key: key.to_vec(),
+                record_type: None,
+                value_len: bytes.len() as u64,
+                tombstone: false,
+            };
+            self.append(&mut writer, header, &bytes)?;
+            if let Err(err) = fs::remove_file(&path) {
+                warn!("Failed to remove migrated record file {path:?}: {err:?}");
+            }
+            migrated += 1;
+        }
+        Self::snapshot(&self.storage_dir, &mut writer, &self.index)?;
+        info!("Migrated {migrated} records into the record log");
+        Ok(())
+    }
+
+    // A panic while holding any of the locks leaves at worst a partially written entry,
+    // which is dealt with on the next replay.
+
+    fn lock_writer(writer: &Mutex<Writer>) -> MutexGuard<'_, Writer> {
+        writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn read_index(index: &RwLock<Index>) -> RwLockReadGuard<'_, Index> {
+        index
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write_index(index: &RwLock<Index>) -> RwLockWriteGuard<'_, Index> {
+        index
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RecordStorageBackend for AppendLogBackend {
+    fn stored_records(&self) -> Vec<StoredRecord> {
+        Self::read_index(&self.index)
+            .entries
+            .iter()
+            .map(|(key, entry)| StoredRecord {
+                key: key.clone(),
+                record_type: entry.record_type.clone(),
+                size: entry.value_len,
+            })
+            .collect()
+    }
+
+    fn read(&self, key: &Key) -> io::Result<Vec<u8>> {
+        let (entry, reader) = {
+            let index = Self::read_index(&self.index);
+            let entry = index.entries.get(key).cloned().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{:?} is not in the log", PrettyPrintRecordKey::from(key)),
+                )
+            })?;
+            (entry, Arc::clone(&index.reader))
+        };
+        let mut reader = reader
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Self::read_value(&mut reader, &entry)
+    }
+
+    fn write(&self, key: &Key, bytes: &[u8], record_type: &RecordType) -> io::Result<()> {
+        let header = LogEntryHeader {
+            key: key.to_vec(),
+            record_type: Some(record_type.clone()),
+            value_len: bytes.len() as u64,
+            tombstone: false,
+        };
+        let mut writer = Self::lock_writer(&self.writer);
+        self.append(&mut writer, header, bytes)?;
+        self.compact_if_needed(&mut writer);
+        Ok(())
+    }
+
+    fn remove(&self, key: &Key) -> io::Result<()> {
+        let mut writer = Self::lock_writer(&self.writer);
+        if !Self::read_index(&self.index).entries.contains_key(key) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{:?} is not in the log", PrettyPrintRecordKey::from(key)),
+            ));
+        }
+        let header = LogEntryHeader {
+            key: key.to_vec(),
+            record_type: None,
+            value_len: 0,
+            tombstone: true,
+        };
+        self.append(&mut writer, header, &[])?;
+        self.compact_if_needed(&mut writer);
+        Ok(())
+    }
+
+    fn index_record_type(&self, key: &Key, record_type: &RecordType) {
+        if let Some(entry) = Self::write_index(&self.index).entries.get_mut(key) {
+            entry.record_type = Some(record_type.clone());
+        }
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        let mut writer = Self::lock_writer(&self.writer);
+        Self::snapshot(&self.storage_dir, &mut writer, &self.index)
+    }
+}
+
+impl Drop for AppendLogBackend {
+    fn drop(&mut self) {
+        self.wait_for_compaction();
+        let mut writer = Self::lock_writer(&self.writer);
+        if writer.changes_since_snapshot > 0 {
+            if let Err(err) = Self::snapshot(&self.storage_dir, &mut writer, &self.index) {
+                warn!("Failed to persist the record index snapshot on drop: {err:?}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+    use libp2p::PeerId;
+    use sn_protocol::NetworkAddress;
+    use xor_name::XorName;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&dir).expect("Failed to create directory");
+        dir
+    }
+
+    fn random_key() -> Key {
+        NetworkAddress::from_peer(PeerId::random()).to_record_key()
+    }
+
+    #[test]
+    fn write_read_remove_and_reopen() -> Result<()> {
+        let dir = temp_dir();
+        let (kept, removed, replaced) = (random_key(), random_key(), random_key());
+        let non_chunk = RecordType::NonChunk(XorName::random(&mut rand::thread_rng()));
+
+        {
+            let backend = AppendLogBackend::open(&dir)?;
+            backend.write(&kept, b"kept", &RecordType::Chunk)?;
+            backend.write(&removed, b"removed", &RecordType::Chunk)?;
+            backend.write(&replaced, b"first", &non_chunk)?;
+            backend.write(&replaced, b"second", &non_chunk)?;
+            backend.remove(&removed)?;
+
+            assert_eq!(backend.read(&kept)?, b"kept");
+            assert_eq!(backend.read(&replaced)?, b"second");
+            assert!(backend.read(&removed).is_err());
+        }
+
+        // Remove the snapshot to force replaying the whole log.
+        fs::remove_file(dir.join(INDEX_FILENAME))?;
+
+        let backend = AppendLogBackend::open(&dir)?;
+        let mut stored = backend.stored_records();
+        stored.sort_by_key(|record| record.key.to_vec());
+        let mut expected = vec![
+            StoredRecord {
+                key: kept.clone(),
+                record_type: Some(RecordType::Chunk),
+                size: 4,
+            },
+            StoredRecord {
+                key: replaced.clone(),
+                record_type: Some(non_chunk),
+                size: 6,
+            },
+        ];
+        expected.sort_by_key(|record| record.key.to_vec());
+        assert_eq!(stored, expected);
+        assert_eq!(backend.read(&kept)?, b"kept");
+        assert_eq!(backend.read(&replaced)?, b"second");
+
+        Ok(())
+    }
+
+    #[test]
+    fn partially_written_entry_is_truncated() -> Result<()> {
+        let dir = temp_dir();
+        let key = random_key();
+        {
+            let backend = AppendLogBackend::open(&dir)?;
+            backend.write(&key, b"value", &RecordType::Chunk)?;
+            backend.flush()?;
+        }
+
+        // Simulate a crash halfway through appending an entry.
+        let mut log = OpenOptions::new()
+            .append(true)
+            .open(dir.join(LOG_FILENAME))?;
+        log.write_all(&[42, 0, 0, 0, 1, 2])?;
+
+        let backend = AppendLogBackend::open(&dir)?;
+        assert_eq!(backend.read(&key)?, b"value");
+
+        let other_key = random_key();
+        backend.write(&other_key, b"other", &RecordType::Chunk)?;
+        drop(backend);
+
+        let backend = AppendLogBackend::open(&dir)?;
+        assert_eq!(backend.read(&key)?, b"value");
+        assert_eq!(backend.read(&other_key)?, b"other");
+
+        Ok(())
+    }
+
+    #[test]
+    fn compaction_keeps_live_records() -> Result<()> {
+        let dir = temp_dir();
+        let backend = AppendLogBackend::open(&dir)?;
+        let key = random_key();
+        let value = vec![7u8; 1024 * 1024];
+
+        // Overwrite the same record until enough dead bytes trigger a compaction.
+        for _ in 0..(MIN_DEAD_BYTES_FOR_COMPACTION / value.len() as u64 + 2) {
+            backend.write(&key, &value, &RecordType::Chunk)?;
+        }
+        // Written while the compaction is likely running.
+        let other_key = random_key();
+        backend.write(&other_key, b"other", &RecordType::Chunk)?;
+        backend.remove(&key)?;
+        backend.write(&key, b"last", &RecordType::Chunk)?;
+        backend.wait_for_compaction();
+
+        let log_len = fs::metadata(dir.join(LOG_FILENAME))?.len();
+        assert!(log_len < MIN_DEAD_BYTES_FOR_COMPACTION);
+        assert_eq!(backend.read(&key)?, b"last");
+        assert_eq!(backend.read(&other_key)?, b"other");
+
+        drop(backend);
+        let backend = AppendLogBackend::open(&dir)?;
+        assert_eq!(backend.read(&key)?, b"last");
+        assert_eq!(backend.read(&other_key)?, b"other");
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_do_not_wait_for_the_writer() -> Result<()> {
+        let dir = temp_dir();
+        let backend = AppendLogBackend::open(&dir)?;
+        let key = random_key();
+        backend.write(&key, b"value", &RecordType::Chunk)?;
+
+        // e.g. while a snapshot is written or a compaction copies the tail of the log
+        let _writer = AppendLogBackend::lock_writer(&backend.writer);
+        assert_eq!(backend.read(&key)?, b"value");
+        assert_eq!(backend.stored_records().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrates_flat_files() -> Result<()> {
+        let dir = temp_dir();
+        let key = random_key();
+        let flat_file = FlatFileBackend::new(&dir);
+        flat_file.write(&key, b"flat", &RecordType::Chunk)?;
+
+        let backend = AppendLogBackend::open(&dir)?;
+        backend.migrate_from_flat_files(&dir)?;
+
+        assert_eq!(
+            backend.stored_records(),
+            vec![StoredRecord {
+                key: key.clone(),
+                record_type: None,
+                size: 4,
+            }]
+        );
+        assert_eq!(backend.read(&key)?, b"flat");
+        assert!(flat_file.stored_records().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{append_log, key_from_filename, record_filename, RecordStorageBackend, StoredRecord};
+use libp2p::kad::RecordKey as Key;
+use sn_protocol::storage::RecordType;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Extension of the temp file a record is written to, before being moved into place.
+const TMP_EXTENSION: &str = "tmp";
+
+/// Stores every record as a separate file under the storage dir.
+pub(crate) struct FlatFileBackend {
+    storage_dir: PathBuf,
+}
+
+impl FlatFileBackend {
+    pub(crate) fn new(storage_dir: &Path) -> Self {
+        Self {
+            storage_dir: storage_dir.to_path_buf(),
+        }
+    }
+
+    /// Lists the record files under `storage_dir`, removing any file that is not a valid record.
+    pub(super) fn record_files(storage_dir: &Path) -> Vec<(Key, PathBuf)> {
+        WalkDir::new(storage_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                if !path.is_file() {
+                    return None;
+                }
+                trace!("Existing record found: {path:?}");
+                // if we've got a file, lets try and read it
+                let filename = match path.file_name().and_then(|n| n.to_str()) {
+                    Some(file_name) => file_name,
+                    None => {
+                        // warn and remove this file as it's not a valid record
+                        warn!(
+                            "Found a file in the storage dir that is not a valid record: {:?}",
+                            path
+                        );
+                        if let Err(e) = fs::remove_file(path) {
+                            warn!(
+                                "Failed to remove invalid record file from storage dir: {:?}",
+                                e
+                            );
+                        }
+                        return None;
+                    }
+                };
+                // remove the temp file of a write that got interrupted
+                if path.extension().and_then(|ext| ext.to_str()) == Some(TMP_EXTENSION) {
+                    warn!("Removing the leftover of an interrupted record write: {path:?}");
+                    if let Err(e) = fs::remove_file(path) {
+                        warn!("Failed to remove leftover record write {path:?}: {e:?}");
+                    }
+                    return None;
+                }
+                // skip the files of the append log backend, in case it was used before
+                if append_log::is_backend_file(filename) {
+                    return None;
+                }
+                // get the record key from the filename
+                let key = key_from_filename(filename)?;
+                Some((key, path.to_path_buf()))
+            })
+            .collect()
+    }
+}
+
+impl RecordStorageBackend for FlatFileBackend {
+    fn stored_records(&self) -> Vec<StoredRecord> {
+        Self::record_files(&self.storage_dir)
+            .into_iter()
+            .map(|(key, path)| StoredRecord {
+                key,
+                record_type: None,
+                size: fs::metadata(path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or(0),
+            })
+            .collect()
+    }
+
+    fn read(&self, key: &Key) -> io::Result<Vec<u8>> {
+        fs::read(self.storage_dir.join(record_filename(key)))
+    }
+
+    /// The record is written to a temp file first, then moved into place, so that overwriting
+    /// an existing record (e.g. re-encrypting it) never leaves it half written on a crash.
+    fn write(&self, key: &Key, bytes: &[u8], _record_type: &RecordType) -> io::Result<()> {
+        let path = self.storage_dir.join(record_filename(key));
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)
+    }
+
+    fn remove(&self, key: &Key) -> io::Result<()> {
+        fs::remove_file(self.storage_dir.join(record_filename(key)))
+    }
+}
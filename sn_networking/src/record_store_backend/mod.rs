@@ -0,0 +1,122 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+#![allow(clippy::mutable_key_type)] // for the Bytes in RecordKey
+
+mod append_log;
+mod flat_file;
+
+pub(crate) use self::{append_log::AppendLogBackend, flat_file::FlatFileBackend};
+
+use libp2p::kad::RecordKey as Key;
+use sn_protocol::storage::RecordType;
+use std::{fmt, io, path::Path, str::FromStr, sync::Arc};
+
+/// The storage underneath the `NodeRecordStore`.
+///
+/// A backend only deals with the bytes of the records, which are already encrypted if the
+/// `encrypt-records` feature is enabled. Keeping track of what is stored is left to the store.
+pub(crate) trait RecordStorageBackend: Send + Sync {
+    /// Lists all the stored records.
+    fn stored_records(&self) -> Vec<StoredRecord>;
+
+    /// Reads the bytes of the record.
+    fn read(&self, key: &Key) -> io::Result<Vec<u8>>;
+
+    /// Writes the bytes of the record, replacing any existing entry under the same key.
+    fn write(&self, key: &Key, bytes: &[u8], record_type: &RecordType) -> io::Result<()>;
+
+    /// Removes the record.
+    fn remove(&self, key: &Key) -> io::Result<()>;
+
+    /// Remembers the `RecordType` of a record the backend could not index on write,
+    /// e.g. one migrated from another layout.
+    fn index_record_type(&self, _key: &Key, _record_type: &RecordType) {}
+
+    /// Persists any pending state, e.g. the index.
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A record found in the backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredRecord {
+    pub(crate) key: Key,
+    /// Known if the backend indexed it on write, saving the store from reading the record
+    /// on startup.
+    pub(crate) record_type: Option<RecordType>,
+    /// The number of bytes the record takes on disk.
+    pub(crate) size: u64,
+}
+
+/// The available backends to store the records on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordStorageBackendKind {
+    /// One file per record under the storage dir, named after the hex of the record key.
+    #[default]
+    FlatFile,
+    /// All the records appended into a single log file, with a compacted index persisted
+    /// alongside to avoid scanning the records on startup.
+    /// Records found in the flat file layout are migrated into the log on startup.
+    AppendLog,
+}
+
+impl RecordStorageBackendKind {
+    /// Opens the backend under the `storage_dir`.
+    pub(crate) fn open(self, storage_dir: &Path) -> io::Result<Arc<dyn RecordStorageBackend>> {
+        info!("Opening {self} record storage backend at {storage_dir:?}");
+        let backend: Arc<dyn RecordStorageBackend> = match self {
+            Self::FlatFile => Arc::new(FlatFileBackend::new(storage_dir)),
+            Self::AppendLog => {
+                let backend = AppendLogBackend::open(storage_dir)?;
+                backend.migrate_from_flat_files(storage_dir)?;
+                Arc::new(backend)
+            }
+        };
+        Ok(backend)
+    }
+}
+
+impl fmt::Display for RecordStorageBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FlatFile => write!(f, "flat-file"),
+            Self::AppendLog => write!(f, "append-log"),
+        }
+    }
+}
+
+impl FromStr for RecordStorageBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat-file" => Ok(Self::FlatFile),
+            "append-log" => Ok(Self::AppendLog),
+            other => Err(format!(
+                "Unknown record storage backend {other:?}, expected `flat-file` or `append-log`"
+            )),
+        }
+    }
+}
+
+/// Converts a Key into a Hex string, used as the file name of the record in the flat file layout.
+pub(crate) fn record_filename(key: &Key) -> String {
+    hex::encode(key.as_ref())
+}
+
+/// Converts a Hex string back into a Key.
+fn key_from_filename(hex_str: &str) -> Option<Key> {
+    match hex::decode(hex_str) {
+        Ok(bytes) => Some(Key::from(bytes)),
+        Err(error) => {
+            error!("Error decoding hex string {hex_str:?}: {error:?}");
+            None
+        }
+    }
+}
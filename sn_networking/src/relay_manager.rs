@@ -6,7 +6,10 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::driver::{BadNodes, NodeBehaviour};
+use crate::{
+    driver::{BadNodes, NodeBehaviour},
+    reputation::Reputations,
+};
 use itertools::Itertools;
 use libp2p::{
     core::transport::ListenerId, multiaddr::Protocol, Multiaddr, PeerId, StreamProtocol, Swarm,
@@ -130,6 +133,7 @@ impl RelayManager {
         &mut self,
         swarm: &mut Swarm<NodeBehaviour>,
         bad_nodes: &BadNodes,
+        reputations: &Reputations,
     ) {
         if !self.enable_client {
             return;
@@ -148,12 +152,25 @@ impl RelayManager {
             // todo: should we remove all our other `listen_addr`? And should we block from adding `add_external_address` if
             // we're behind nat?
 
-            // Pick a random candidate from the vector. Check if empty, or `gen_range` panics for empty range.
+            // Pick the best scored candidate, a random one among the equally scored ones.
+            // Check if empty, or `gen_range` panics for empty range.
             let index = if self.candidates.is_empty() {
                 trace!("No more relay candidates.");
                 break;
             } else {
-                rand::thread_rng().gen_range(0..self.candidates.len())
+                let scores: Vec<_> = self
+                    .candidates
+                    .iter()
+                    .map(|(peer_id, _)| reputations.score(peer_id))
+                    .collect();
+                let best_score = scores.iter().copied().fold(f64::MIN, f64::max);
+                let best_indices: Vec<_> = scores
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, score)| **score == best_score)
+                    .map(|(index, _)| index)
+                    .collect();
+                best_indices[rand::thread_rng().gen_range(0..best_indices.len())]
             };
 
             if let Some((peer_id, relay_addr)) = self.candidates.remove(index) {
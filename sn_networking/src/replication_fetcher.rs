@@ -7,8 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 #![allow(clippy::mutable_key_type)]
 
+#[cfg(feature = "open-metrics")]
+use crate::metrics::ReplicationMetrics;
 use crate::target_arch::spawn;
-use crate::{event::NetworkEvent, target_arch::Instant};
+use crate::{event::NetworkEvent, reputation::Reputations, target_arch::Instant};
 use libp2p::{
     kad::{KBucketDistance as Distance, RecordKey, K_VALUE},
     PeerId,
@@ -20,6 +22,18 @@ use tokio::{sync::mpsc, time::Duration};
 // Max parallel fetches that can be undertaken at the same time.
 const MAX_PARALLEL_FETCH: usize = K_VALUE.get();
 
+// A holder taking longer than this on average to deliver a record is considered as slow,
+// and only gets half of its parallel fetches.
+const SLOW_HOLDER_LATENCY: Duration = Duration::from_secs(5);
+
+// The weight of the latest fetch in the per-holder moving averages.
+const HOLDER_STATS_WEIGHT: f64 = 0.2;
+
+// Once the record store is filled above these ratios, fetching slows down to a half,
+// and then to a quarter of the parallel fetches.
+const STORE_LOAD_HIGH: f64 = 0.8;
+const STORE_LOAD_CRITICAL: f64 = 0.95;
+
 // The duration after which a peer will be considered failed to fetch data from,
 // if no response got from that peer.
 // Note this will also cover the period that node self write the fetched copy to disk.
@@ -33,13 +47,67 @@ const PENDING_TIMEOUT: Duration = Duration::from_secs(900);
 // The time the entry will be considered as `time out` and to be cleared.
 type ReplicationTimeout = Instant;
 
+/// The observed performance of a holder, as moving averages over its latest fetches.
+#[derive(Debug)]
+struct HolderStats {
+    latency: Option<Duration>,
+    success_rate: f64,
+    last_fetch: Instant,
+}
+
+impl Default for HolderStats {
+    fn default() -> Self {
+        Self {
+            latency: None,
+            success_rate: 1.0,
+            last_fetch: Instant::now(),
+        }
+    }
+}
+
+impl HolderStats {
+    fn record_success(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - HOLDER_STATS_WEIGHT) + latency.mul_f64(HOLDER_STATS_WEIGHT)
+            }
+            None => latency,
+        });
+        self.success_rate = self.success_rate * (1.0 - HOLDER_STATS_WEIGHT) + HOLDER_STATS_WEIGHT;
+        self.last_fetch = Instant::now();
+    }
+
+    fn record_failure(&mut self) {
+        self.success_rate *= 1.0 - HOLDER_STATS_WEIGHT;
+        self.last_fetch = Instant::now();
+    }
+
+    /// The parallel fetches the holder is trusted with, scaled down by its success rate.
+    /// A holder yet to fail us can take all of the parallel fetches.
+    fn fetch_limit(&self) -> usize {
+        let mut limit = (MAX_PARALLEL_FETCH as f64 * self.success_rate).round() as usize;
+        if self
+            .latency
+            .is_some_and(|latency| latency > SLOW_HOLDER_LATENCY)
+        {
+            limit /= 2;
+        }
+        limit.max(1)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ReplicationFetcher {
     self_peer_id: PeerId,
     // Pending entries that to be fetched from the target peer.
     to_be_fetched: HashMap<(RecordKey, RecordType, PeerId), ReplicationTimeout>,
     // Avoid fetching same chunk from different nodes AND carry out too many parallel tasks.
-    on_going_fetches: HashMap<(RecordKey, RecordType), (PeerId, ReplicationTimeout)>,
+    // The time the fetch started at is kept to observe the latency of the holder.
+    on_going_fetches: HashMap<(RecordKey, RecordType), (PeerId, Instant, ReplicationTimeout)>,
+    // The observed performance of the holders, to adapt the parallel fetches to.
+    holder_stats: HashMap<PeerId, HolderStats>,
+    // How full the record store is, from 0 to 1, slowing fetching down when nearly full.
+    store_load: f64,
     event_sender: mpsc::Sender<NetworkEvent>,
     /// ilog2 bucket distance range that the incoming key shall be fetched
     distance_range: Option<u32>,
@@ -47,6 +115,8 @@ pub(crate) struct ReplicationFetcher {
     /// used when the node is full, but we still have "close" data coming in
     /// that is _not_ closer than our farthest max record
     farthest_acceptable_distance: Option<Distance>,
+    #[cfg(feature = "open-metrics")]
+    metrics: Option<ReplicationMetrics>,
 }
 
 impl ReplicationFetcher {
@@ -56,9 +126,38 @@ impl ReplicationFetcher {
             self_peer_id,
             to_be_fetched: HashMap::new(),
             on_going_fetches: HashMap::new(),
+            holder_stats: HashMap::new(),
+            store_load: 0.0,
             event_sender,
             distance_range: None,
             farthest_acceptable_distance: None,
+            #[cfg(feature = "open-metrics")]
+            metrics: None,
+        }
+    }
+
+    /// Export the queue depth and the fetch latency to the metrics.
+    #[cfg(feature = "open-metrics")]
+    pub(crate) fn set_metrics(&mut self, metrics: ReplicationMetrics) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Set how full the record store is, from 0 to 1.
+    pub(crate) fn set_store_load(&mut self, store_load: f64) {
+        if (self.store_load < STORE_LOAD_HIGH) != (store_load < STORE_LOAD_HIGH) {
+            info!("Record store load is now {store_load:.2}, adapting the parallel fetches to it");
+        }
+        self.store_load = store_load;
+    }
+
+    /// The parallel fetches allowed, reduced to back off once the record store is nearly full.
+    fn max_parallel_fetch(&self) -> usize {
+        if self.store_load >= STORE_LOAD_CRITICAL {
+            MAX_PARALLEL_FETCH / 4
+        } else if self.store_load >= STORE_LOAD_HIGH {
+            MAX_PARALLEL_FETCH / 2
+        } else {
+            MAX_PARALLEL_FETCH
         }
     }
 
@@ -76,6 +175,7 @@ impl ReplicationFetcher {
         holder: PeerId,
         mut incoming_keys: Vec<(NetworkAddress, RecordType)>,
         locally_stored_keys: &HashMap<RecordKey, (NetworkAddress, RecordType)>,
+        reputations: &Reputations,
     ) -> Vec<(PeerId, RecordKey)> {
         self.remove_stored_keys(locally_stored_keys);
         let self_address = NetworkAddress::from_peer(self.self_peer_id);
@@ -109,7 +209,7 @@ impl ReplicationFetcher {
             if let Entry::Vacant(entry) = self.on_going_fetches.entry(new_data_key.clone()) {
                 let (record_key, _record_type) = new_data_key;
                 keys_to_fetch.push((holder, record_key));
-                let _ = entry.insert((holder, Instant::now() + FETCH_TIMEOUT));
+                let _ = entry.insert((holder, Instant::now(), Instant::now() + FETCH_TIMEOUT));
             }
 
             // To avoid later on un-necessary actions.
@@ -148,7 +248,7 @@ impl ReplicationFetcher {
                 .or_insert(Instant::now() + PENDING_TIMEOUT);
         });
 
-        keys_to_fetch.extend(self.next_keys_to_fetch());
+        keys_to_fetch.extend(self.next_keys_to_fetch(reputations));
 
         keys_to_fetch
     }
@@ -194,39 +294,57 @@ impl ReplicationFetcher {
         &mut self,
         new_put: RecordKey,
         record_type: RecordType,
+        reputations: &Reputations,
     ) -> Vec<(PeerId, RecordKey)> {
         self.to_be_fetched
             .retain(|(key, t, _), _| key != &new_put || t != &record_type);
 
         // if we're actively fetching for the key, reduce the on_going_fetches
-        self.on_going_fetches.retain(|(key, _t), _| key != &new_put);
+        self.complete_on_going_fetches(&new_put);
+
+        self.next_keys_to_fetch(reputations)
+    }
 
-        self.next_keys_to_fetch()
+    /// The peer the record is being fetched from, if any.
+    pub(crate) fn on_going_fetch_holder(&self, key: &RecordKey) -> Option<PeerId> {
+        self.on_going_fetches
+            .iter()
+            .find(|((fetching_key, _t), _)| fetching_key == key)
+            .map(|(_, (holder, _, _))| *holder)
     }
 
     // An early completion of a fetch means the target is an old version record (Register or Spend).
     pub(crate) fn notify_fetch_early_completed(
         &mut self,
         key_in: RecordKey,
+        reputations: &Reputations,
     ) -> Vec<(PeerId, RecordKey)> {
         self.to_be_fetched.retain(|(key, _t, _), _| key != &key_in);
 
-        self.on_going_fetches.retain(|(key, _t), _| key != &key_in);
+        self.complete_on_going_fetches(&key_in);
 
-        self.next_keys_to_fetch()
+        self.next_keys_to_fetch(reputations)
     }
 
     // Returns the set of keys that has to be fetched from the peer/network.
     // Target must not be under-fetching
-    // and no more than MAX_PARALLEL_FETCH fetches to be undertaken at the same time.
-    pub(crate) fn next_keys_to_fetch(&mut self) -> Vec<(PeerId, RecordKey)> {
+    // and no more than `max_parallel_fetch` fetches to be undertaken at the same time,
+    // nor more than its `fetch_limit` from the same holder.
+    // The keys closest to us are fetched first.
+    // Among the holders of a same key, the higher scored ones are fetched from first.
+    pub(crate) fn next_keys_to_fetch(
+        &mut self,
+        reputations: &Reputations,
+    ) -> Vec<(PeerId, RecordKey)> {
         self.prune_expired_keys_and_slow_nodes();
 
         trace!("Next to fetch....");
 
-        if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+        let max_parallel_fetch = self.max_parallel_fetch();
+        if self.on_going_fetches.len() >= max_parallel_fetch {
             warn!("Replication Fetcher doesn't have free fetch capacity. Currently has {} entries in queue.",
                 self.to_be_fetched.len());
+            self.record_metrics();
             return vec![];
         }
 
@@ -239,34 +357,46 @@ impl ReplicationFetcher {
 
         let mut data_to_fetch = vec![];
         // Sort to_be_fetched by key closeness to our PeerId
-        let mut to_be_fetched_sorted: Vec<_> = self.to_be_fetched.iter_mut().collect();
+        let mut to_be_fetched_sorted: Vec<_> = self.to_be_fetched.keys().collect();
 
         let self_address = NetworkAddress::from_peer(self.self_peer_id);
 
-        to_be_fetched_sorted.sort_by(|((a, _, _), _), ((b, _, _), _)| {
+        to_be_fetched_sorted.sort_by(|(a, _, a_holder), (b, _, b_holder)| {
             let a = NetworkAddress::from_record_key(a);
             let b = NetworkAddress::from_record_key(b);
-            self_address.distance(&a).cmp(&self_address.distance(&b))
+            self_address
+                .distance(&a)
+                .cmp(&self_address.distance(&b))
+                .then_with(|| reputations.compare(a_holder, b_holder))
         });
 
-        for ((key, t, holder), _) in to_be_fetched_sorted {
+        let mut fetches_per_holder: HashMap<PeerId, usize> = HashMap::new();
+        for (holder, _, _) in self.on_going_fetches.values() {
+            *fetches_per_holder.entry(*holder).or_default() += 1;
+        }
+
+        for (key, t, holder) in to_be_fetched_sorted {
+            // Don't flood the slow or failing holders, leaving their keys for later on
+            let holder_fetches = fetches_per_holder.entry(*holder).or_default();
+            let holder_limit = self
+                .holder_stats
+                .get(holder)
+                .map_or(MAX_PARALLEL_FETCH, HolderStats::fetch_limit);
+            if *holder_fetches >= holder_limit {
+                continue;
+            }
+
             // Already carried out expiration pruning above.
             // Hence here only need to check whether is ongoing fetching.
             // Also avoid fetching same record from different nodes.
-            if self.on_going_fetches.len() < MAX_PARALLEL_FETCH
-                && !self
-                    .on_going_fetches
-                    .contains_key(&(key.clone(), t.clone()))
-            {
+            if let Entry::Vacant(entry) = self.on_going_fetches.entry((key.clone(), t.clone())) {
                 data_to_fetch.push((*holder, key.clone(), t.clone()));
-                let _ = self.on_going_fetches.insert(
-                    (key.clone(), t.clone()),
-                    (*holder, Instant::now() + FETCH_TIMEOUT),
-                );
+                *holder_fetches += 1;
+                let _ = entry.insert((*holder, Instant::now(), Instant::now() + FETCH_TIMEOUT));
             }
 
             // break out the loop early if we can do no more now
-            if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+            if self.on_going_fetches.len() >= max_parallel_fetch {
                 break;
             }
         }
@@ -284,14 +414,49 @@ impl ReplicationFetcher {
             );
         }
 
-        data_to_fetch
+        let keys_to_fetch = data_to_fetch
             .iter()
             .map(|(holder, key, t)| {
                 let entry_key = (key.clone(), t.clone(), *holder);
                 let _ = self.to_be_fetched.remove(&entry_key);
                 (*holder, key.clone())
             })
-            .collect()
+            .collect();
+        self.record_metrics();
+        keys_to_fetch
+    }
+
+    // The fetches of the key have completed, accounting for the latency of their holders.
+    fn complete_on_going_fetches(&mut self, completed_key: &RecordKey) {
+        let holder_stats = &mut self.holder_stats;
+        #[cfg(feature = "open-metrics")]
+        let metrics = &self.metrics;
+        self.on_going_fetches
+            .retain(|(key, _t), (holder, started, _time_out)| {
+                if key != completed_key {
+                    return true;
+                }
+                let latency = started.elapsed();
+                holder_stats
+                    .entry(*holder)
+                    .or_default()
+                    .record_success(latency);
+                #[cfg(feature = "open-metrics")]
+                if let Some(metrics) = metrics {
+                    metrics.fetch_latency.observe(latency.as_secs_f64());
+                }
+                false
+            });
+    }
+
+    fn record_metrics(&self) {
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics) = &self.metrics {
+            let _ = metrics.queue_depth.set(self.to_be_fetched.len() as i64);
+            let _ = metrics
+                .on_going_fetches
+                .set(self.on_going_fetches.len() as i64);
+        }
     }
 
     // Just remove outdated entries in `on_going_fetch`, indicates a failure to fetch from network.
@@ -302,7 +467,7 @@ impl ReplicationFetcher {
         let mut failed_fetches = vec![];
 
         self.on_going_fetches
-            .retain(|(record_key, _), (peer_id, time_out)| {
+            .retain(|(record_key, _), (peer_id, _started, time_out)| {
                 if *time_out < Instant::now() {
                     failed_fetches.push((record_key.clone(), *peer_id));
                     false
@@ -318,9 +483,17 @@ impl ReplicationFetcher {
                 "Failed to fetch {:?} from {peer_id:?}",
                 PrettyPrintRecordKey::from(&record_key)
             );
+            self.holder_stats
+                .entry(peer_id)
+                .or_default()
+                .record_failure();
             let _ = failed_holders.insert(peer_id);
         }
 
+        // forget the holders we haven't fetched from for long
+        self.holder_stats
+            .retain(|_, stats| stats.last_fetch.elapsed() < PENDING_TIMEOUT);
+
         // now to clear any failed nodes from our lists.
         self.to_be_fetched
             .retain(|(_, _, holder), _| !failed_holders.contains(holder));
@@ -378,7 +551,8 @@ impl ReplicationFetcher {
 
 #[cfg(test)]
 mod tests {
-    use super::{ReplicationFetcher, FETCH_TIMEOUT, MAX_PARALLEL_FETCH};
+    use super::{ReplicationFetcher, FETCH_TIMEOUT, MAX_PARALLEL_FETCH, STORE_LOAD_CRITICAL};
+    use crate::reputation::Reputations;
     use eyre::Result;
     use libp2p::{kad::RecordKey, PeerId};
     use sn_protocol::{storage::RecordType, NetworkAddress};
@@ -392,6 +566,7 @@ mod tests {
         let (event_sender, _event_receiver) = mpsc::channel(4);
         let mut replication_fetcher = ReplicationFetcher::new(peer_id, event_sender);
         let locally_stored_keys = HashMap::new();
+        let reputations = Reputations::default();
 
         let mut incoming_keys = Vec::new();
         (0..MAX_PARALLEL_FETCH * 2).for_each(|_| {
@@ -400,8 +575,12 @@ mod tests {
             incoming_keys.push((key, RecordType::Chunk));
         });
 
-        let keys_to_fetch =
-            replication_fetcher.add_keys(PeerId::random(), incoming_keys, &locally_stored_keys);
+        let keys_to_fetch = replication_fetcher.add_keys(
+            PeerId::random(),
+            incoming_keys,
+            &locally_stored_keys,
+            &reputations,
+        );
         assert_eq!(keys_to_fetch.len(), MAX_PARALLEL_FETCH);
 
         // we should not fetch anymore keys
@@ -413,6 +592,7 @@ mod tests {
             PeerId::random(),
             vec![(key_1, RecordType::Chunk), (key_2, RecordType::Chunk)],
             &locally_stored_keys,
+            &reputations,
         );
         assert!(keys_to_fetch.is_empty());
 
@@ -423,22 +603,72 @@ mod tests {
             PeerId::random(),
             vec![(key, RecordType::Chunk)],
             &locally_stored_keys,
+            &reputations,
         );
         assert!(!keys_to_fetch.is_empty());
 
         sleep(FETCH_TIMEOUT + Duration::from_secs(1)).await;
 
         // all the previous fetches should have failed and fetching next batch...
-        let keys_to_fetch = replication_fetcher.next_keys_to_fetch();
+        let keys_to_fetch = replication_fetcher.next_keys_to_fetch(&reputations);
         // but as we've marked the previous fetches as failed, that node should be entirely removed from the list
         // leaving us with just _one_ peer left (but with two entries)
         assert_eq!(keys_to_fetch.len(), 2);
-        let keys_to_fetch = replication_fetcher.next_keys_to_fetch();
+        let keys_to_fetch = replication_fetcher.next_keys_to_fetch(&reputations);
         assert!(keys_to_fetch.is_empty());
 
         Ok(())
     }
 
+    #[test]
+    fn verify_fetches_adapt_to_holders_and_store_load() {
+        let (event_sender, _event_receiver) = mpsc::channel(4);
+        let mut replication_fetcher = ReplicationFetcher::new(PeerId::random(), event_sender);
+        let reputations = Reputations::default();
+        let random_keys = |count: usize| -> Vec<_> {
+            (0..count)
+                .map(|_| {
+                    let random_data: Vec<u8> = (0..50).map(|_| rand::random::<u8>()).collect();
+                    let key = NetworkAddress::from_record_key(&RecordKey::from(random_data));
+                    (key, RecordType::Chunk)
+                })
+                .collect()
+        };
+
+        // a holder failing us gets fewer parallel fetches
+        let failing_holder = PeerId::random();
+        for _ in 0..5 {
+            replication_fetcher
+                .holder_stats
+                .entry(failing_holder)
+                .or_default()
+                .record_failure();
+        }
+        let keys_to_fetch = replication_fetcher.add_keys(
+            failing_holder,
+            random_keys(MAX_PARALLEL_FETCH),
+            &Default::default(),
+            &reputations,
+        );
+        let holder_limit = replication_fetcher.holder_stats[&failing_holder].fetch_limit();
+        assert!(holder_limit < MAX_PARALLEL_FETCH);
+        assert_eq!(keys_to_fetch.len(), holder_limit);
+
+        // the other holders still get fetched from, unless the store is nearly full
+        replication_fetcher.set_store_load(STORE_LOAD_CRITICAL);
+        let keys_to_fetch = replication_fetcher.add_keys(
+            PeerId::random(),
+            random_keys(MAX_PARALLEL_FETCH),
+            &Default::default(),
+            &reputations,
+        );
+        assert!(keys_to_fetch.is_empty());
+
+        replication_fetcher.set_store_load(0.0);
+        let keys_to_fetch = replication_fetcher.next_keys_to_fetch(&reputations);
+        assert_eq!(keys_to_fetch.len(), MAX_PARALLEL_FETCH - holder_limit);
+    }
+
     #[test]
     fn verify_in_range_check() {
         //random peer_id
@@ -465,8 +695,12 @@ mod tests {
             incoming_keys.push((key, RecordType::Chunk));
         });
 
-        let keys_to_fetch =
-            replication_fetcher.add_keys(PeerId::random(), incoming_keys, &Default::default());
+        let keys_to_fetch = replication_fetcher.add_keys(
+            PeerId::random(),
+            incoming_keys,
+            &Default::default(),
+            &Default::default(),
+        );
         assert_eq!(
             keys_to_fetch.len(),
             replication_fetcher.on_going_fetches.len()
@@ -0,0 +1,145 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{cmd::NodeIssue, target_arch::Instant};
+use libp2p::PeerId;
+use std::{cmp::Ordering, collections::HashMap, time::Duration};
+
+/// The scores decay towards neutral, halving every this long.
+const SCORE_HALF_LIFE: Duration = Duration::from_secs(600);
+
+/// A long good history shall not cover for a peer starting to misbehave.
+const MAX_SCORE: f64 = 20.0;
+/// A peer shall get another chance once its bad history has decayed.
+const MIN_SCORE: f64 = -100.0;
+
+/// A peer whose score falls below this is considered as a bad node.
+pub(crate) const BAD_NODE_SCORE: f64 = -25.0;
+
+/// The scores that decayed to about neutral are forgotten once tracking more peers than this.
+const MAX_TRACKED_PEERS: usize = 1024;
+
+/// The good behaviours observed from a peer, crediting its reputation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NodeCredit {
+    /// A record got fetched from the peer during replication
+    ReplicationFetched,
+    /// Peer passed the chunk proof verification
+    ValidChunkProof,
+    /// Provided a quote consistent with its previous ones
+    HonestQuote,
+}
+
+impl NodeCredit {
+    fn weight(&self) -> f64 {
+        match self {
+            NodeCredit::ReplicationFetched => 0.5,
+            NodeCredit::ValidChunkProof => 2.0,
+            NodeCredit::HonestQuote => 1.0,
+        }
+    }
+}
+
+impl NodeIssue {
+    /// Three of the same issue in a row make a neutral peer a bad node.
+    fn weight(&self) -> f64 {
+        match self {
+            NodeIssue::ConnectionIssue => 9.0,
+            NodeIssue::ReplicationFailure => 9.0,
+            NodeIssue::CloseNodesShunning => 10.0,
+            NodeIssue::BadQuoting => 10.0,
+            NodeIssue::FailedChunkProofCheck => 10.0,
+        }
+    }
+}
+
+/// The reputation of the peers, as a score decaying over time.
+/// Issues lower the score of a peer, its good behaviours raise it.
+#[derive(Debug, Default)]
+pub(crate) struct Reputations {
+    /// The score of each peer, along with when it was last updated
+    scores: HashMap<PeerId, (f64, Instant)>,
+}
+
+impl Reputations {
+    /// The current score of the peer, 0 for the peers we know nothing about.
+    pub(crate) fn score(&self, peer_id: &PeerId) -> f64 {
+        self.scores
+            .get(peer_id)
+            .map(|(score, updated)| decayed(*score, *updated))
+            .unwrap_or_default()
+    }
+
+    /// Lowers the score of the peer, returning its new score.
+    pub(crate) fn record_issue(&mut self, peer_id: PeerId, issue: &NodeIssue) -> f64 {
+        self.adjust(peer_id, -issue.weight())
+    }
+
+    /// Raises the score of the peer, returning its new score.
+    pub(crate) fn record_credit(&mut self, peer_id: PeerId, credit: &NodeCredit) -> f64 {
+        self.adjust(peer_id, credit.weight())
+    }
+
+    /// Orders the higher scored peers first.
+    pub(crate) fn compare(&self, a: &PeerId, b: &PeerId) -> Ordering {
+        self.score(b).total_cmp(&self.score(a))
+    }
+
+    fn adjust(&mut self, peer_id: PeerId, delta: f64) -> f64 {
+        if self.scores.len() > MAX_TRACKED_PEERS {
+            self.scores
+                .retain(|_, (score, updated)| decayed(*score, *updated).abs() >= 0.5);
+        }
+
+        let score = (self.score(&peer_id) + delta).clamp(MIN_SCORE, MAX_SCORE);
+        let _ = self.scores.insert(peer_id, (score, Instant::now()));
+        score
+    }
+}
+
+fn decayed(score: f64, updated: Instant) -> f64 {
+    let half_lives = updated.elapsed().as_secs_f64() / SCORE_HALF_LIFE.as_secs_f64();
+    score * 0.5_f64.powf(half_lives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credits_and_issues_move_the_score() {
+        let mut reputations = Reputations::default();
+        let good_peer = PeerId::random();
+        let bad_peer = PeerId::random();
+        let unknown_peer = PeerId::random();
+
+        for _ in 0..100 {
+            let _ = reputations.record_credit(good_peer, &NodeCredit::ValidChunkProof);
+        }
+        // the credits are capped
+        assert!(reputations.score(&good_peer) <= MAX_SCORE);
+
+        let _ = reputations.record_issue(bad_peer, &NodeIssue::FailedChunkProofCheck);
+        let _ = reputations.record_issue(bad_peer, &NodeIssue::FailedChunkProofCheck);
+        assert!(reputations.score(&bad_peer) > BAD_NODE_SCORE);
+        let score = reputations.record_issue(bad_peer, &NodeIssue::FailedChunkProofCheck);
+        assert!(score < BAD_NODE_SCORE);
+
+        let mut peers = vec![bad_peer, unknown_peer, good_peer];
+        peers.sort_by(|a, b| reputations.compare(a, b));
+        assert_eq!(peers, vec![good_peer, unknown_peer, bad_peer]);
+    }
+
+    #[test]
+    fn scores_decay_towards_neutral() {
+        let score = -20.0;
+        let updated = Instant::now() - SCORE_HALF_LIFE;
+        let decayed = decayed(score, updated);
+        assert!((decayed + 10.0).abs() < 0.1);
+    }
+}
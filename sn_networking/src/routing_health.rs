@@ -0,0 +1,104 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{driver::SwarmDriver, relay_manager::is_a_relayed_peer, target_arch::SystemTime};
+use libp2p::{kad::K_VALUE, PeerId};
+use sn_protocol::NetworkAddress;
+use std::collections::HashSet;
+
+/// A summary of how well connected the node is, from the state of its routing table.
+#[derive(Debug, Clone)]
+pub struct RoutingHealth {
+    /// The non-empty buckets, the closest to us first
+    pub buckets: Vec<BucketHealth>,
+    /// The number of nodes in the network, estimated from the density of the buckets
+    pub estimated_network_size: usize,
+    /// The ilog2 distance up to which the node is responsible for records, if set yet
+    pub responsible_distance_range: Option<u32>,
+}
+
+/// The state of a bucket of the routing table.
+#[derive(Debug, Clone)]
+pub struct BucketHealth {
+    /// The ilog2 distance of the peers of the bucket to us
+    pub distance: u32,
+    pub num_peers: usize,
+    /// The number of peers the bucket can hold
+    pub capacity: usize,
+    /// The number of peers only reachable through a relay
+    pub relayed_peers: usize,
+    /// When a peer of the bucket last answered a request from us
+    pub last_successful_query: Option<SystemTime>,
+}
+
+impl SwarmDriver {
+    /// Records a peer answering a request from us, for the routing health of its bucket.
+    pub(crate) fn record_successful_query(&mut self, peer: &PeerId) {
+        if let Some(distance) = self.distance_to(peer) {
+            let _ = self
+                .last_successful_queries
+                .insert(distance, SystemTime::now());
+        }
+    }
+
+    pub(crate) fn routing_health(&mut self) -> RoutingHealth {
+        let mut buckets = vec![];
+        let mut peers_in_non_full_buckets = 0;
+        let mut num_of_full_buckets = 0;
+
+        for kbucket in self.swarm.behaviour_mut().kademlia.kbuckets() {
+            let Some(distance) = kbucket.range().0.ilog2() else {
+                // This shall never happen.
+                error!("bucket is ourself ???!!!");
+                continue;
+            };
+            let num_peers = kbucket.num_entries();
+            if num_peers >= K_VALUE.get() {
+                num_of_full_buckets += 1;
+            } else {
+                peers_in_non_full_buckets += num_peers;
+            }
+
+            let relayed_peers = kbucket
+                .iter()
+                .filter(|entry| {
+                    let addrs: HashSet<_> = entry.node.value.iter().cloned().collect();
+                    is_a_relayed_peer(&addrs)
+                })
+                .count();
+
+            buckets.push(BucketHealth {
+                distance,
+                num_peers,
+                capacity: K_VALUE.get(),
+                relayed_peers,
+                last_successful_query: self.last_successful_queries.get(&distance).copied(),
+            });
+        }
+
+        RoutingHealth {
+            buckets,
+            estimated_network_size: Self::estimate_network_size(
+                peers_in_non_full_buckets,
+                num_of_full_buckets,
+            ),
+            responsible_distance_range: self
+                .swarm
+                .behaviour_mut()
+                .kademlia
+                .store_mut()
+                .get_farthest_replication_distance_bucket(),
+        }
+    }
+
+    fn distance_to(&self, peer: &PeerId) -> Option<u32> {
+        NetworkAddress::from_peer(self.self_peer_id)
+            .distance(&NetworkAddress::from_peer(*peer))
+            .ilog2()
+    }
+}
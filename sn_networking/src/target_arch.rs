@@ -7,21 +7,28 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 #[cfg(not(target_arch = "wasm32"))]
-pub use std::time::Instant;
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
 /// Wasm32 target arch does not support `time` or spawning via tokio
 /// so we shim in alternatives here when building for that architecture
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use tokio::{
     spawn,
+    task::spawn_blocking,
     time::{interval, sleep, timeout, Duration, Interval},
 };
 
 #[cfg(target_arch = "wasm32")]
 pub use wasmtimer::{
-    std::Instant,
+    std::{Instant, SystemTime, UNIX_EPOCH},
     tokio::{interval, sleep, timeout, Interval},
 };
 
 #[cfg(target_arch = "wasm32")]
 pub use wasm_bindgen_futures::spawn_local as spawn;
+
+/// There are no threads to offload blocking work to, so it is run in place.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn_blocking<F: FnOnce() + 'static>(f: F) {
+    f()
+}
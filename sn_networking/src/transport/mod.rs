@@ -3,3 +3,68 @@
 pub(crate) mod mod_impl;
 
 pub(crate) use mod_impl::build_transport;
+
+use libp2p::{multiaddr::Protocol, Multiaddr};
+use std::{fmt, net::SocketAddr, str::FromStr};
+
+/// The transports a node can listen on. The node can dial peers over any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListenTransport {
+    /// QUIC over UDP
+    Quic,
+    /// TCP, authenticated with noise and multiplexed with yamux
+    Tcp,
+    /// WebSocket over TCP, authenticated with noise and multiplexed with yamux
+    WebSocket,
+}
+
+impl ListenTransport {
+    /// The transports listened on when none are selected.
+    /// WebSocket is added with the `websockets` feature, or when compiling for wasm32.
+    pub fn defaults() -> Vec<Self> {
+        if cfg!(any(feature = "websockets", target_arch = "wasm32")) {
+            vec![Self::Quic, Self::WebSocket]
+        } else {
+            vec![Self::Quic]
+        }
+    }
+
+    /// The multiaddr to listen on the socket address over this transport.
+    pub(crate) fn listen_addr(&self, socket_addr: SocketAddr) -> Multiaddr {
+        let addr = Multiaddr::from(socket_addr.ip());
+        match self {
+            Self::Quic => addr
+                .with(Protocol::Udp(socket_addr.port()))
+                .with(Protocol::QuicV1),
+            Self::Tcp => addr.with(Protocol::Tcp(socket_addr.port())),
+            Self::WebSocket => addr
+                .with(Protocol::Tcp(socket_addr.port()))
+                .with(Protocol::Ws("/".into())),
+        }
+    }
+}
+
+impl fmt::Display for ListenTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quic => write!(f, "quic"),
+            Self::Tcp => write!(f, "tcp"),
+            Self::WebSocket => write!(f, "websocket"),
+        }
+    }
+}
+
+impl FromStr for ListenTransport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quic" => Ok(Self::Quic),
+            "tcp" => Ok(Self::Tcp),
+            "websocket" => Ok(Self::WebSocket),
+            other => Err(format!(
+                "Unknown transport {other:?}, expected `quic`, `tcp` or `websocket`"
+            )),
+        }
+    }
+}
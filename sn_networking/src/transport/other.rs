@@ -1,39 +1,29 @@
-#[cfg(feature = "websockets")]
 use futures::future::Either;
-#[cfg(feature = "websockets")]
-use libp2p::{core::upgrade, noise, yamux};
 use libp2p::{
-    core::{muxing::StreamMuxerBox, transport},
+    core::{muxing::StreamMuxerBox, transport, upgrade},
     identity::Keypair,
-    PeerId, Transport as _,
+    noise, yamux, PeerId, Transport as _,
 };
 
+/// Builds a transport able to dial over QUIC, WebSocket and TCP,
+/// whichever of them the node listens on.
 pub(crate) fn build_transport(keypair: &Keypair) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
-    let trans = generate_quic_transport(keypair);
+    let quic = generate_quic_transport(keypair)
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
 
-    #[cfg(feature = "websockets")]
-    // Using a closure here due to the complex return type
-    let generate_ws_transport = || {
-        let tcp = libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default());
-        libp2p::websocket::WsConfig::new(tcp)
-            .upgrade(upgrade::Version::V1)
-            .authenticate(
-                noise::Config::new(keypair)
-                    .expect("Signing libp2p-noise static DH keypair failed."),
-            )
-            .multiplex(yamux::Config::default())
-    };
-
-    // With the `websockets` feature enabled, we add it as a fallback transport.
-    #[cfg(feature = "websockets")]
-    let trans = trans
-        .or_transport(generate_ws_transport())
+    // The WebSocket transport is tried before the plain TCP one, which refuses the `/ws`
+    // multiaddrs anyway.
+    let trans = quic
+        .or_transport(generate_ws_transport(keypair))
+        .map(|either_output, _| match either_output {
+            Either::Left(output) => output,
+            Either::Right(output) => output,
+        })
+        .or_transport(generate_tcp_transport(keypair))
         .map(|either_output, _| match either_output {
-            Either::Left((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
-            Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+            Either::Left(output) => output,
+            Either::Right(output) => output,
         });
-    #[cfg(not(feature = "websockets"))]
-    let trans = trans.map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
 
     trans.boxed()
 }
@@ -43,3 +33,26 @@ fn generate_quic_transport(
 ) -> libp2p::quic::GenTransport<libp2p::quic::tokio::Provider> {
     libp2p::quic::tokio::Transport::new(libp2p::quic::Config::new(keypair))
 }
+
+fn generate_ws_transport(keypair: &Keypair) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
+    let tcp = libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default());
+    libp2p::websocket::WsConfig::new(tcp)
+        .upgrade(upgrade::Version::V1)
+        .authenticate(
+            noise::Config::new(keypair).expect("Signing libp2p-noise static DH keypair failed."),
+        )
+        .multiplex(yamux::Config::default())
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed()
+}
+
+fn generate_tcp_transport(keypair: &Keypair) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
+    libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default())
+        .upgrade(upgrade::Version::V1)
+        .authenticate(
+            noise::Config::new(keypair).expect("Signing libp2p-noise static DH keypair failed."),
+        )
+        .multiplex(yamux::Config::default())
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed()
+}
@@ -17,6 +17,7 @@ use libp2p::{identity::Keypair, PeerId};
 #[cfg(feature = "metrics")]
 use sn_logging::metrics::init_metrics;
 use sn_logging::{Level, LogFormat, LogOutputDest, ReloadHandle};
+use sn_networking::{read_cached_peers, ListenTransport, RecordStorageBackendKind};
 use sn_node::{Marker, NodeBuilder, NodeEvent, NodeEventsReceiver};
 use sn_peers_acquisition::PeersArgs;
 use sn_protocol::{node::get_safenode_root_dir, node_rpc::NodeCtrl};
@@ -140,6 +141,18 @@ struct Opt {
     #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::UNSPECIFIED))]
     ip: IpAddr,
 
+    /// Specify the transports to listen on, all of them on the same port.
+    ///
+    /// Valid values are "quic", "tcp" or "websocket". The argument can be repeated, or take a
+    /// comma-separated list, e.g. `--transport quic,tcp`.
+    ///
+    /// "tcp" and "websocket" can't be used together unless the port is 0.
+    ///
+    /// If the argument is not used, the node listens on QUIC, and also on WebSocket if built
+    /// with the `websockets` feature.
+    #[clap(long = "transport", value_delimiter = ',', verbatim_doc_comment)]
+    transports: Vec<ListenTransport>,
+
     #[command(flatten)]
     peers: PeersArgs,
 
@@ -159,6 +172,55 @@ struct Opt {
     #[clap(long)]
     owner: Option<String>,
 
+    /// Rotate the key used to encrypt the stored records, re-encrypting all of them on startup.
+    ///
+    /// Only has an effect if the node was built with the `encrypt-records` feature.
+    #[clap(long, default_value_t = false)]
+    rotate_record_encryption_key: bool,
+
+    /// Specify the backend used to store the records on disk.
+    ///
+    /// Valid values are "flat-file" (one file per record) or "append-log" (a single log file with an index).
+    ///
+    /// Switching to "append-log" migrates the records stored as flat files into the log.
+    #[clap(long, default_value_t = RecordStorageBackendKind::FlatFile, verbatim_doc_comment)]
+    record_storage_backend: RecordStorageBackendKind,
+
+    /// Specify the maximum disk space, in bytes, the stored records can take.
+    ///
+    /// When the limit is reached, the records farthest from the node are pruned to make room.
+    /// If not set, the node stores up to a fixed number of records instead.
+    #[clap(long)]
+    max_storage_bytes: Option<u64>,
+
+    /// Specify the maximum number of established connections, inbound and outbound.
+    ///
+    /// Connections beyond the limit are denied. If not set, the connections are not capped.
+    #[clap(long)]
+    max_connections: Option<u32>,
+
+    /// Specify the maximum number of established connections with any single peer.
+    #[clap(long)]
+    max_connections_per_peer: Option<u32>,
+
+    /// Specify the maximum bytes per second the node can send, over all its connections.
+    ///
+    /// Only the payloads of the substreams are capped, not the framing of the muxer
+    /// and transport, nor their handshakes.
+    ///
+    /// If not set, the upload bandwidth is not capped.
+    #[clap(long)]
+    max_upload_bytes_per_sec: Option<u64>,
+
+    /// Specify the maximum bytes per second the node can receive, over all its connections.
+    ///
+    /// Only the payloads of the substreams are capped, not the framing of the muxer
+    /// and transport, nor their handshakes.
+    ///
+    /// If not set, the download bandwidth is not capped.
+    #[clap(long)]
+    max_download_bytes_per_sec: Option<u64>,
+
     #[cfg(feature = "open-metrics")]
     /// Specify the port for the OpenMetrics server.
     ///
@@ -190,7 +252,9 @@ fn main() -> Result<()> {
         init_logging(&opt, keypair.public().to_peer_id())?;
 
     let rt = Runtime::new()?;
-    let bootstrap_peers = rt.block_on(opt.peers.get_peers())?;
+    // the peers learnt by a previous run let the node rejoin without the network contacts
+    let cached_peers = read_cached_peers(&root_dir);
+    let bootstrap_peers = rt.block_on(opt.peers.get_peers_or_cached(cached_peers))?;
     let msg = format!(
         "Running {} v{}",
         env!("CARGO_BIN_NAME"),
@@ -222,6 +286,14 @@ fn main() -> Result<()> {
             opt.upnp,
         );
         node_builder.is_behind_home_network = opt.home_network;
+        node_builder.listen_transports = opt.transports.clone();
+        node_builder.rotate_record_encryption_key = opt.rotate_record_encryption_key;
+        node_builder.record_storage_backend = opt.record_storage_backend;
+        node_builder.max_storage_bytes = opt.max_storage_bytes;
+        node_builder.max_connections = opt.max_connections;
+        node_builder.max_connections_per_peer = opt.max_connections_per_peer;
+        node_builder.max_upload_bytes_per_sec = opt.max_upload_bytes_per_sec;
+        node_builder.max_download_bytes_per_sec = opt.max_download_bytes_per_sec;
         #[cfg(feature = "open-metrics")]
         let mut node_builder = node_builder;
         // if enable flag is provided or only if the port is specified then enable the server by setting Some()
@@ -11,19 +11,20 @@ use sn_logging::ReloadHandle;
 use sn_node::RunningNode;
 use sn_protocol::node_rpc::NodeCtrl;
 use sn_protocol::safenode_proto::{
-    k_buckets_response,
+    bad_nodes_response, k_buckets_response, routing_health_response,
     safe_node_server::{SafeNode, SafeNodeServer},
-    KBucketsRequest, KBucketsResponse, NetworkInfoRequest, NetworkInfoResponse, NodeEvent,
-    NodeEventsRequest, NodeInfoRequest, NodeInfoResponse, RecordAddressesRequest,
-    RecordAddressesResponse, RestartRequest, RestartResponse, StopRequest, StopResponse,
-    UpdateLogLevelRequest, UpdateLogLevelResponse, UpdateRequest, UpdateResponse,
+    BadNodesRequest, BadNodesResponse, KBucketsRequest, KBucketsResponse, NetworkInfoRequest,
+    NetworkInfoResponse, NodeEvent, NodeEventsRequest, NodeInfoRequest, NodeInfoResponse,
+    RecordAddressesRequest, RecordAddressesResponse, RestartRequest, RestartResponse,
+    RoutingHealthRequest, RoutingHealthResponse, StopRequest, StopResponse, UpdateLogLevelRequest,
+    UpdateLogLevelResponse, UpdateRequest, UpdateResponse,
 };
 use std::{
     collections::HashMap,
     env,
     net::SocketAddr,
     process,
-    time::{Duration, Instant},
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 use tokio::sync::mpsc::{self, Sender};
 use tokio_stream::wrappers::ReceiverStream;
@@ -55,6 +56,12 @@ impl SafeNode for SafeNodeRpcService {
             request.get_ref()
         );
 
+        let state = self
+            .running_node
+            .get_swarm_local_state()
+            .await
+            .expect("failed to get local swarm state");
+
         let resp = Response::new(NodeInfoResponse {
             peer_id: self.running_node.peer_id().to_bytes(),
             log_dir: self.log_dir.clone(),
@@ -71,6 +78,11 @@ impl SafeNode for SafeNodeRpcService {
                 .get_node_wallet_balance()
                 .expect("Failed to get node wallet balance")
                 .as_nano(),
+            connections: state.connections,
+            bytes_sent: state.bandwidth.bytes_sent,
+            bytes_received: state.bandwidth.bytes_received,
+            upload_rate: state.bandwidth.upload_rate,
+            download_rate: state.bandwidth.download_rate,
         });
 
         Ok(resp)
@@ -190,6 +202,84 @@ impl SafeNode for SafeNodeRpcService {
         Ok(Response::new(KBucketsResponse { kbuckets }))
     }
 
+    async fn bad_nodes(
+        &self,
+        request: Request<BadNodesRequest>,
+    ) -> Result<Response<BadNodesResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let bad_nodes = self
+            .running_node
+            .get_bad_nodes()
+            .await
+            .expect("failed to get bad nodes")
+            .into_iter()
+            .map(|bad_node| {
+                let issues = bad_node
+                    .issues
+                    .into_iter()
+                    .map(|(issue, timestamp)| bad_nodes_response::NodeIssue {
+                        issue: format!("{issue:?}"),
+                        timestamp_secs: timestamp
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                    })
+                    .collect();
+                bad_nodes_response::BadNode {
+                    peer_id: bad_node.peer_id.to_bytes(),
+                    issues,
+                    is_bad: bad_node.is_bad,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(BadNodesResponse { bad_nodes }))
+    }
+
+    async fn routing_health(
+        &self,
+        request: Request<RoutingHealthRequest>,
+    ) -> Result<Response<RoutingHealthResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let routing_health = self
+            .running_node
+            .get_routing_health()
+            .await
+            .expect("failed to get routing health");
+        let buckets = routing_health
+            .buckets
+            .into_iter()
+            .map(|bucket| routing_health_response::Bucket {
+                distance: bucket.distance,
+                num_peers: bucket.num_peers as u32,
+                capacity: bucket.capacity as u32,
+                relayed_peers: bucket.relayed_peers as u32,
+                last_successful_query_secs: bucket
+                    .last_successful_query
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map_or(0, |elapsed| elapsed.as_secs()),
+            })
+            .collect();
+
+        Ok(Response::new(RoutingHealthResponse {
+            buckets,
+            estimated_network_size: routing_health.estimated_network_size as u64,
+            responsible_distance_range: routing_health
+                .responsible_distance_range
+                .map(|distance| routing_health_response::DistanceRange { distance }),
+        }))
+    }
+
     async fn stop(&self, request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
         debug!(
             "RPC request received at {}: {:?}",
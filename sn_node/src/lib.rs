@@ -46,7 +46,7 @@ pub use self::{
 use crate::error::{Error, Result};
 
 use libp2p::PeerId;
-use sn_networking::{Network, SwarmLocalState};
+use sn_networking::{BadNodeInfo, Network, RoutingHealth, SwarmLocalState};
 use sn_protocol::{get_port_from_multiaddr, NetworkAddress};
 use sn_transfers::{HotWallet, NanoTokens};
 use std::{
@@ -95,6 +95,18 @@ impl RunningNode {
         Ok(state)
     }
 
+    /// Returns the peers this node has recorded issues against, and whether it considers them as bad.
+    pub async fn get_bad_nodes(&self) -> Result<Vec<BadNodeInfo>> {
+        let bad_nodes = self.network.get_bad_nodes().await?;
+        Ok(bad_nodes)
+    }
+
+    /// Returns a summary of the state of the node's routing table, to tell how well connected it is.
+    pub async fn get_routing_health(&self) -> Result<RoutingHealth> {
+        let routing_health = self.network.get_routing_health().await?;
+        Ok(routing_health)
+    }
+
     /// Return the node's listening port
     pub async fn get_node_listening_port(&self) -> Result<u16> {
         let listen_addrs = self.network.get_swarm_local_state().await?.listeners;
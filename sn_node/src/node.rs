@@ -23,8 +23,8 @@ use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::registry::Registry;
 use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 use sn_networking::{
-    close_group_majority, Instant, Network, NetworkBuilder, NetworkError, NetworkEvent, NodeIssue,
-    SwarmDriver, CLOSE_GROUP_SIZE,
+    close_group_majority, Instant, ListenTransport, Network, NetworkBuilder, NetworkError,
+    NetworkEvent, NodeCredit, NodeIssue, RecordStorageBackendKind, SwarmDriver, CLOSE_GROUP_SIZE,
 };
 use sn_protocol::{
     error::Error as ProtocolError,
@@ -83,6 +83,23 @@ pub struct NodeBuilder {
     metrics_server_port: Option<u16>,
     /// Enable hole punching for nodes connecting from home networks.
     pub is_behind_home_network: bool,
+    /// The transports to listen on, the defaults of the network are used if empty.
+    pub listen_transports: Vec<ListenTransport>,
+    /// Cap on the number of established connections.
+    pub max_connections: Option<u32>,
+    /// Cap on the number of established connections with any single peer.
+    pub max_connections_per_peer: Option<u32>,
+    /// Cap on the bytes sent per second.
+    pub max_upload_bytes_per_sec: Option<u64>,
+    /// Cap on the bytes received per second.
+    pub max_download_bytes_per_sec: Option<u64>,
+    /// Rotate the key used to encrypt the records at rest on startup.
+    pub rotate_record_encryption_key: bool,
+    /// The backend used to store the records on disk.
+    pub record_storage_backend: RecordStorageBackendKind,
+    /// Limit the disk space used by the records, in bytes.
+    /// The default limit on the number of records applies if not set.
+    pub max_storage_bytes: Option<u64>,
     owner: Option<String>,
     #[cfg(feature = "upnp")]
     upnp: bool,
@@ -108,6 +125,14 @@ impl NodeBuilder {
             #[cfg(feature = "open-metrics")]
             metrics_server_port: None,
             is_behind_home_network: false,
+            listen_transports: vec![],
+            max_connections: None,
+            max_connections_per_peer: None,
+            max_upload_bytes_per_sec: None,
+            max_download_bytes_per_sec: None,
+            rotate_record_encryption_key: false,
+            record_storage_backend: Default::default(),
+            max_storage_bytes: None,
             owner,
             #[cfg(feature = "upnp")]
             upnp,
@@ -159,12 +184,30 @@ impl NodeBuilder {
         let mut network_builder = NetworkBuilder::new(self.keypair, self.local, self.root_dir);
 
         network_builder.listen_addr(self.addr);
+        network_builder.listen_transports(self.listen_transports.clone());
         #[cfg(feature = "open-metrics")]
         network_builder.metrics_registry(metrics_registry);
         #[cfg(feature = "open-metrics")]
         network_builder.metrics_server_port(self.metrics_server_port);
         network_builder.initial_peers(self.initial_peers.clone());
         network_builder.is_behind_home_network(self.is_behind_home_network);
+        network_builder.rotate_record_encryption_key(self.rotate_record_encryption_key);
+        network_builder.record_storage_backend(self.record_storage_backend);
+        if let Some(max_storage_bytes) = self.max_storage_bytes {
+            network_builder.max_storage_bytes(max_storage_bytes);
+        }
+        if let Some(max_connections) = self.max_connections {
+            network_builder.max_connections(max_connections);
+        }
+        if let Some(max_connections_per_peer) = self.max_connections_per_peer {
+            network_builder.max_connections_per_peer(max_connections_per_peer);
+        }
+        if let Some(max_upload_bytes_per_sec) = self.max_upload_bytes_per_sec {
+            network_builder.max_upload_bytes_per_sec(max_upload_bytes_per_sec);
+        }
+        if let Some(max_download_bytes_per_sec) = self.max_download_bytes_per_sec {
+            network_builder.max_download_bytes_per_sec(max_download_bytes_per_sec);
+        }
 
         #[cfg(feature = "upnp")]
         network_builder.upnp(self.upnp);
@@ -604,6 +647,7 @@ impl Node {
                     let mut attempts = 0;
                     while attempts < MAX_CHUNK_PROOF_VERIFY_ATTEMPTS {
                         if chunk_proof_verify_peer(&network, peer_id, &keys_to_verify).await {
+                            network.record_node_credit(peer_id, NodeCredit::ValidChunkProof);
                             return;
                         }
                         // Replication interval is 22s - 45s.
@@ -613,8 +657,8 @@ impl Node {
                     }
                     // Now ALL attempts failed, hence report the issue.
                     // Note this won't immediately trigger the node to be considered as BAD.
-                    // Only the same peer accumulated three same issue
-                    // within 5 mins will be considered as BAD.
+                    // Only once the issues dropped the reputation of the peer low enough,
+                    // i.e. about three of them within a few mins, it will be considered as BAD.
                     // As the chunk_proof_check will be triggered every periodical replication,
                     // a low performed or cheaty peer will raise multiple issue alerts during it.
                     network.record_node_issues(peer_id, NodeIssue::FailedChunkProofCheck);
@@ -360,6 +360,12 @@ impl Node {
                     warn!("Ignoring spend for another record key {spend_pretty:?} when verifying: {pretty_key:?}");
                     return false;
                 }
+                // reject the spends with an invalid signature or tx early on,
+                // before fetching their parent spends from the network
+                if let Err(err) = s.verify(s.spent_tx_hash()) {
+                    warn!("Ignoring spend {spend_pretty:?} with an invalid signature or tx: {err}");
+                    return false;
+                }
                 true
             })
             .collect();
@@ -610,7 +616,8 @@ impl Node {
         };
         let local_register: SignedRegister = try_deserialize_record(&record)?;
 
-        // merge the two registers
+        // merge the two registers, the ops we didn't know of yet are only accepted from the
+        // current writers, this is how removing a writer takes effect
         let mut merged_register = local_register.clone();
         merged_register.verified_merge(register)?;
         if merged_register == local_register {
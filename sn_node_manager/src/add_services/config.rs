@@ -48,9 +48,14 @@ pub struct InstallNodeServiceCtxBuilder {
     pub env_variables: Option<Vec<(String, String)>>,
     pub genesis: bool,
     pub home_network: bool,
+    pub listen_transports: Vec<String>,
     pub local: bool,
     pub log_dir_path: PathBuf,
     pub log_format: Option<LogFormat>,
+    pub max_connections: Option<u32>,
+    pub max_download_bytes_per_sec: Option<u64>,
+    pub max_storage_bytes: Option<u64>,
+    pub max_upload_bytes_per_sec: Option<u64>,
     pub name: String,
     pub metrics_port: Option<u16>,
     pub node_port: Option<u16>,
@@ -93,6 +98,10 @@ impl InstallNodeServiceCtxBuilder {
             args.push(OsString::from("--port"));
             args.push(OsString::from(node_port.to_string()));
         }
+        if !self.listen_transports.is_empty() {
+            args.push(OsString::from("--transport"));
+            args.push(OsString::from(self.listen_transports.join(",")));
+        }
         if let Some(metrics_port) = self.metrics_port {
             args.push(OsString::from("--metrics-server-port"));
             args.push(OsString::from(metrics_port.to_string()));
@@ -101,6 +110,22 @@ impl InstallNodeServiceCtxBuilder {
             args.push(OsString::from("--owner"));
             args.push(OsString::from(owner));
         }
+        if let Some(max_storage_bytes) = self.max_storage_bytes {
+            args.push(OsString::from("--max-storage-bytes"));
+            args.push(OsString::from(max_storage_bytes.to_string()));
+        }
+        if let Some(max_connections) = self.max_connections {
+            args.push(OsString::from("--max-connections"));
+            args.push(OsString::from(max_connections.to_string()));
+        }
+        if let Some(max_upload_bytes_per_sec) = self.max_upload_bytes_per_sec {
+            args.push(OsString::from("--max-upload-bytes-per-sec"));
+            args.push(OsString::from(max_upload_bytes_per_sec.to_string()));
+        }
+        if let Some(max_download_bytes_per_sec) = self.max_download_bytes_per_sec {
+            args.push(OsString::from("--max-download-bytes-per-sec"));
+            args.push(OsString::from(max_download_bytes_per_sec.to_string()));
+        }
 
         if !self.bootstrap_peers.is_empty() {
             let peers_str = self
@@ -136,8 +161,13 @@ pub struct AddNodeServiceOptions {
     pub env_variables: Option<Vec<(String, String)>>,
     pub genesis: bool,
     pub home_network: bool,
+    pub listen_transports: Vec<String>,
     pub local: bool,
     pub log_format: Option<LogFormat>,
+    pub max_connections: Option<u32>,
+    pub max_download_bytes_per_sec: Option<u64>,
+    pub max_storage_bytes: Option<u64>,
+    pub max_upload_bytes_per_sec: Option<u64>,
     pub metrics_port: Option<PortRange>,
     pub owner: Option<String>,
     pub node_port: Option<PortRange>,
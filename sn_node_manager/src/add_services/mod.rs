@@ -234,9 +234,14 @@ pub async fn add_node(
             env_variables: options.env_variables.clone(),
             genesis: options.genesis,
             home_network: options.home_network,
+            listen_transports: options.listen_transports.clone(),
             local: options.local,
             log_dir_path: service_log_dir_path.clone(),
             log_format: options.log_format,
+            max_connections: options.max_connections,
+            max_download_bytes_per_sec: options.max_download_bytes_per_sec,
+            max_storage_bytes: options.max_storage_bytes,
+            max_upload_bytes_per_sec: options.max_upload_bytes_per_sec,
             metrics_port: metrics_free_port,
             name: service_name.clone(),
             node_port,
@@ -266,9 +271,14 @@ pub async fn add_node(
                     genesis: options.genesis,
                     home_network: options.home_network,
                     listen_addr: None,
+                    listen_transports: options.listen_transports.clone(),
                     local: options.local,
                     log_dir_path: service_log_dir_path.clone(),
                     log_format: options.log_format,
+                    max_connections: options.max_connections,
+                    max_download_bytes_per_sec: options.max_download_bytes_per_sec,
+                    max_storage_bytes: options.max_storage_bytes,
+                    max_upload_bytes_per_sec: options.max_upload_bytes_per_sec,
                     metrics_port: metrics_free_port,
                     node_port,
                     number: node_number,
@@ -117,9 +117,14 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
         env_variables: None,
         genesis: true,
         home_network: false,
+        listen_transports: vec![],
         local: true,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -151,8 +156,13 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
             env_variables: None,
             genesis: true,
             home_network: false,
+            listen_transports: vec![],
             local: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -222,9 +232,14 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             genesis: true,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -267,8 +282,13 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             env_variables: None,
             genesis: true,
             home_network: false,
+            listen_transports: vec![],
             local: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -335,8 +355,13 @@ async fn add_genesis_node_should_return_an_error_if_count_is_greater_than_1() ->
             env_variables: None,
             genesis: true,
             home_network: false,
+            listen_transports: vec![],
             local: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -408,9 +433,14 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -445,9 +475,14 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode2"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode2".to_string(),
         node_port: None,
@@ -482,8 +517,13 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode3"),
         metrics_port: None,
         name: "safenode3".to_string(),
@@ -517,8 +557,13 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -639,9 +684,14 @@ async fn add_node_should_update_the_bootstrap_peers_inside_node_registry() -> Re
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -671,10 +721,15 @@ async fn add_node_should_update_the_bootstrap_peers_inside_node_registry() -> Re
             delete_safenode_src: true,
             enable_metrics_server: false,
             env_variables: None,
+            listen_transports: vec![],
             local: false,
             genesis: false,
             home_network: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -769,9 +824,14 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
         env_variables: env_variables.clone(),
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -803,8 +863,13 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
             env_variables: env_variables.clone(),
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -873,9 +938,14 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             genesis: true,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -917,9 +987,14 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode2"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode2".to_string(),
         node_port: None,
@@ -952,8 +1027,13 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -1039,9 +1119,14 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: Some(custom_port),
@@ -1074,8 +1159,13 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Single(custom_port)),
@@ -1292,8 +1382,13 @@ async fn add_node_should_use_a_custom_port_range() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Range(12000, 12002)),
@@ -1342,8 +1437,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             metrics_port: None,
             node_port: Some(12000),
@@ -1385,8 +1485,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Single(12000)),
@@ -1433,8 +1538,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             metrics_port: None,
             node_port: Some(12000),
@@ -1476,8 +1586,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Range(12000, 12002)),
@@ -1542,8 +1657,13 @@ async fn add_node_should_return_an_error_if_port_and_node_count_do_not_match() -
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Range(12000, 12002)),
@@ -1613,8 +1733,13 @@ async fn add_node_should_return_an_error_if_multiple_services_are_specified_with
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: Some(PortRange::Single(12000)),
@@ -1737,8 +1862,13 @@ async fn add_node_should_set_random_ports_if_enable_metrics_server_is_true() ->
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -1949,8 +2079,13 @@ async fn add_node_should_use_a_custom_port_range_for_metrics_server() -> Result<
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(PortRange::Range(12000, 12002)),
             owner: None,
             node_port: None,
@@ -1996,9 +2131,14 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(12000),
             node_port: None,
             number: 1,
@@ -2039,8 +2179,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(PortRange::Single(12000)),
             owner: None,
             node_port: None,
@@ -2088,9 +2233,14 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(12000),
             node_port: None,
             number: 1,
@@ -2131,8 +2281,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(PortRange::Range(12000, 12002)),
             owner: None,
             node_port: None,
@@ -2327,8 +2482,13 @@ async fn add_node_should_use_a_custom_port_range_for_the_rpc_server() -> Result<
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2385,9 +2545,14 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2428,8 +2593,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2477,9 +2647,14 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2520,8 +2695,13 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2592,9 +2772,14 @@ async fn add_node_should_disable_upnp_and_home_network_if_nat_status_is_public()
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -2624,10 +2809,15 @@ async fn add_node_should_disable_upnp_and_home_network_if_nat_status_is_public()
             delete_safenode_src: true,
             enable_metrics_server: false,
             env_variables: None,
+            listen_transports: vec![],
             local: false,
             genesis: false,
             home_network: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2695,9 +2885,14 @@ async fn add_node_should_enable_upnp_if_nat_status_is_upnp() -> Result<()> {
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -2727,10 +2922,15 @@ async fn add_node_should_enable_upnp_if_nat_status_is_upnp() -> Result<()> {
             delete_safenode_src: true,
             enable_metrics_server: false,
             env_variables: None,
+            listen_transports: vec![],
             local: false,
             genesis: false,
             home_network: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2798,9 +2998,14 @@ async fn add_node_should_enable_home_network_if_nat_status_is_private() -> Resul
         env_variables: None,
         genesis: false,
         home_network: true,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -2830,10 +3035,15 @@ async fn add_node_should_enable_home_network_if_nat_status_is_private() -> Resul
             delete_safenode_src: true,
             enable_metrics_server: false,
             env_variables: None,
+            listen_transports: vec![],
             local: false,
             genesis: false,
             home_network: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -2904,10 +3114,15 @@ async fn add_node_should_return_an_error_if_nat_status_is_none_but_auto_set_nat_
             delete_safenode_src: true,
             enable_metrics_server: false,
             env_variables: None,
+            listen_transports: vec![],
             local: false,
             genesis: false,
             home_network: true,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -3516,9 +3731,14 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
         env_variables: None,
         genesis: false,
         home_network: false,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -3551,8 +3771,13 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -3621,9 +3846,14 @@ async fn add_node_should_apply_the_home_network_flag_if_it_is_used() -> Result<(
         env_variables: None,
         genesis: false,
         home_network: true,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -3656,8 +3886,13 @@ async fn add_node_should_apply_the_home_network_flag_if_it_is_used() -> Result<(
             env_variables: None,
             genesis: false,
             home_network: true,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -3726,9 +3961,14 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
         env_variables: None,
         genesis: false,
         home_network: true,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -3761,8 +4001,13 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: true,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -3828,9 +4073,14 @@ async fn add_node_should_add_the_node_with_upnp_enabled() -> Result<()> {
         env_variables: None,
         genesis: false,
         home_network: true,
+        listen_transports: vec![],
         local: false,
         log_dir_path: node_logs_dir.to_path_buf().join("safenode1"),
         log_format: None,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         name: "safenode1".to_string(),
         node_port: None,
@@ -3863,8 +4113,13 @@ async fn add_node_should_add_the_node_with_upnp_enabled() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: true,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: None,
             node_port: None,
@@ -3978,8 +4233,13 @@ async fn add_node_should_assign_an_owner() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: Some("discord_username".to_string()),
             node_port: None,
@@ -4095,8 +4355,13 @@ async fn add_node_should_auto_restart() -> Result<()> {
             env_variables: None,
             genesis: false,
             home_network: false,
+            listen_transports: vec![],
             local: false,
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             owner: Some("discord_username".to_string()),
             node_port: None,
@@ -4121,3 +4386,378 @@ async fn add_node_should_auto_restart() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn add_node_should_apply_the_max_storage_bytes_option() -> Result<()> {
+    let tmp_data_dir = assert_fs::TempDir::new()?;
+    let node_reg_path = tmp_data_dir.child("node_reg.json");
+
+    let latest_version = "0.96.4";
+    let temp_dir = assert_fs::TempDir::new()?;
+    let node_data_dir = temp_dir.child("data");
+    node_data_dir.create_dir_all()?;
+    let node_logs_dir = temp_dir.child("logs");
+    node_logs_dir.create_dir_all()?;
+    let safenode_download_path = temp_dir.child(SAFENODE_FILE_NAME);
+    safenode_download_path.write_binary(b"fake safenode bin")?;
+
+    let mut node_registry = NodeRegistry {
+        auditor: None,
+        bootstrap_peers: vec![],
+        daemon: None,
+        environment_variables: None,
+        faucet: None,
+        nat_status: None,
+        nodes: vec![],
+        save_path: node_reg_path.to_path_buf(),
+    };
+
+    let mut mock_service_control = MockServiceControl::new();
+    let mut seq = Sequence::new();
+    mock_service_control
+        .expect_get_available_port()
+        .times(1)
+        .returning(|| Ok(8081))
+        .in_sequence(&mut seq);
+
+    mock_service_control
+        .expect_install()
+        .with(
+            eq(ServiceInstallCtx {
+                args: vec![
+                    OsString::from("--rpc"),
+                    OsString::from("127.0.0.1:8081"),
+                    OsString::from("--root-dir"),
+                    OsString::from(
+                        node_data_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--log-output-dest"),
+                    OsString::from(
+                        node_logs_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--max-storage-bytes"),
+                    OsString::from("53687091200"),
+                ],
+                autostart: false,
+                contents: None,
+                environment: None,
+                label: "safenode1".parse()?,
+                program: node_data_dir
+                    .to_path_buf()
+                    .join("safenode1")
+                    .join(SAFENODE_FILE_NAME),
+                username: Some(get_username()),
+                working_directory: None,
+            }),
+            eq(false),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()))
+        .in_sequence(&mut seq);
+
+    add_node(
+        AddNodeServiceOptions {
+            auto_restart: false,
+            auto_set_nat_flags: false,
+            bootstrap_peers: vec![],
+            count: None,
+            delete_safenode_src: true,
+            enable_metrics_server: false,
+            env_variables: None,
+            genesis: false,
+            home_network: false,
+            listen_transports: vec![],
+            local: false,
+            log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: Some(53_687_091_200),
+            max_upload_bytes_per_sec: None,
+            metrics_port: None,
+            owner: None,
+            node_port: None,
+            rpc_address: None,
+            rpc_port: None,
+            safenode_dir_path: temp_dir.to_path_buf(),
+            safenode_src_path: safenode_download_path.to_path_buf(),
+            service_data_dir_path: node_data_dir.to_path_buf(),
+            service_log_dir_path: node_logs_dir.to_path_buf(),
+            upnp: false,
+            user: Some(get_username()),
+            user_mode: false,
+            version: latest_version.to_string(),
+        },
+        &mut node_registry,
+        &mock_service_control,
+        VerbosityLevel::Normal,
+    )
+    .await?;
+
+    assert_eq!(
+        node_registry.nodes[0].max_storage_bytes,
+        Some(53_687_091_200)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_node_should_apply_the_listen_transports_option() -> Result<()> {
+    let tmp_data_dir = assert_fs::TempDir::new()?;
+    let node_reg_path = tmp_data_dir.child("node_reg.json");
+
+    let latest_version = "0.96.4";
+    let temp_dir = assert_fs::TempDir::new()?;
+    let node_data_dir = temp_dir.child("data");
+    node_data_dir.create_dir_all()?;
+    let node_logs_dir = temp_dir.child("logs");
+    node_logs_dir.create_dir_all()?;
+    let safenode_download_path = temp_dir.child(SAFENODE_FILE_NAME);
+    safenode_download_path.write_binary(b"fake safenode bin")?;
+
+    let mut node_registry = NodeRegistry {
+        auditor: None,
+        bootstrap_peers: vec![],
+        daemon: None,
+        environment_variables: None,
+        faucet: None,
+        nat_status: None,
+        nodes: vec![],
+        save_path: node_reg_path.to_path_buf(),
+    };
+
+    let mut mock_service_control = MockServiceControl::new();
+    let mut seq = Sequence::new();
+    mock_service_control
+        .expect_get_available_port()
+        .times(1)
+        .returning(|| Ok(8081))
+        .in_sequence(&mut seq);
+
+    mock_service_control
+        .expect_install()
+        .with(
+            eq(ServiceInstallCtx {
+                args: vec![
+                    OsString::from("--rpc"),
+                    OsString::from("127.0.0.1:8081"),
+                    OsString::from("--root-dir"),
+                    OsString::from(
+                        node_data_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--log-output-dest"),
+                    OsString::from(
+                        node_logs_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--transport"),
+                    OsString::from("quic,tcp"),
+                ],
+                autostart: false,
+                contents: None,
+                environment: None,
+                label: "safenode1".parse()?,
+                program: node_data_dir
+                    .to_path_buf()
+                    .join("safenode1")
+                    .join(SAFENODE_FILE_NAME),
+                username: Some(get_username()),
+                working_directory: None,
+            }),
+            eq(false),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()))
+        .in_sequence(&mut seq);
+
+    add_node(
+        AddNodeServiceOptions {
+            auto_restart: false,
+            auto_set_nat_flags: false,
+            bootstrap_peers: vec![],
+            count: None,
+            delete_safenode_src: true,
+            enable_metrics_server: false,
+            env_variables: None,
+            genesis: false,
+            home_network: false,
+            listen_transports: vec!["quic".to_string(), "tcp".to_string()],
+            local: false,
+            log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
+            metrics_port: None,
+            owner: None,
+            node_port: None,
+            rpc_address: None,
+            rpc_port: None,
+            safenode_dir_path: temp_dir.to_path_buf(),
+            safenode_src_path: safenode_download_path.to_path_buf(),
+            service_data_dir_path: node_data_dir.to_path_buf(),
+            service_log_dir_path: node_logs_dir.to_path_buf(),
+            upnp: false,
+            user: Some(get_username()),
+            user_mode: false,
+            version: latest_version.to_string(),
+        },
+        &mut node_registry,
+        &mock_service_control,
+        VerbosityLevel::Normal,
+    )
+    .await?;
+
+    assert_eq!(
+        node_registry.nodes[0].listen_transports,
+        vec!["quic".to_string(), "tcp".to_string()]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_node_should_apply_the_connection_and_bandwidth_limits() -> Result<()> {
+    let tmp_data_dir = assert_fs::TempDir::new()?;
+    let node_reg_path = tmp_data_dir.child("node_reg.json");
+
+    let latest_version = "0.96.4";
+    let temp_dir = assert_fs::TempDir::new()?;
+    let node_data_dir = temp_dir.child("data");
+    node_data_dir.create_dir_all()?;
+    let node_logs_dir = temp_dir.child("logs");
+    node_logs_dir.create_dir_all()?;
+    let safenode_download_path = temp_dir.child(SAFENODE_FILE_NAME);
+    safenode_download_path.write_binary(b"fake safenode bin")?;
+
+    let mut node_registry = NodeRegistry {
+        auditor: None,
+        bootstrap_peers: vec![],
+        daemon: None,
+        environment_variables: None,
+        faucet: None,
+        nat_status: None,
+        nodes: vec![],
+        save_path: node_reg_path.to_path_buf(),
+    };
+
+    let mut mock_service_control = MockServiceControl::new();
+    let mut seq = Sequence::new();
+    mock_service_control
+        .expect_get_available_port()
+        .times(1)
+        .returning(|| Ok(8081))
+        .in_sequence(&mut seq);
+
+    mock_service_control
+        .expect_install()
+        .with(
+            eq(ServiceInstallCtx {
+                args: vec![
+                    OsString::from("--rpc"),
+                    OsString::from("127.0.0.1:8081"),
+                    OsString::from("--root-dir"),
+                    OsString::from(
+                        node_data_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--log-output-dest"),
+                    OsString::from(
+                        node_logs_dir
+                            .to_path_buf()
+                            .join("safenode1")
+                            .to_string_lossy()
+                            .to_string(),
+                    ),
+                    OsString::from("--max-connections"),
+                    OsString::from("100"),
+                    OsString::from("--max-upload-bytes-per-sec"),
+                    OsString::from("1048576"),
+                    OsString::from("--max-download-bytes-per-sec"),
+                    OsString::from("2097152"),
+                ],
+                autostart: false,
+                contents: None,
+                environment: None,
+                label: "safenode1".parse()?,
+                program: node_data_dir
+                    .to_path_buf()
+                    .join("safenode1")
+                    .join(SAFENODE_FILE_NAME),
+                username: Some(get_username()),
+                working_directory: None,
+            }),
+            eq(false),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()))
+        .in_sequence(&mut seq);
+
+    add_node(
+        AddNodeServiceOptions {
+            auto_restart: false,
+            auto_set_nat_flags: false,
+            bootstrap_peers: vec![],
+            count: None,
+            delete_safenode_src: true,
+            enable_metrics_server: false,
+            env_variables: None,
+            genesis: false,
+            home_network: false,
+            listen_transports: vec![],
+            local: false,
+            log_format: None,
+            max_connections: Some(100),
+            max_download_bytes_per_sec: Some(2_097_152),
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: Some(1_048_576),
+            metrics_port: None,
+            owner: None,
+            node_port: None,
+            rpc_address: None,
+            rpc_port: None,
+            safenode_dir_path: temp_dir.to_path_buf(),
+            safenode_src_path: safenode_download_path.to_path_buf(),
+            service_data_dir_path: node_data_dir.to_path_buf(),
+            service_log_dir_path: node_logs_dir.to_path_buf(),
+            upnp: false,
+            user: Some(get_username()),
+            user_mode: false,
+            version: latest_version.to_string(),
+        },
+        &mut node_registry,
+        &mock_service_control,
+        VerbosityLevel::Normal,
+    )
+    .await?;
+
+    assert_eq!(node_registry.nodes[0].max_connections, Some(100));
+    assert_eq!(
+        node_registry.nodes[0].max_upload_bytes_per_sec,
+        Some(1_048_576)
+    );
+    assert_eq!(
+        node_registry.nodes[0].max_download_bytes_per_sec,
+        Some(2_097_152)
+    );
+
+    Ok(())
+}
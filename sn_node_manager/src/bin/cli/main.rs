@@ -130,6 +130,28 @@ pub enum SubCmd {
         /// If the argument is not used, the default format will be applied.
         #[clap(long, value_parser = LogFormat::parse_from_str, verbatim_doc_comment)]
         log_format: Option<LogFormat>,
+        /// Specify the maximum number of connections each node can establish.
+        ///
+        /// Connections beyond the limit are denied. If not set, the connections are not capped.
+        #[clap(long)]
+        max_connections: Option<u32>,
+        /// Specify the maximum bytes per second each node can receive.
+        ///
+        /// If not set, the download bandwidth of the nodes is not capped.
+        #[clap(long)]
+        max_download_bytes_per_sec: Option<u64>,
+        /// Specify the maximum disk space, in bytes, the records stored by each node can take.
+        ///
+        /// When the limit is reached, the node prunes the records farthest from it.
+        ///
+        /// If not set, each node stores up to a fixed number of records instead.
+        #[clap(long)]
+        max_storage_bytes: Option<u64>,
+        /// Specify the maximum bytes per second each node can send.
+        ///
+        /// If not set, the upload bandwidth of the nodes is not capped.
+        #[clap(long)]
+        max_upload_bytes_per_sec: Option<u64>,
         /// Specify a port for the open metrics server.
         ///
         /// This argument should only be used with a safenode binary that has the open-metrics
@@ -184,6 +206,16 @@ pub enum SubCmd {
         /// services, which in this case would be 5. The range must also go from lower to higher.
         #[clap(long, value_parser = parse_port_range)]
         rpc_port: Option<PortRange>,
+        /// Specify the transports the node service(s) listen on, all of them on the node port.
+        ///
+        /// Valid values are "quic", "tcp" or "websocket". The argument can be repeated, or take a
+        /// comma-separated list, e.g. '--transport quic,tcp'.
+        ///
+        /// "tcp" and "websocket" can't be used together unless the node port is chosen at random.
+        ///
+        /// If not used, the nodes listen on the default transports of the safenode binary.
+        #[clap(name = "transport", long, value_delimiter = ',', value_parser = ["quic", "tcp", "websocket"])]
+        transports: Vec<String>,
         /// Try to use UPnP to open a port in the home router and allow incoming connections.
         ///
         /// This requires a safenode binary built with the 'upnp' feature.
@@ -905,6 +937,10 @@ async fn main() -> Result<()> {
             local,
             log_dir_path,
             log_format,
+            max_connections,
+            max_download_bytes_per_sec,
+            max_storage_bytes,
+            max_upload_bytes_per_sec,
             metrics_port,
             node_port,
             owner,
@@ -912,6 +948,7 @@ async fn main() -> Result<()> {
             peers,
             rpc_address,
             rpc_port,
+            transports,
             url,
             upnp,
             user,
@@ -928,6 +965,10 @@ async fn main() -> Result<()> {
                 local,
                 log_dir_path,
                 log_format,
+                max_connections,
+                max_download_bytes_per_sec,
+                max_storage_bytes,
+                max_upload_bytes_per_sec,
                 metrics_port,
                 node_port,
                 owner,
@@ -935,6 +976,7 @@ async fn main() -> Result<()> {
                 rpc_address,
                 rpc_port,
                 path,
+                transports,
                 upnp,
                 url,
                 user,
@@ -46,6 +46,10 @@ pub async fn add(
     local: bool,
     log_dir_path: Option<PathBuf>,
     log_format: Option<LogFormat>,
+    max_connections: Option<u32>,
+    max_download_bytes_per_sec: Option<u64>,
+    max_storage_bytes: Option<u64>,
+    max_upload_bytes_per_sec: Option<u64>,
     metrics_port: Option<PortRange>,
     node_port: Option<PortRange>,
     owner: Option<String>,
@@ -53,6 +57,7 @@ pub async fn add(
     rpc_address: Option<Ipv4Addr>,
     rpc_port: Option<PortRange>,
     src_path: Option<PathBuf>,
+    transports: Vec<String>,
     upnp: bool,
     url: Option<String>,
     user: Option<String>,
@@ -142,8 +147,13 @@ pub async fn add(
         env_variables,
         genesis: is_first,
         home_network,
+        listen_transports: transports,
         local,
         log_format,
+        max_connections,
+        max_download_bytes_per_sec,
+        max_storage_bytes,
+        max_upload_bytes_per_sec,
         metrics_port,
         owner,
         node_port,
@@ -569,6 +579,10 @@ pub async fn maintain_n_running_nodes(
     local: bool,
     log_dir_path: Option<PathBuf>,
     log_format: Option<LogFormat>,
+    max_connections: Option<u32>,
+    max_download_bytes_per_sec: Option<u64>,
+    max_storage_bytes: Option<u64>,
+    max_upload_bytes_per_sec: Option<u64>,
     metrics_port: Option<PortRange>,
     node_port: Option<PortRange>,
     owner: Option<String>,
@@ -576,6 +590,7 @@ pub async fn maintain_n_running_nodes(
     rpc_address: Option<Ipv4Addr>,
     rpc_port: Option<PortRange>,
     src_path: Option<PathBuf>,
+    transports: Vec<String>,
     url: Option<String>,
     upnp: bool,
     user: Option<String>,
@@ -662,6 +677,10 @@ pub async fn maintain_n_running_nodes(
                     local,
                     log_dir_path,
                     log_format,
+                    max_connections,
+                    max_download_bytes_per_sec,
+                    max_storage_bytes,
+                    max_upload_bytes_per_sec,
                     metrics_port,
                     node_port,
                     owner,
@@ -669,6 +688,7 @@ pub async fn maintain_n_running_nodes(
                     rpc_address,
                     rpc_port,
                     src_path,
+                    transports,
                     upnp,
                     url,
                     user,
@@ -633,7 +633,7 @@ mod tests {
     use sn_service_management::{
         error::{Error as ServiceControlError, Result as ServiceControlResult},
         node::{NodeService, NodeServiceData},
-        rpc::{NetworkInfo, NodeInfo, RecordAddress, RpcActions},
+        rpc::{BadNode, NetworkInfo, NodeInfo, RecordAddress, RoutingHealth, RpcActions},
         UpgradeOptions, UpgradeResult,
     };
     use sn_transfers::NanoTokens;
@@ -651,6 +651,8 @@ mod tests {
             async fn node_info(&self) -> ServiceControlResult<NodeInfo>;
             async fn network_info(&self) -> ServiceControlResult<NetworkInfo>;
             async fn record_addresses(&self) -> ServiceControlResult<Vec<RecordAddress>>;
+            async fn bad_nodes(&self) -> ServiceControlResult<Vec<BadNode>>;
+            async fn routing_health(&self) -> ServiceControlResult<RoutingHealth>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> ServiceControlResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> ServiceControlResult<()>;
             async fn node_update(&self, delay_millis: u64) -> ServiceControlResult<()>;
@@ -703,6 +705,11 @@ mod tests {
                 version: "0.98.1".to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -724,9 +731,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -804,6 +816,11 @@ mod tests {
                 version: "0.98.1".to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -823,9 +840,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -888,9 +910,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -976,6 +1003,11 @@ mod tests {
                 version: "0.98.1".to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -995,9 +1027,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1073,9 +1110,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1142,6 +1184,11 @@ mod tests {
                 version: "0.98.1".to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -1161,9 +1208,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1216,9 +1268,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1264,9 +1321,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1310,9 +1372,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1359,9 +1426,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1421,9 +1493,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1526,6 +1603,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -1545,9 +1627,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1632,9 +1719,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1743,6 +1835,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -1762,9 +1859,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -1886,6 +1988,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -1905,9 +2012,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2043,9 +2155,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2163,6 +2280,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2182,9 +2304,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2331,6 +2458,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2350,9 +2482,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2482,6 +2619,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2501,9 +2643,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: Some(LogFormat::Json),
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             owner: None,
@@ -2636,6 +2783,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2655,9 +2807,14 @@ mod tests {
             genesis: false,
             home_network: true,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -2787,6 +2944,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2806,9 +2968,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             number: 1,
             node_port: Some(12000),
@@ -2938,6 +3105,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -2957,9 +3129,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(12000),
             node_port: None,
             number: 1,
@@ -3092,6 +3269,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -3111,9 +3293,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: Some(12000),
             node_port: None,
             number: 1,
@@ -3246,6 +3433,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -3265,9 +3457,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3400,6 +3597,11 @@ mod tests {
                 version: target_version.to_string(),
                 uptime: std::time::Duration::from_secs(1), // the service was just started
                 wallet_balance: 0,
+                connections: 0,
+                bytes_sent: 0,
+                bytes_received: 0,
+                upload_rate: 0,
+                download_rate: 0,
             })
         });
         mock_rpc_client
@@ -3419,9 +3621,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3488,9 +3695,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3544,9 +3756,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3616,9 +3833,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: PathBuf::from("/var/log/safenode/safenode1"),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3680,9 +3902,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
@@ -3742,9 +3969,14 @@ mod tests {
             genesis: false,
             home_network: false,
             listen_addr: None,
+            listen_transports: vec![],
             local: false,
             log_dir_path: log_dir.to_path_buf(),
             log_format: None,
+            max_connections: None,
+            max_download_bytes_per_sec: None,
+            max_storage_bytes: None,
+            max_upload_bytes_per_sec: None,
             metrics_port: None,
             node_port: None,
             number: 1,
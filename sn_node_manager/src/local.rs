@@ -352,9 +352,14 @@ pub async fn run_node(
         genesis: run_options.genesis,
         home_network: false,
         listen_addr: Some(listen_addrs),
+        listen_transports: vec![],
         local: true,
         log_dir_path: node_info.log_path,
         log_format: run_options.log_format,
+        max_connections: None,
+        max_download_bytes_per_sec: None,
+        max_storage_bytes: None,
+        max_upload_bytes_per_sec: None,
         metrics_port: None,
         node_port: None,
         number: run_options.number,
@@ -444,7 +449,7 @@ mod tests {
     use mockall::predicate::*;
     use sn_service_management::{
         error::Result as RpcResult,
-        rpc::{NetworkInfo, NodeInfo, RecordAddress, RpcActions},
+        rpc::{BadNode, NetworkInfo, NodeInfo, RecordAddress, RoutingHealth, RpcActions},
     };
     use std::str::FromStr;
 
@@ -455,6 +460,8 @@ mod tests {
             async fn node_info(&self) -> RpcResult<NodeInfo>;
             async fn network_info(&self) -> RpcResult<NetworkInfo>;
             async fn record_addresses(&self) -> RpcResult<Vec<RecordAddress>>;
+            async fn bad_nodes(&self) -> RpcResult<Vec<BadNode>>;
+            async fn routing_health(&self) -> RpcResult<RoutingHealth>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> RpcResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> RpcResult<()>;
             async fn node_update(&self, delay_millis: u64) -> RpcResult<()>;
@@ -496,6 +503,11 @@ mod tests {
                     version: "0.100.12".to_string(),
                     uptime: std::time::Duration::from_secs(1), // the service was just started
                     wallet_balance: 0,
+                    connections: 0,
+                    bytes_sent: 0,
+                    bytes_received: 0,
+                    upload_rate: 0,
+                    download_rate: 0,
                 })
             });
         mock_rpc_client
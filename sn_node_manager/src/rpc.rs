@@ -68,9 +68,14 @@ pub async fn restart_node_service(
             env_variables: node_registry.environment_variables.clone(),
             genesis: current_node_clone.genesis,
             home_network: current_node_clone.home_network,
+            listen_transports: current_node_clone.listen_transports.clone(),
             local: current_node_clone.local,
             log_dir_path: current_node_clone.log_dir_path.clone(),
             log_format: current_node_clone.log_format,
+            max_connections: current_node_clone.max_connections,
+            max_download_bytes_per_sec: current_node_clone.max_download_bytes_per_sec,
+            max_storage_bytes: current_node_clone.max_storage_bytes,
+            max_upload_bytes_per_sec: current_node_clone.max_upload_bytes_per_sec,
             metrics_port: None,
             owner: current_node_clone.owner.clone(),
             name: current_node_clone.service_name.clone(),
@@ -183,9 +188,14 @@ pub async fn restart_node_service(
             env_variables: node_registry.environment_variables.clone(),
             genesis: current_node_clone.genesis,
             home_network: current_node_clone.home_network,
+            listen_transports: current_node_clone.listen_transports.clone(),
             local: current_node_clone.local,
             log_dir_path: log_dir_path.clone(),
             log_format: current_node_clone.log_format,
+            max_connections: current_node_clone.max_connections,
+            max_download_bytes_per_sec: current_node_clone.max_download_bytes_per_sec,
+            max_storage_bytes: current_node_clone.max_storage_bytes,
+            max_upload_bytes_per_sec: current_node_clone.max_upload_bytes_per_sec,
             name: new_service_name.clone(),
             metrics_port: None,
             node_port: None,
@@ -207,9 +217,14 @@ pub async fn restart_node_service(
             genesis: current_node_clone.genesis,
             home_network: current_node_clone.home_network,
             listen_addr: None,
+            listen_transports: current_node_clone.listen_transports.clone(),
             local: current_node_clone.local,
             log_dir_path,
             log_format: current_node_clone.log_format,
+            max_connections: current_node_clone.max_connections,
+            max_download_bytes_per_sec: current_node_clone.max_download_bytes_per_sec,
+            max_storage_bytes: current_node_clone.max_storage_bytes,
+            max_upload_bytes_per_sec: current_node_clone.max_upload_bytes_per_sec,
             metrics_port: None,
             node_port: None,
             number: new_node_number as u16,
@@ -17,7 +17,10 @@ use sn_protocol::safenode_proto::{safe_node_client::SafeNodeClient, NodeEventsRe
 
 use sn_service_management::rpc::{RpcActions, RpcClient};
 
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    net::SocketAddr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tokio_stream::StreamExt;
 use tonic::Request;
 
@@ -39,6 +42,13 @@ enum Cmd {
     /// Retrieve information about the node's connections to the network
     #[clap(name = "netinfo")]
     Netinfo,
+    /// Retrieve the peers the node has recorded issues against, and whether they are considered as bad
+    #[clap(name = "badnodes")]
+    BadNodes,
+    /// Retrieve the fill of the node's routing table buckets, and other indicators of how well
+    /// connected it is
+    #[clap(name = "routing")]
+    RoutingHealth,
     /// Start listening for node events.
     /// Note this blocks the app and it will print events as they are broadcasted by the node
     #[clap(name = "events")]
@@ -95,6 +105,8 @@ async fn main() -> Result<()> {
     match opt.cmd {
         Cmd::Info => node_info(addr).await,
         Cmd::Netinfo => network_info(addr).await,
+        Cmd::BadNodes => bad_nodes(addr).await,
+        Cmd::RoutingHealth => routing_health(addr).await,
         Cmd::Events => node_events(addr).await,
         Cmd::Restart {
             delay_millis,
@@ -119,6 +131,15 @@ pub async fn node_info(addr: SocketAddr) -> Result<()> {
     println!("PID: {}", node_info.pid);
     println!("Binary version: {}", node_info.version);
     println!("Time since last restart: {:?}", node_info.uptime);
+    println!("Connections: {}", node_info.connections);
+    println!(
+        "Bytes sent/received: {}/{}",
+        node_info.bytes_sent, node_info.bytes_received
+    );
+    println!(
+        "Upload/download rate: {}/{} bytes/s",
+        node_info.upload_rate, node_info.download_rate
+    );
 
     Ok(())
 }
@@ -144,6 +165,82 @@ pub async fn network_info(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
+pub async fn bad_nodes(addr: SocketAddr) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let bad_nodes = client.bad_nodes().await?;
+
+    println!("Peers with recorded issues: {}", bad_nodes.len());
+    for bad_node in bad_nodes.iter() {
+        println!();
+        println!("Peer: {}", bad_node.peer_id);
+        println!("Considered as bad: {}", bad_node.is_bad);
+        for (issue, timestamp) in bad_node.issues.iter() {
+            let secs = timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            println!("Issue: {issue} (recorded at {secs}s since UNIX epoch)");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn routing_health(addr: SocketAddr) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let routing_health = client.routing_health().await?;
+
+    let total_peers: u32 = routing_health
+        .buckets
+        .iter()
+        .map(|bucket| bucket.num_peers)
+        .sum();
+    let relayed_peers: u32 = routing_health
+        .buckets
+        .iter()
+        .map(|bucket| bucket.relayed_peers)
+        .sum();
+    println!("Routing table health:");
+    println!(
+        "Peers: {total_peers} ({} direct, {relayed_peers} relayed)",
+        total_peers - relayed_peers
+    );
+    println!(
+        "Estimated network size: {}",
+        routing_health.estimated_network_size
+    );
+    match routing_health.responsible_distance_range {
+        Some(distance) => println!("Responsible distance range: {distance}"),
+        None => println!("Responsible distance range: not set yet"),
+    }
+    if total_peers == 0 {
+        println!("The routing table is empty, the node is isolated from the network");
+    }
+
+    println!();
+    println!("Buckets by ilog2 distance:");
+    for bucket in routing_health.buckets.iter() {
+        let last_query = match bucket.last_successful_query {
+            Some(time) => format!(
+                "{}s ago",
+                SystemTime::now()
+                    .duration_since(time)
+                    .unwrap_or_default()
+                    .as_secs()
+            ),
+            None => "never".to_string(),
+        };
+        println!(
+            "{}: {}/{} peers ({} relayed), last successful query {last_query}",
+            bucket.distance, bucket.num_peers, bucket.capacity, bucket.relayed_peers
+        );
+    }
+
+    Ok(())
+}
+
 pub async fn node_events(addr: SocketAddr) -> Result<()> {
     let endpoint = format!("https://{addr}");
     let mut client = SafeNodeClient::connect(endpoint).await?;
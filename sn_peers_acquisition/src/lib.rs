@@ -100,6 +100,36 @@ impl PeersArgs {
         self.get_peers_inner(true).await
     }
 
+    /// Gets the peers based on the arguments provided, merged with the `cached_peers` a node
+    /// learnt during a previous run.
+    ///
+    /// If the `--first` flag is used, no peers will be provided.
+    ///
+    /// The cached peers are used on their own when no peers could be obtained otherwise, e.g. when
+    /// the network contacts cannot be fetched.
+    pub async fn get_peers_or_cached(self, cached_peers: Vec<Multiaddr>) -> Result<Vec<Multiaddr>> {
+        if self.first {
+            return self.get_peers().await;
+        }
+
+        match self.get_peers().await {
+            Ok(mut peers) => {
+                info!("Merging {} cached peer addresses", cached_peers.len());
+                for addr in cached_peers {
+                    if !peers.contains(&addr) {
+                        peers.push(addr);
+                    }
+                }
+                Ok(peers)
+            }
+            Err(err) if !cached_peers.is_empty() => {
+                warn!("Failed to obtain peers: {err:?}. Rejoining through the cached peers.");
+                Ok(cached_peers)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     async fn get_peers_inner(self, skip_network_contacts: bool) -> Result<Vec<Multiaddr>> {
         if self.first {
             info!("First node in a new network");
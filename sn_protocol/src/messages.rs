@@ -12,6 +12,7 @@ mod cmd;
 mod node_id;
 mod query;
 mod register;
+mod replication_digest;
 mod response;
 
 pub use self::{
@@ -20,6 +21,7 @@ pub use self::{
     node_id::NodeId,
     query::Query,
     register::RegisterCmd,
+    replication_digest::{DigestBucket, ReplicationDigest, MAX_DIGEST_DEPTH},
     response::{CmdResponse, QueryResponse},
 };
 
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 #![allow(clippy::mutable_key_type)] // for Bytes in NetworkAddress
 
+use super::ReplicationDigest;
 use crate::{storage::RecordType, NetworkAddress};
 use serde::{Deserialize, Serialize};
 // TODO: remove this dependency and define these types herein.
@@ -30,6 +31,21 @@ pub enum Cmd {
         /// Keys of copy that shall be replicated.
         keys: Vec<(NetworkAddress, RecordType)>,
     },
+    /// Write operation to notify peer of the records held by the holder, summarised as a digest.
+    ///
+    /// The peer responds with the buckets of the digest it holds differently. The holder then
+    /// sends the keys within the small ones as a [`Cmd::Replicate`], and splits the large ones
+    /// further with another digest.
+    /// The peers not supporting it are sent a [`Cmd::Replicate`] of all the keys instead.
+    ReplicateDigest {
+        /// Holder of the replication keys.
+        holder: NetworkAddress,
+        /// The digest only covers the keys within this ilog2 distance of the peer,
+        /// all the keys if None.
+        distance_range: Option<u32>,
+        /// Digest of the keys that shall be replicated.
+        digest: ReplicationDigest,
+    },
     /// Write operation to notify nodes a list of PaymentQuote collected.
     QuoteVerification {
         target: NetworkAddress,
@@ -54,6 +70,16 @@ impl std::fmt::Debug for Cmd {
                     .field("first_ten_keys", &first_ten_keys)
                     .finish()
             }
+            Cmd::ReplicateDigest {
+                holder,
+                distance_range,
+                digest,
+            } => f
+                .debug_struct("Cmd::ReplicateDigest")
+                .field("holder", holder)
+                .field("distance_range", distance_range)
+                .field("digest", digest)
+                .finish(),
             Cmd::QuoteVerification { target, quotes } => f
                 .debug_struct("Cmd::QuoteVerification")
                 .field("target", target)
@@ -77,7 +103,7 @@ impl Cmd {
     /// Used to send a cmd to the close group of the address.
     pub fn dst(&self) -> NetworkAddress {
         match self {
-            Cmd::Replicate { holder, .. } => holder.clone(),
+            Cmd::Replicate { holder, .. } | Cmd::ReplicateDigest { holder, .. } => holder.clone(),
             Cmd::QuoteVerification { target, .. } => target.clone(),
             Cmd::PeerConsideredAsBad { bad_peer, .. } => bad_peer.clone(),
         }
@@ -95,6 +121,13 @@ impl std::fmt::Display for Cmd {
                     keys.len()
                 )
             }
+            Cmd::ReplicateDigest { holder, digest, .. } => {
+                write!(
+                    f,
+                    "Cmd::ReplicateDigest({:?} has {digest:?})",
+                    holder.as_peer_id()
+                )
+            }
             Cmd::QuoteVerification { target, quotes } => {
                 write!(
                     f,
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use sn_registers::{Register, RegisterAddress, RegisterOp};
+use sn_registers::{PermissionOp, Register, RegisterAddress, RegisterOp};
 
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +23,8 @@ pub enum RegisterCmd {
     },
     /// Edit the register
     Edit(RegisterOp),
+    /// Change the permissions of the register
+    ChangePermissions(PermissionOp),
 }
 
 /// Custom debug implementation to avoid printing the whole register
@@ -34,6 +36,9 @@ impl std::fmt::Debug for RegisterCmd {
                 write!(f, "RegisterCmd::Create({:?})", register.address())
             }
             RegisterCmd::Edit(op) => write!(f, "RegisterCmd::Edit({:?})", op.address()),
+            RegisterCmd::ChangePermissions(op) => {
+                write!(f, "RegisterCmd::ChangePermissions({:?})", op.address())
+            }
         }
     }
 }
@@ -44,6 +49,7 @@ impl RegisterCmd {
         match self {
             Self::Create { register, .. } => *register.address(),
             Self::Edit(op) => op.address(),
+            Self::ChangePermissions(op) => op.address(),
         }
     }
 }
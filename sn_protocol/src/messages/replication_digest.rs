@@ -0,0 +1,160 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+#![allow(clippy::mutable_key_type)] // for Bytes in NetworkAddress
+
+use crate::{storage::RecordType, NetworkAddress};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// A bucket of a digest, i.e. the leading bytes of the hashes of the keys within it.
+/// The empty bucket holds all the keys.
+pub type DigestBucket = Vec<u8>;
+
+/// The buckets are split down to this many leading bytes at most.
+pub const MAX_DIGEST_DEPTH: usize = 4;
+
+/// A summary of the records held by a peer, to find out the ones another peer holds differently
+/// without exchanging the whole list of keys.
+///
+/// The digest covers the keys within its scope buckets, each of them split into 256 buckets by
+/// the next byte of the hash of the keys. Each bucket is summarised by combining the hashes of the
+/// records within it. The buckets held differently can then be split further by another digest,
+/// rather than exchanging all their keys.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReplicationDigest {
+    /// The buckets split by this digest, all of the same depth
+    scope: Vec<DigestBucket>,
+    /// The hash of each bucket holding records
+    buckets: BTreeMap<DigestBucket, [u8; 32]>,
+}
+
+impl ReplicationDigest {
+    /// A digest of all the keys, split by the leading byte of their hash.
+    pub fn new<'a>(keys: impl IntoIterator<Item = &'a (NetworkAddress, RecordType)>) -> Self {
+        Self::within(keys, vec![DigestBucket::new()])
+    }
+
+    /// A digest of the keys within the scope buckets, splitting each of them by the next byte.
+    pub fn within<'a>(
+        keys: impl IntoIterator<Item = &'a (NetworkAddress, RecordType)>,
+        scope: Vec<DigestBucket>,
+    ) -> Self {
+        let depth = scope.first().map_or(0, Vec::len).min(MAX_DIGEST_DEPTH - 1);
+        let scope_set: BTreeSet<&[u8]> = scope.iter().map(Vec::as_slice).collect();
+        let mut buckets: BTreeMap<DigestBucket, [u8; 32]> = BTreeMap::new();
+        for (addr, record_type) in keys {
+            let key_hash = key_hash(addr);
+            if !scope_set.contains(&key_hash[..depth]) {
+                continue;
+            }
+            // XOR-ing the hashes makes the digest independent of the order of the keys
+            let bucket_hash = buckets.entry(key_hash[..=depth].to_vec()).or_default();
+            for (byte, key_byte) in bucket_hash.iter_mut().zip(record_hash(addr, record_type)) {
+                *byte ^= key_byte;
+            }
+        }
+        Self { scope, buckets }
+    }
+
+    /// The buckets split by this digest.
+    pub fn scope(&self) -> &[DigestBucket] {
+        &self.scope
+    }
+
+    /// The bucket of the provided depth the address falls in, the different versions of a record
+    /// falling in the same.
+    pub fn bucket_of(addr: &NetworkAddress, depth: usize) -> DigestBucket {
+        key_hash(addr)[..depth.min(MAX_DIGEST_DEPTH)].to_vec()
+    }
+
+    /// The buckets holding different records in the two digests.
+    pub fn differing_buckets(&self, other: &ReplicationDigest) -> Vec<DigestBucket> {
+        let all_buckets: BTreeSet<_> = self.buckets.keys().chain(other.buckets.keys()).collect();
+        all_buckets
+            .into_iter()
+            .filter(|bucket| self.buckets.get(*bucket) != other.buckets.get(*bucket))
+            .cloned()
+            .collect()
+    }
+}
+
+fn key_hash(addr: &NetworkAddress) -> [u8; 32] {
+    sha3_256(&[&addr.as_bytes()])
+}
+
+/// The hash of a record, accounting for its content for the records that can be updated.
+fn record_hash(addr: &NetworkAddress, record_type: &RecordType) -> [u8; 32] {
+    match record_type {
+        RecordType::Chunk => key_hash(addr),
+        RecordType::NonChunk(content_hash) => sha3_256(&[&addr.as_bytes(), &content_hash.0]),
+    }
+}
+
+fn sha3_256(inputs: &[&[u8]]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Sha3};
+
+    let mut sha3 = Sha3::v256();
+    let mut output = [0; 32];
+    for input in inputs {
+        sha3.update(input);
+    }
+    sha3.finalize(&mut output);
+    output
+}
+
+impl fmt::Debug for ReplicationDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicationDigest")
+            .field("scope_len", &self.scope.len())
+            .field("buckets_len", &self.buckets.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::rand::thread_rng;
+    use libp2p::kad::RecordKey;
+    use xor_name::XorName;
+
+    #[test]
+    fn digests_only_differ_in_the_buckets_of_the_differing_keys() {
+        let mut rng = thread_rng();
+        let keys: Vec<_> = (0..100)
+            .map(|_| {
+                let key = RecordKey::new(&XorName::random(&mut rng));
+                (NetworkAddress::from_record_key(&key), RecordType::Chunk)
+            })
+            .collect();
+        let digest = ReplicationDigest::new(&keys);
+        let reversed_digest = ReplicationDigest::new(keys.iter().rev());
+        assert_eq!(digest, reversed_digest);
+        assert!(digest.differing_buckets(&reversed_digest).is_empty());
+
+        let (missing_addr, _) = &keys[0];
+        let partial_digest = ReplicationDigest::new(&keys[1..]);
+        let differing_buckets = digest.differing_buckets(&partial_digest);
+        assert_eq!(
+            differing_buckets,
+            vec![ReplicationDigest::bucket_of(missing_addr, 1)]
+        );
+
+        // splitting the differing bucket narrows it down further
+        let refined_digest = ReplicationDigest::within(&keys, differing_buckets);
+        let refined_partial_digest =
+            ReplicationDigest::within(&keys[1..], refined_digest.scope().to_vec());
+        assert_eq!(
+            refined_digest.differing_buckets(&refined_partial_digest),
+            vec![ReplicationDigest::bucket_of(missing_addr, 2)]
+        );
+    }
+}
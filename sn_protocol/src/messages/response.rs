@@ -8,7 +8,7 @@
 
 use crate::{error::Result, NetworkAddress};
 
-use super::ChunkProof;
+use super::{ChunkProof, DigestBucket};
 use bytes::Bytes;
 use core::fmt;
 use serde::{Deserialize, Serialize};
@@ -106,6 +106,8 @@ pub enum CmdResponse {
     //
     /// Response to replication cmd
     Replicate(Result<()>),
+    /// Response to replication digest cmd, with the buckets of the digest held differently
+    ReplicateDigest(Result<Vec<DigestBucket>>),
     //
     // ===== QuoteVerification =====
     //
@@ -16,6 +16,9 @@ use std::{
 };
 use xor_name::{XorName, XOR_NAME_LEN};
 
+/// Appended to the meta of a register to derive the meta of its continuation.
+const CONTINUATION_DOMAIN: &[u8] = b"register-continuation";
+
 /// Address of a Register on the SAFE Network
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct RegisterAddress {
@@ -89,6 +92,19 @@ impl RegisterAddress {
     pub fn owner(&self) -> PublicKey {
         self.owner
     }
+
+    /// Return the address of the register continuing this one once it is full, created by `owner`.
+    /// Its meta is derived from this register's meta only, so it is the same for all the
+    /// continuations of this register whoever created them.
+    pub fn continuation(&self, owner: PublicKey) -> Self {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.meta.0);
+        bytes.extend_from_slice(CONTINUATION_DOMAIN);
+        Self {
+            meta: XorName::from_content(&bytes),
+            owner,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +36,25 @@ pub enum Error {
     /// Access denied for user
     #[error("Access denied for user: {0:?}")]
     AccessDenied(PublicKey),
+    /// The owner of the Register cannot revoke their own write permission
+    #[error("The owner of the Register cannot be removed from its writers: {0:?}")]
+    CannotRemoveOwner(PublicKey),
+    /// The op was written atop of permission ops which are not known yet
+    #[error("The op was written after {version} permission ops, only {known} are known")]
+    MissingPermissionOps {
+        /// Number of permission ops the op was written after
+        version: u64,
+        /// Number of permission ops known
+        known: u64,
+    },
+    /// Another permission op was already applied at this version of the permissions
+    #[error("Another permission op was already applied at version {0} of the permissions")]
+    PermissionVersionTaken(u64),
+    /// The Register op claims older permissions than the entries it was written atop of
+    #[error(
+        "Register op of {0:?} claims older permissions than the entries it was written atop of"
+    )]
+    StalePermissionsVersion(PublicKey),
     /// Cannot add another entry since the register entry cap has been reached.
     #[error("Cannot add another entry since the register entry cap has been reached: {0}")]
     TooManyEntries(usize),
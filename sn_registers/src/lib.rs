@@ -9,6 +9,7 @@
 mod address;
 pub(crate) mod error;
 mod metadata;
+mod permission_op;
 mod permissions;
 pub(crate) mod reg_crdt;
 pub(crate) mod register;
@@ -18,7 +19,8 @@ pub use self::{
     address::RegisterAddress,
     error::Error,
     metadata::{Entry, EntryHash},
+    permission_op::{PermissionChange, PermissionOp},
     permissions::Permissions,
-    register::{Register, SignedRegister},
+    register::{Register, SignedRegister, MAX_REG_ENTRY_SIZE, MAX_REG_NUM_ENTRIES},
     register_op::RegisterOp,
 };
@@ -0,0 +1,130 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{error::Result, Error, RegisterAddress};
+
+use bls::{PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+use xor_name::XorName;
+
+/// A change to the permissions of a Register.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PermissionChange {
+    /// Allow the user to write to the Register
+    AddWriter(PublicKey),
+    /// Revoke the write permission of the user, the current owner cannot be removed
+    RemoveWriter(PublicKey),
+    /// Make the user the owner of the Register, i.e. the one allowed to change its permissions.
+    /// The new owner is also allowed to write, while the address of the Register is left unchanged.
+    TransferOwnership(PublicKey),
+}
+
+/// Register permissions operation to apply to Register.
+/// The permission ops of a Register form a chain: the op of version `n` applies atop of the
+/// `n` ops before it, refers to the hash of the previous one and shall be signed by the owner
+/// of the Register at that point. Only one op can take each version.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PermissionOp {
+    /// Address of a Register object on the network.
+    pub(crate) address: RegisterAddress,
+    /// The number of permission ops applied before this one.
+    pub(crate) version: u64,
+    /// The hash of the permission op this one applies atop of, none for the first one.
+    pub(crate) previous: Option<XorName>,
+    /// The change to apply to the permissions.
+    pub(crate) change: PermissionChange,
+    /// The PublicKey of the entity that generated the operation
+    pub(crate) source: PublicKey,
+    /// The signature of source on hash(address, version, previous, change, source)
+    /// required to apply the op
+    pub(crate) signature: bls::Signature,
+}
+
+impl PermissionOp {
+    /// Create a new PermissionOp
+    pub(crate) fn new(
+        address: RegisterAddress,
+        version: u64,
+        previous: Option<XorName>,
+        change: PermissionChange,
+        signer: &SecretKey,
+    ) -> Result<Self> {
+        let source = signer.public_key();
+        let signature = signer.sign(Self::bytes_for_signing(
+            &address, version, previous, &change, &source,
+        )?);
+        Ok(Self {
+            address,
+            version,
+            previous,
+            change,
+            source,
+            signature,
+        })
+    }
+
+    /// address of the register this op is destined for
+    pub fn address(&self) -> RegisterAddress {
+        self.address
+    }
+
+    /// the number of permission ops applied before this one
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// the hash of the permission op this one applies atop of
+    pub fn previous(&self) -> Option<XorName> {
+        self.previous
+    }
+
+    /// the change to the permissions
+    pub fn change(&self) -> &PermissionChange {
+        &self.change
+    }
+
+    /// the entity that generated the operation
+    pub fn source(&self) -> PublicKey {
+        self.source
+    }
+
+    /// Check signature of permission Op against provided public key
+    pub fn verify_signature(&self, pk: &PublicKey) -> Result<()> {
+        let bytes = Self::bytes_for_signing(
+            &self.address,
+            self.version,
+            self.previous,
+            &self.change,
+            &self.source,
+        )?;
+        if !pk.verify(&self.signature, bytes) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Returns the hash of the op, signature included, which the next op of the chain refers to.
+    pub fn hash(&self) -> Result<XorName> {
+        let bytes = rmp_serde::to_vec(self).map_err(|_| Error::SerialisationFailed)?;
+        Ok(XorName::from_content(&bytes))
+    }
+
+    /// Returns a bytes version of the PermissionOp used for signing,
+    /// i.e. the sha3 digest of the serialised op without its signature.
+    fn bytes_for_signing(
+        address: &RegisterAddress,
+        version: u64,
+        previous: Option<XorName>,
+        change: &PermissionChange,
+        source: &PublicKey,
+    ) -> Result<Vec<u8>> {
+        let bytes = rmp_serde::to_vec(&(address, version, previous, change, source))
+            .map_err(|_| Error::SerialisationFailed)?;
+        Ok(XorName::from_content(&bytes).0.to_vec())
+    }
+}
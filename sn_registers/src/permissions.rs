@@ -58,4 +58,11 @@ impl Permissions {
             writers.insert(user);
         }
     }
+
+    /// If this is restricted to a set of users, remove a user from the list of users that can write to this Register
+    pub fn remove_writer(&mut self, user: &PublicKey) {
+        if let Self::Writers(writers) = self {
+            writers.remove(user);
+        }
+    }
 }
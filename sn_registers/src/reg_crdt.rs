@@ -8,7 +8,7 @@
 
 use crate::{error::Result, Entry, EntryHash, Error, RegisterAddress, RegisterOp};
 
-use crdts::merkle_reg::Node as MerkleDagEntry;
+use crdts::merkle_reg::{Hash as MerkleHash, Node as MerkleDagEntry};
 use crdts::{merkle_reg::MerkleReg, CmRDT, CvRDT};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -122,6 +122,103 @@ impl RegisterCrdt {
             .collect()
     }
 
+    /// Returns true if the entry is part of the register.
+    pub(crate) fn contains(&self, hash: &EntryHash) -> bool {
+        self.data.node(hash.0).is_some()
+    }
+
+    /// Returns all the entries, ordered from the roots to the current entries,
+    /// an entry always coming after the entries it was written atop of.
+    pub(crate) fn history(&self) -> Vec<(EntryHash, Entry)> {
+        let heads = self
+            .data
+            .read()
+            .hashes_and_nodes()
+            .map(|(hash, _)| hash)
+            .collect();
+        self.topologically_ordered(&self.reachable_from(heads))
+    }
+
+    /// Returns the entries the provided entry was written atop of, directly or not,
+    /// ordered from the roots.
+    pub(crate) fn ancestors(&self, hash: &EntryHash) -> Vec<(EntryHash, Entry)> {
+        let mut ancestors = self.reachable_from([hash.0].into_iter().collect());
+        let _ = ancestors.remove(&hash.0);
+        self.topologically_ordered(&ancestors)
+    }
+
+    /// Returns the entries written atop of the provided entry, directly or not,
+    /// ordered from the closest to the provided entry.
+    pub(crate) fn descendants(&self, hash: &EntryHash) -> Vec<(EntryHash, Entry)> {
+        let mut descendants = BTreeSet::new();
+        let mut to_visit = vec![hash.0];
+        while let Some(current) = to_visit.pop() {
+            for (parent, _) in self.data.parents(current).hashes_and_nodes() {
+                if descendants.insert(parent) {
+                    to_visit.push(parent);
+                }
+            }
+        }
+        self.topologically_ordered(&descendants)
+    }
+
+    /// Returns the entries known from the `to` heads but not from the `from` heads,
+    /// ordered from the roots.
+    pub(crate) fn diff(
+        &self,
+        from: &BTreeSet<EntryHash>,
+        to: &BTreeSet<EntryHash>,
+    ) -> Vec<(EntryHash, Entry)> {
+        let known_from = self.reachable_from(from.iter().map(|hash| hash.0).collect());
+        let known_to = self.reachable_from(to.iter().map(|hash| hash.0).collect());
+        self.topologically_ordered(&known_to.difference(&known_from).copied().collect())
+    }
+
+    /// Returns the provided entries and all the entries they were written atop of.
+    fn reachable_from(&self, hashes: BTreeSet<MerkleHash>) -> BTreeSet<MerkleHash> {
+        let mut reachable = BTreeSet::new();
+        let mut to_visit: Vec<_> = hashes.into_iter().collect();
+        while let Some(current) = to_visit.pop() {
+            let Some(node) = self.data.node(current) else {
+                continue;
+            };
+            if reachable.insert(current) {
+                to_visit.extend(node.children.iter().copied());
+            }
+        }
+        reachable
+    }
+
+    /// Orders the provided entries so that an entry comes after its children.
+    /// Children outside of the provided entries are ignored.
+    fn topologically_ordered(&self, hashes: &BTreeSet<MerkleHash>) -> Vec<(EntryHash, Entry)> {
+        let mut ordered = Vec::with_capacity(hashes.len());
+        let mut visited = BTreeSet::new();
+        for start in hashes {
+            // depth first, pushing an entry once all of its children got pushed
+            let mut stack = vec![(*start, false)];
+            while let Some((current, children_pushed)) = stack.pop() {
+                let Some(node) = self.data.node(current) else {
+                    continue;
+                };
+                if children_pushed {
+                    ordered.push((EntryHash(current), node.value.clone()));
+                    continue;
+                }
+                if !visited.insert(current) {
+                    continue;
+                }
+                stack.push((current, true));
+                for child in node.children.iter().rev() {
+                    if hashes.contains(child) && !visited.contains(child) {
+                        stack.push((*child, false));
+                    }
+                }
+            }
+        }
+        ordered
+    }
+
     /// Access the underlying MerkleReg (e.g. for access to history)
     /// NOTE: This API is unstable and may be removed in the future
     pub(crate) fn merkle_reg(&self) -> &MerkleReg<Entry> {
@@ -226,4 +323,71 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn entry_history() -> Result<()> {
+        let mut rng = rand::thread_rng();
+        let address = RegisterAddress {
+            meta: XorName::random(&mut rng),
+            owner: SecretKey::random().public_key(),
+        };
+        let mut crdt = RegisterCrdt::new(address);
+
+        // let's build the following entries hierarchy to test:
+        // - entry_1 has no child
+        // - entry_2_1 and entry_2_2 both have entry_1 as child
+        // - entry_3 has entry_2_1 as child, leaving entry_2_2 as another branch
+        let entry_1 = vec![0x0, 0x1];
+        let entry_2_1 = vec![0x2, 0x1];
+        let entry_2_2 = vec![0x2, 0x2];
+        let entry_3 = vec![0x0, 0x3];
+        let (entry_hash_1, _, _) = crdt.write(entry_1.clone(), &BTreeSet::new())?;
+        let (entry_hash_2_1, _, _) =
+            crdt.write(entry_2_1.clone(), &[entry_hash_1].into_iter().collect())?;
+        let (entry_hash_2_2, _, _) =
+            crdt.write(entry_2_2.clone(), &[entry_hash_1].into_iter().collect())?;
+        let (entry_hash_3, _, _) =
+            crdt.write(entry_3.clone(), &[entry_hash_2_1].into_iter().collect())?;
+
+        let position = |history: &[(EntryHash, Entry)], hash: EntryHash| {
+            history
+                .iter()
+                .position(|(entry_hash, _)| *entry_hash == hash)
+        };
+
+        // every entry comes after the entries it was written atop of
+        let history = crdt.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0], (entry_hash_1, entry_1.clone()));
+        assert!(position(&history, entry_hash_2_1) < position(&history, entry_hash_3));
+        assert!(position(&history, entry_hash_2_2).is_some());
+
+        assert_eq!(
+            crdt.ancestors(&entry_hash_3),
+            vec![
+                (entry_hash_1, entry_1.clone()),
+                (entry_hash_2_1, entry_2_1.clone())
+            ]
+        );
+        assert!(crdt.ancestors(&entry_hash_1).is_empty());
+
+        let descendants = crdt.descendants(&entry_hash_1);
+        assert_eq!(descendants.len(), 3);
+        assert!(position(&descendants, entry_hash_2_1) < position(&descendants, entry_hash_3));
+        assert_eq!(
+            crdt.descendants(&entry_hash_2_1),
+            vec![(entry_hash_3, entry_3.clone())]
+        );
+
+        let diff = crdt.diff(
+            &[entry_hash_2_2].into_iter().collect(),
+            &[entry_hash_3].into_iter().collect(),
+        );
+        assert_eq!(
+            diff,
+            vec![(entry_hash_2_1, entry_2_1), (entry_hash_3, entry_3)]
+        );
+
+        Ok(())
+    }
 }
@@ -7,21 +7,21 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    error::Result, reg_crdt::RegisterCrdt, Entry, EntryHash, Error, Permissions, RegisterAddress,
-    RegisterOp,
+    error::Result, reg_crdt::RegisterCrdt, Entry, EntryHash, Error, PermissionChange, PermissionOp,
+    Permissions, RegisterAddress, RegisterOp,
 };
 
 use bls::{PublicKey, SecretKey, Signature};
-use crdts::merkle_reg::MerkleReg;
+use crdts::merkle_reg::{Hash as MerkleHash, MerkleReg};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use xor_name::XorName;
 
 /// Arbitrary maximum size of a register entry.
-const MAX_REG_ENTRY_SIZE: usize = 1024;
+pub const MAX_REG_ENTRY_SIZE: usize = 1024;
 
 /// Maximum number of entries of a register.
-const MAX_REG_NUM_ENTRIES: u16 = 1024;
+pub const MAX_REG_NUM_ENTRIES: u16 = 1024;
 
 /// A Register on the SAFE Network
 #[derive(Clone, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize, Debug)]
@@ -32,6 +32,21 @@ pub struct Register {
     /// Depending on the permissions, the owner can allow other users to write to the register
     /// Everyone can always read the Register because all data is public
     permissions: Permissions,
+    /// Signed changes to the above permissions, they are not part of the bytes signed by the owner
+    #[serde(default)]
+    permission_ops: BTreeSet<PermissionOp>,
+}
+
+/// The permissions of a Register once a number of permission ops got applied.
+struct PermissionsState {
+    /// the owner allowed to sign the next permission op
+    owner: PublicKey,
+    /// the users allowed to write
+    permissions: Permissions,
+    /// the number of permission ops applied
+    version: u64,
+    /// the hash of the last permission op applied
+    head: Option<XorName>,
 }
 
 /// A Signed Register on the SAFE Network
@@ -45,6 +60,10 @@ pub struct SignedRegister {
     /// operations to apply on this register,
     /// they contain a signature of the writer
     ops: BTreeSet<RegisterOp>,
+    /// changes to the permissions of the base register,
+    /// they contain a signature of the owner at the time of the change
+    #[serde(default)]
+    permission_ops: BTreeSet<PermissionOp>,
 }
 
 impl SignedRegister {
@@ -54,6 +73,7 @@ impl SignedRegister {
             base_register,
             signature,
             ops: BTreeSet::new(),
+            permission_ops: BTreeSet::new(),
         }
     }
 
@@ -68,8 +88,11 @@ impl SignedRegister {
             return Err(Error::InvalidSignature);
         }
 
+        let permissions_view = self.permissions_view()?;
+        let versions = self.ops_permissions_versions();
         for op in &self.ops {
-            self.base_register.check_register_op(op)?;
+            permissions_view.check_past_op(op)?;
+            Self::check_op_causality(op, &versions)?;
         }
         Ok(())
     }
@@ -86,9 +109,11 @@ impl SignedRegister {
 
     /// Return the Register after applying all the operations
     pub fn register(self) -> Result<Register> {
-        let mut register = self.base_register;
+        let mut register = self.permissions_view()?;
         for op in self.ops {
-            register.apply_op(op)?;
+            register.check_entry_and_reg_sizes(&op.crdt_op.value)?;
+            register.check_past_op(&op)?;
+            register.crdt.apply_op(op)?;
         }
         Ok(register)
     }
@@ -98,6 +123,7 @@ impl SignedRegister {
         self.base_register
             .verify_is_mergeable(&other.base_register)?;
         self.ops.extend(other.ops.clone());
+        self.permission_ops.extend(other.permission_ops.clone());
         Ok(())
     }
 
@@ -107,7 +133,20 @@ impl SignedRegister {
         self.base_register
             .verify_is_mergeable(&other.base_register)?;
         other.verify()?;
+        let mut merged = Self::new(self.base_register.clone(), self.signature.clone());
+        merged.permission_ops = self.permission_ops.clone();
+        merged
+            .permission_ops
+            .extend(other.base_register.permission_ops.clone());
+        merged.permission_ops.extend(other.permission_ops.clone());
+        // the ops we didn't know of yet are only accepted from the current writers,
+        // this is how removing a writer takes effect
+        let permissions_view = merged.permissions_view()?;
+        for op in other.ops.difference(&self.ops) {
+            permissions_view.check_register_op(op)?;
+        }
         self.ops.extend(other.ops.clone());
+        self.permission_ops = merged.permission_ops;
         Ok(())
     }
 
@@ -123,16 +162,63 @@ impl SignedRegister {
 
     /// Check and add an Op to the SignedRegister
     pub fn add_op(&mut self, op: RegisterOp) -> Result<()> {
-        self.base_register.check_register_op(&op)?;
+        self.permissions_view()?.check_register_op(&op)?;
+        Self::check_op_causality(&op, &self.ops_permissions_versions())?;
         self.ops.insert(op);
         Ok(())
     }
 
+    /// Check and add a permission Op to the SignedRegister
+    pub fn add_permission_op(&mut self, op: PermissionOp) -> Result<()> {
+        self.permissions_view()?.apply_permission_op(op.clone())?;
+        self.permission_ops.insert(op);
+        Ok(())
+    }
+
     /// Access the underlying MerkleReg (e.g. for access to history)
     /// NOTE: This API is unstable and may be removed in the future
     pub fn merkle_reg(&self) -> &MerkleReg<Entry> {
         self.base_register.merkle_reg()
     }
+
+    // Private helper returning the base register with all the permission ops applied,
+    // verifying they form a single chain in the process.
+    fn permissions_view(&self) -> Result<Register> {
+        let mut register = self.base_register.clone();
+        register
+            .permission_ops
+            .extend(self.permission_ops.iter().cloned());
+        register.check_permission_chain()?;
+        Ok(register)
+    }
+
+    // Private helper returning the permissions version of the ops, by the hash of their entry.
+    // The lowest version is kept for an entry written by several ops.
+    fn ops_permissions_versions(&self) -> BTreeMap<MerkleHash, u64> {
+        let mut versions = BTreeMap::new();
+        for op in &self.ops {
+            versions
+                .entry(op.crdt_op.hash())
+                .and_modify(|version: &mut u64| *version = (*version).min(op.permissions_version))
+                .or_insert(op.permissions_version);
+        }
+        versions
+    }
+
+    // Private helper to check an op is not written under older permissions than the entries
+    // it was written atop of. Otherwise a writer could claim the permissions from before its
+    // removal, for entries written after it.
+    fn check_op_causality(op: &RegisterOp, versions: &BTreeMap<MerkleHash, u64>) -> Result<()> {
+        let stale = op.crdt_op.children.iter().any(|child| {
+            versions
+                .get(child)
+                .is_some_and(|version| *version > op.permissions_version)
+        });
+        if stale {
+            return Err(Error::StalePermissionsVersion(op.source));
+        }
+        Ok(())
+    }
 }
 
 impl Register {
@@ -143,6 +229,7 @@ impl Register {
         Self {
             crdt: RegisterCrdt::new(address),
             permissions,
+            permission_ops: BTreeSet::new(),
         }
     }
 
@@ -158,8 +245,9 @@ impl Register {
 
     /// Returns a bytes version of the Register used for signing
     /// Use this API when you want to sign a Register withtout providing a secret key to the Register API
+    /// The permission ops are left out as they are signed on their own.
     pub fn bytes(&self) -> Result<Vec<u8>> {
-        rmp_serde::to_vec(self).map_err(|_| Error::SerialisationFailed)
+        rmp_serde::to_vec(&(&self.crdt, &self.permissions)).map_err(|_| Error::SerialisationFailed)
     }
 
     /// Sign a Register into a SignedRegister
@@ -174,15 +262,27 @@ impl Register {
     }
 
     /// Return the owner of the data.
+    /// This is the owner the Register was created with, the one its address derives from.
     pub fn owner(&self) -> PublicKey {
         self.address().owner()
     }
 
+    /// Return the current owner, allowed to change the permissions.
+    /// This differs from `owner` once the ownership got transferred.
+    pub fn current_owner(&self) -> PublicKey {
+        self.current_permissions().owner
+    }
+
     /// Return the number of items held in the register
     pub fn size(&self) -> u64 {
         self.crdt.size()
     }
 
+    /// Return true if the register holds `MAX_REG_NUM_ENTRIES` items, i.e. no more can be written.
+    pub fn is_full(&self) -> bool {
+        self.size() >= MAX_REG_NUM_ENTRIES.into()
+    }
+
     /// Return a value corresponding to the provided 'hash', if present.
     pub fn get(&self, hash: EntryHash) -> Result<&Entry> {
         self.crdt.get(hash).ok_or(Error::NoSuchEntry(hash))
@@ -198,9 +298,85 @@ impl Register {
         self.crdt.children(hash)
     }
 
-    /// Return the permission.
-    pub fn permissions(&self) -> &Permissions {
-        &self.permissions
+    /// Returns all the entries of the Register, ordered from the first entries written
+    /// to the current ones. An entry always comes after the entries it was written atop of,
+    /// concurrent branches being interleaved.
+    pub fn history(&self) -> Vec<(EntryHash, Entry)> {
+        self.crdt.history()
+    }
+
+    /// Returns the entries the provided entry was written atop of, directly or not,
+    /// ordered from the first entries written.
+    pub fn ancestors(&self, hash: &EntryHash) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_entry_exists(hash)?;
+        Ok(self.crdt.ancestors(hash))
+    }
+
+    /// Returns the entries written atop of the provided entry, directly or not,
+    /// ordered from the first entries written.
+    pub fn descendants(&self, hash: &EntryHash) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_entry_exists(hash)?;
+        Ok(self.crdt.descendants(hash))
+    }
+
+    /// Returns the entries written between two versions of the Register, given by their heads
+    /// (i.e. the result of `read`), ordered from the first entries written.
+    /// These are the entries known from the `to` heads but not from the `from` heads.
+    pub fn diff(
+        &self,
+        from: &BTreeSet<EntryHash>,
+        to: &BTreeSet<EntryHash>,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        for hash in from.iter().chain(to) {
+            self.check_entry_exists(hash)?;
+        }
+        Ok(self.crdt.diff(from, to))
+    }
+
+    /// Return the current permissions, i.e. after applying the permission ops.
+    pub fn permissions(&self) -> Permissions {
+        self.current_permissions().permissions
+    }
+
+    /// Return the permission ops applied to the Register.
+    pub fn permission_ops(&self) -> &BTreeSet<PermissionOp> {
+        &self.permission_ops
+    }
+
+    /// Change the permissions of the Register, returning the generated
+    /// permission operation so the caller can broadcast it to other replicas.
+    /// Only the current owner can change the permissions.
+    pub fn change_permissions(
+        &mut self,
+        change: PermissionChange,
+        signer: &SecretKey,
+    ) -> Result<PermissionOp> {
+        let current = self.current_permissions();
+        if signer.public_key() != current.owner {
+            return Err(Error::AccessDenied(signer.public_key()));
+        }
+        if change == PermissionChange::RemoveWriter(current.owner) {
+            return Err(Error::CannotRemoveOwner(current.owner));
+        }
+        let op = PermissionOp::new(
+            *self.address(),
+            current.version,
+            current.head,
+            change,
+            signer,
+        )?;
+        self.apply_permission_op(op.clone())?;
+        Ok(op)
+    }
+
+    /// Apply a signed permission operation, applying it again has no effect.
+    pub fn apply_permission_op(&mut self, op: PermissionOp) -> Result<()> {
+        if self.permission_ops.contains(&op) {
+            return Ok(());
+        }
+        self.check_permission_op(&op)?;
+        let _ = self.permission_ops.insert(op);
+        Ok(())
     }
 
     /// Write an entry to the Register, returning the generated
@@ -214,9 +390,12 @@ impl Register {
     ) -> Result<(EntryHash, RegisterOp)> {
         self.check_entry_and_reg_sizes(&entry)?;
         // check permissions before writing on the underlying CRDT
-        self.check_user_permissions(signer.public_key())?;
+        let current = self.current_permissions();
+        if !current.permissions.can_write(&signer.public_key()) {
+            return Err(Error::AccessDenied(signer.public_key()));
+        }
         let (hash, address, crdt_op) = self.crdt.write(entry, children)?;
-        let op = RegisterOp::new(address, crdt_op, signer);
+        let op = RegisterOp::new(address, crdt_op, current.version, signer)?;
         Ok((hash, op))
     }
 
@@ -231,15 +410,49 @@ impl Register {
     pub fn merge(&mut self, other: &Self) -> Result<()> {
         self.verify_is_mergeable(other)?;
         self.crdt.merge(other.crdt.clone());
+        self.permission_ops.extend(other.permission_ops.clone());
         Ok(())
     }
 
     /// Check if a register op is valid for our current register
+    /// The source shall be allowed to write under the current permissions, whatever permissions
+    /// the op claims to be written under. This way the removal of a writer applies to all the
+    /// ops of theirs the Register doesn't hold yet, while the ones it holds remain.
     pub fn check_register_op(&self, op: &RegisterOp) -> Result<()> {
-        if self.permissions.can_anyone_write() {
-            return Ok(()); // anyone can write, so no need to check the signature
+        let current = self.current_permissions();
+        if current.version < op.permissions_version {
+            return Err(Error::MissingPermissionOps {
+                version: op.permissions_version,
+                known: current.version,
+            });
+        }
+        Self::check_op_source(op, &current.permissions)
+    }
+
+    /// Check if a permission op is valid for our current register
+    /// The op shall apply atop of the current permissions, i.e. refer to the last permission op
+    /// applied, and be signed by the current owner. An op at a version already taken is
+    /// rejected, so a former owner can't override the changes made after them.
+    pub fn check_permission_op(&self, op: &PermissionOp) -> Result<()> {
+        if op.address != *self.address() {
+            return Err(Error::RegisterAddrMismatch {
+                dst_addr: Box::new(op.address),
+                reg_addr: Box::new(*self.address()),
+            });
+        }
+        let current = self.current_permissions();
+        if op.version < current.version {
+            return Err(Error::PermissionVersionTaken(op.version));
+        }
+        if op.version > current.version || op.previous != current.head {
+            return Err(Error::MissingPermissionOps {
+                version: op.version,
+                known: current.version,
+            });
+        }
+        if op.source != current.owner {
+            return Err(Error::AccessDenied(op.source));
         }
-        self.check_user_permissions(op.source)?;
         op.verify_signature(&op.source)
     }
 
@@ -249,7 +462,7 @@ impl Register {
     /// `Ok(())` if the user can write to this register
     /// `Err::AccessDenied` if the user cannot write to this register
     pub fn check_user_permissions(&self, requester: PublicKey) -> Result<()> {
-        if self.permissions.can_write(&requester) {
+        if self.permissions().can_write(&requester) {
             Ok(())
         } else {
             Err(Error::AccessDenied(requester))
@@ -281,6 +494,122 @@ impl Register {
         Ok(())
     }
 
+    // Private helper checking an op held by the Register against the permissions it was
+    // written under, as its writer can have been removed since.
+    fn check_past_op(&self, op: &RegisterOp) -> Result<()> {
+        let past = self.permissions_at(op.permissions_version);
+        if past.version < op.permissions_version {
+            return Err(Error::MissingPermissionOps {
+                version: op.permissions_version,
+                known: past.version,
+            });
+        }
+        Self::check_op_source(op, &past.permissions)
+    }
+
+    // Private helper to check the source of an op is allowed to write under the given permissions
+    fn check_op_source(op: &RegisterOp, permissions: &Permissions) -> Result<()> {
+        if permissions.can_anyone_write() {
+            return Ok(()); // anyone can write, so no need to check the signature
+        }
+        if !permissions.can_write(&op.source) {
+            return Err(Error::AccessDenied(op.source));
+        }
+        op.verify_signature(&op.source)
+    }
+
+    // Private helper returning the current permissions, i.e. once all the permission ops applied.
+    fn current_permissions(&self) -> PermissionsState {
+        self.permissions_at(u64::MAX)
+    }
+
+    // Private helper applying the chain of permission ops to the initial permissions, up to
+    // `max_version` of them.
+    // The chain stops at a version taken by several ops, which a verified Register never holds.
+    fn permissions_at(&self, max_version: u64) -> PermissionsState {
+        let mut state = PermissionsState {
+            owner: self.owner(),
+            permissions: self.permissions.clone(),
+            version: 0,
+            head: None,
+        };
+        while state.version < max_version {
+            let mut next_ops = self.permission_ops.iter().filter(|op| {
+                op.previous == state.head && op.version == state.version && op.source == state.owner
+            });
+            let (Some(op), None) = (next_ops.next(), next_ops.next()) else {
+                break;
+            };
+            let Ok(hash) = op.hash() else {
+                break;
+            };
+            match &op.change {
+                PermissionChange::AddWriter(user) => state.permissions.add_writer(*user),
+                PermissionChange::RemoveWriter(user) => {
+                    if *user != state.owner {
+                        state.permissions.remove_writer(user);
+                    }
+                }
+                PermissionChange::TransferOwnership(user) => {
+                    state.owner = *user;
+                    state.permissions.add_writer(*user);
+                }
+            }
+            state.version += 1;
+            state.head = Some(hash);
+        }
+        state
+    }
+
+    // Private helper to check the permission ops form a single chain starting from the initial
+    // permissions, each op referring to the previous one and signed by the owner at that point.
+    fn check_permission_chain(&self) -> Result<()> {
+        let mut owner = self.owner();
+        let mut head = None;
+        for version in 0..self.permission_ops.len() as u64 {
+            let mut next_ops = self.permission_ops.iter().filter(|op| op.previous == head);
+            let Some(op) = next_ops.next() else {
+                return Err(Error::MissingPermissionOps {
+                    version: self.permission_ops.len() as u64,
+                    known: version,
+                });
+            };
+            if next_ops.next().is_some() {
+                return Err(Error::PermissionVersionTaken(version));
+            }
+            if op.address != *self.address() {
+                return Err(Error::RegisterAddrMismatch {
+                    dst_addr: Box::new(op.address),
+                    reg_addr: Box::new(*self.address()),
+                });
+            }
+            if op.version != version {
+                return Err(Error::MissingPermissionOps {
+                    version: op.version,
+                    known: version,
+                });
+            }
+            if op.source != owner {
+                return Err(Error::AccessDenied(op.source));
+            }
+            op.verify_signature(&op.source)?;
+            if let PermissionChange::TransferOwnership(user) = op.change {
+                owner = user;
+            }
+            head = Some(op.hash()?);
+        }
+        Ok(())
+    }
+
+    // Private helper to check the entry is part of this Register
+    fn check_entry_exists(&self, hash: &EntryHash) -> Result<()> {
+        if self.crdt.contains(hash) {
+            Ok(())
+        } else {
+            Err(Error::NoSuchEntry(*hash))
+        }
+    }
+
     // Private helper to check if this Register is mergeable with another
     fn verify_is_mergeable(&self, other: &Self) -> Result<()> {
         if self.address() != other.address() || self.permissions != other.permissions {
@@ -295,6 +624,7 @@ impl Register {
         Register {
             crdt: RegisterCrdt::new(address),
             permissions: Permissions::AnyoneCanWrite,
+            permission_ops: BTreeSet::new(),
         }
     }
 }
@@ -304,7 +634,8 @@ mod tests {
     use crate::RegisterOp;
 
     use super::{
-        EntryHash, Error, Permissions, Register, RegisterAddress, Result, MAX_REG_NUM_ENTRIES,
+        EntryHash, Error, PermissionChange, Permissions, Register, RegisterAddress, Result,
+        MAX_REG_NUM_ENTRIES,
     };
 
     use bls::SecretKey;
@@ -405,7 +736,7 @@ mod tests {
             "Unexpected result: {res:?}"
         );
         let (_, address, crdt_op) = replica1.crdt.write(item.clone(), &BTreeSet::new())?;
-        let op_signed_by_other_user = RegisterOp::new(address, crdt_op, &other_user_sk);
+        let op_signed_by_other_user = RegisterOp::new(address, crdt_op, 0, &other_user_sk)?;
         let res = replica2.apply_op(op_signed_by_other_user);
         assert!(
             matches!(&res, Err(err) if err == &Error::AccessDenied(other_user)),
@@ -441,6 +772,195 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_permission_changes() -> eyre::Result<()> {
+        let owner_sk = SecretKey::random();
+        let owner = owner_sk.public_key();
+        let writer_sk = SecretKey::random();
+        let writer = writer_sk.public_key();
+        let new_owner_sk = SecretKey::random();
+        let new_owner = new_owner_sk.public_key();
+
+        let meta: XorName = xor_name::rand::random();
+        let item = random_register_entry();
+
+        let mut replica = Register::new(owner, meta, Permissions::default());
+        let mut signed_replica = replica.clone().into_signed(&owner_sk)?;
+
+        // the owner grants the writer permission, which can then write
+        let add_op = replica.change_permissions(PermissionChange::AddWriter(writer), &owner_sk)?;
+        let (_, op_before_removal) = replica.write(item.clone(), &BTreeSet::new(), &writer_sk)?;
+        signed_replica.add_permission_op(add_op)?;
+        signed_replica.add_op(op_before_removal.clone())?;
+        signed_replica.verify()?;
+
+        // ...only the owner can change the permissions, which can't remove themselves
+        let res = replica.change_permissions(PermissionChange::AddWriter(new_owner), &writer_sk);
+        assert!(
+            matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+            "Unexpected result: {res:?}"
+        );
+        let res = replica.change_permissions(PermissionChange::RemoveWriter(owner), &owner_sk);
+        assert!(
+            matches!(&res, Err(err) if err == &Error::CannotRemoveOwner(owner)),
+            "Unexpected result: {res:?}"
+        );
+
+        // the writer permission gets revoked
+        let remove_op =
+            replica.change_permissions(PermissionChange::RemoveWriter(writer), &owner_sk)?;
+        signed_replica.add_permission_op(remove_op)?;
+        let res = replica.write(item.clone(), &BTreeSet::new(), &writer_sk);
+        assert!(
+            matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+            "Unexpected result: {res:?}"
+        );
+        assert!(!replica.permissions().can_write(&writer));
+
+        // ...the ops written before the revocation are still valid
+        signed_replica.verify()?;
+        assert_eq!(signed_replica.clone().register()?.size(), 1);
+
+        // ...while the ones written after it are rejected, even by a replica which never saw
+        // any op before
+        let (_, address, crdt_op) = replica
+            .crdt
+            .write(random_register_entry(), &BTreeSet::new())?;
+        let op_after_removal = RegisterOp::new(address, crdt_op, 2, &writer_sk)?;
+        let res = signed_replica.add_op(op_after_removal.clone());
+        assert!(
+            matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+            "Unexpected result: {res:?}"
+        );
+        let mut fresh_replica =
+            Register::new(owner, meta, Permissions::default()).into_signed(&owner_sk)?;
+        let mut forged_replica = fresh_replica.clone();
+        forged_replica.merge(&signed_replica)?;
+        forged_replica.ops.insert(op_after_removal);
+        let res = fresh_replica.verified_merge(&forged_replica);
+        assert!(
+            matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+            "Unexpected result: {res:?}"
+        );
+
+        // ...and a Register claiming the permissions from before the removal for an op written
+        // atop of an entry written after it doesn't verify
+        let (owner_entry, owner_op) =
+            replica.write(random_register_entry(), &BTreeSet::new(), &owner_sk)?;
+        signed_replica.add_op(owner_op)?;
+        let (_, address, crdt_op) = replica
+            .crdt
+            .write(random_register_entry(), &BTreeSet::from([owner_entry]))?;
+        let backdated_op = RegisterOp::new(address, crdt_op, 1, &writer_sk)?;
+        let mut forged_replica = signed_replica.clone();
+        forged_replica.ops.insert(backdated_op);
+        let res = forged_replica.verify();
+        assert!(
+            matches!(&res, Err(err) if err == &Error::StalePermissionsVersion(writer)),
+            "Unexpected result: {res:?}"
+        );
+        let mut merged_replica = signed_replica.clone();
+
+        // the ownership gets transferred, the previous owner can't change the permissions anymore
+        let before_transfer = replica.clone();
+        let transfer_op = replica
+            .change_permissions(PermissionChange::TransferOwnership(new_owner), &owner_sk)?;
+        merged_replica.add_permission_op(transfer_op.clone())?;
+        assert_eq!(replica.current_owner(), new_owner);
+        assert_eq!(replica.owner(), owner);
+        assert!(replica.permissions().can_write(&new_owner));
+        let res = replica.change_permissions(PermissionChange::AddWriter(writer), &owner_sk);
+        assert!(
+            matches!(&res, Err(err) if err == &Error::AccessDenied(owner)),
+            "Unexpected result: {res:?}"
+        );
+        let add_op =
+            replica.change_permissions(PermissionChange::AddWriter(writer), &new_owner_sk)?;
+        merged_replica.add_permission_op(add_op)?;
+
+        // ...nor override the transfer with an op of the same version
+        let conflicting_op = before_transfer
+            .clone()
+            .change_permissions(PermissionChange::AddWriter(writer), &owner_sk)?;
+        assert_eq!(conflicting_op.version(), transfer_op.version());
+        let res = merged_replica.add_permission_op(conflicting_op.clone());
+        assert!(
+            matches!(&res, Err(err) if err == &Error::PermissionVersionTaken(transfer_op.version())),
+            "Unexpected result: {res:?}"
+        );
+        let mut forged_replica = merged_replica.clone();
+        forged_replica.permission_ops.insert(conflicting_op);
+        let res = forged_replica.verify();
+        assert!(
+            matches!(&res, Err(err) if err == &Error::PermissionVersionTaken(transfer_op.version())),
+            "Unexpected result: {res:?}"
+        );
+
+        // ...and all replicas end up with the same permissions
+        let merged_register = merged_replica.register()?;
+        assert_eq!(merged_register.current_owner(), new_owner);
+        assert_eq!(merged_register.permissions(), replica.permissions());
+        assert!(merged_register.permissions().can_write(&writer));
+
+        Ok(())
+    }
+
+    #[test]
+    fn removed_writer_cannot_backdate_ops() -> eyre::Result<()> {
+        let owner_sk = SecretKey::random();
+        let owner = owner_sk.public_key();
+        let writer_sk = SecretKey::random();
+        let writer = writer_sk.public_key();
+
+        let meta: XorName = xor_name::rand::random();
+        let mut replica = Register::new(owner, meta, Permissions::new_with([writer]));
+        let mut signed_replica = replica.clone().into_signed(&owner_sk)?;
+
+        // the writer writes an entry, then gets removed
+        let (old_entry, old_op) =
+            replica.write(random_register_entry(), &BTreeSet::new(), &writer_sk)?;
+        signed_replica.add_op(old_op)?;
+        let remove_op =
+            replica.change_permissions(PermissionChange::RemoveWriter(writer), &owner_sk)?;
+        signed_replica.add_permission_op(remove_op)?;
+
+        // ...its ops claiming the permissions from before the removal are all rejected,
+        // whether they are new roots or written atop of an entry from before the removal
+        for children in [BTreeSet::new(), BTreeSet::from([old_entry])] {
+            let (_, address, crdt_op) = replica.crdt.write(random_register_entry(), &children)?;
+            let backdated_op = RegisterOp::new(address, crdt_op, 0, &writer_sk)?;
+
+            let res = signed_replica.add_op(backdated_op.clone());
+            assert!(
+                matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+                "Unexpected result: {res:?}"
+            );
+            let res = signed_replica
+                .clone()
+                .register()?
+                .apply_op(backdated_op.clone());
+            assert!(
+                matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+                "Unexpected result: {res:?}"
+            );
+
+            let mut forged_replica = signed_replica.clone();
+            forged_replica.ops.insert(backdated_op);
+            let mut merged_replica = signed_replica.clone();
+            let res = merged_replica.verified_merge(&forged_replica);
+            assert!(
+                matches!(&res, Err(err) if err == &Error::AccessDenied(writer)),
+                "Unexpected result: {res:?}"
+            );
+        }
+
+        // ...while the one written before the removal remains
+        signed_replica.verify()?;
+        assert_eq!(signed_replica.register()?.size(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn register_concurrent_write_ops() -> eyre::Result<()> {
         let authority_sk1 = SecretKey::random();
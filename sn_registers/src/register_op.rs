@@ -13,6 +13,7 @@ use crdts::merkle_reg::Node as MerkleDagEntry;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use xor_name::XorName;
 
 /// Register mutation operation to apply to Register.
 /// CRDT Data operation applicable to other Register replica.
@@ -24,8 +25,14 @@ pub struct RegisterOp {
     pub(crate) crdt_op: MerkleDagEntry<Entry>,
     /// The PublicKey of the entity that generated the operation
     pub(crate) source: PublicKey,
-    /// The signature of source on hash(address, crdt_op, source) required to apply the op
+    /// The signature of source on hash(address, crdt_op, source, permissions_version)
+    /// required to apply the op
     pub(crate) signature: bls::Signature,
+    /// The number of permission ops applied to the Register when the op was written,
+    /// the source shall be allowed to write under the permissions at that point.
+    /// Last, and 0 by default, for the ops serialised before it existed to be deserialised.
+    #[serde(default)]
+    pub(crate) permissions_version: u64,
 }
 
 impl std::hash::Hash for RegisterOp {
@@ -33,6 +40,7 @@ impl std::hash::Hash for RegisterOp {
         self.address.hash(state);
         self.crdt_op.hash().hash(state);
         self.source.hash(state);
+        self.permissions_version.hash(state);
         self.signature.hash(state);
     }
 }
@@ -42,16 +50,19 @@ impl RegisterOp {
     pub(crate) fn new(
         address: RegisterAddress,
         crdt_op: MerkleDagEntry<Entry>,
+        permissions_version: u64,
         signer: &SecretKey,
-    ) -> Self {
+    ) -> Result<Self> {
         let source = signer.public_key();
-        let signature = signer.sign(Self::bytes_for_signing(&address, &crdt_op, &source));
-        Self {
+        let bytes = Self::bytes_for_signing(&address, &crdt_op, &source, permissions_version)?;
+        let signature = signer.sign(bytes);
+        Ok(Self {
             address,
             crdt_op,
             source,
             signature,
-        }
+            permissions_version,
+        })
     }
 
     /// address of the register this op is destined for
@@ -64,13 +75,29 @@ impl RegisterOp {
         self.source
     }
 
+    /// the number of permission ops applied to the Register when the op was written
+    pub fn permissions_version(&self) -> u64 {
+        self.permissions_version
+    }
+
     /// Check signature of register Op against provided public key
     pub fn verify_signature(&self, pk: &PublicKey) -> Result<()> {
-        let bytes = Self::bytes_for_signing(&self.address, &self.crdt_op, &self.source);
-        if !pk.verify(&self.signature, bytes) {
-            return Err(Error::InvalidSignature);
+        let bytes = Self::bytes_for_signing(
+            &self.address,
+            &self.crdt_op,
+            &self.source,
+            self.permissions_version,
+        )?;
+        if pk.verify(&self.signature, bytes) {
+            return Ok(());
         }
-        Ok(())
+        // ops signed before the permissions version got introduced are all of version 0
+        let legacy_bytes =
+            Self::legacy_bytes_for_signing(&self.address, &self.crdt_op, &self.source);
+        if self.permissions_version == 0 && pk.verify(&self.signature, legacy_bytes) {
+            return Ok(());
+        }
+        Err(Error::InvalidSignature)
     }
 
     /// Returns a bytes version of the RegisterOp used for signing
@@ -78,6 +105,18 @@ impl RegisterOp {
         address: &RegisterAddress,
         crdt_op: &MerkleDagEntry<Entry>,
         source: &PublicKey,
+        permissions_version: u64,
+    ) -> Result<Vec<u8>> {
+        let bytes = rmp_serde::to_vec(&(address, crdt_op.hash(), source, permissions_version))
+            .map_err(|_| Error::SerialisationFailed)?;
+        Ok(XorName::from_content(&bytes).0.to_vec())
+    }
+
+    /// Returns the bytes the RegisterOp used to be signed on, before it had a permissions version
+    fn legacy_bytes_for_signing(
+        address: &RegisterAddress,
+        crdt_op: &MerkleDagEntry<Entry>,
+        source: &PublicKey,
     ) -> Vec<u8> {
         let mut hasher = DefaultHasher::new();
         address.hash(&mut hasher);
@@ -76,6 +76,12 @@ impl<'a> ServiceStateActions for NodeService<'a> {
             args.push(OsString::from("--port"));
             args.push(OsString::from(node_port.to_string()));
         }
+        if !self.service_data.listen_transports.is_empty() {
+            args.push(OsString::from("--transport"));
+            args.push(OsString::from(
+                self.service_data.listen_transports.join(","),
+            ));
+        }
         if let Some(metrics_port) = self.service_data.metrics_port {
             args.push(OsString::from("--metrics-server-port"));
             args.push(OsString::from(metrics_port.to_string()));
@@ -85,6 +91,22 @@ impl<'a> ServiceStateActions for NodeService<'a> {
             args.push(OsString::from("--owner"));
             args.push(OsString::from(owner));
         }
+        if let Some(max_storage_bytes) = self.service_data.max_storage_bytes {
+            args.push(OsString::from("--max-storage-bytes"));
+            args.push(OsString::from(max_storage_bytes.to_string()));
+        }
+        if let Some(max_connections) = self.service_data.max_connections {
+            args.push(OsString::from("--max-connections"));
+            args.push(OsString::from(max_connections.to_string()));
+        }
+        if let Some(max_upload_bytes_per_sec) = self.service_data.max_upload_bytes_per_sec {
+            args.push(OsString::from("--max-upload-bytes-per-sec"));
+            args.push(OsString::from(max_upload_bytes_per_sec.to_string()));
+        }
+        if let Some(max_download_bytes_per_sec) = self.service_data.max_download_bytes_per_sec {
+            args.push(OsString::from("--max-download-bytes-per-sec"));
+            args.push(OsString::from(max_download_bytes_per_sec.to_string()));
+        }
 
         if !options.bootstrap_peers.is_empty() {
             let peers_str = options
@@ -233,10 +255,20 @@ pub struct NodeServiceData {
     pub genesis: bool,
     pub home_network: bool,
     pub listen_addr: Option<Vec<Multiaddr>>,
+    #[serde(default)]
+    pub listen_transports: Vec<String>,
     pub local: bool,
     pub log_dir_path: PathBuf,
     pub log_format: Option<LogFormat>,
     #[serde(default)]
+    pub max_connections: Option<u32>,
+    #[serde(default)]
+    pub max_download_bytes_per_sec: Option<u64>,
+    #[serde(default)]
+    pub max_storage_bytes: Option<u64>,
+    #[serde(default)]
+    pub max_upload_bytes_per_sec: Option<u64>,
+    #[serde(default)]
     pub metrics_port: Option<u16>,
     #[serde(default)]
     pub owner: Option<String>,
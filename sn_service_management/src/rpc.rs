@@ -10,10 +10,16 @@ use crate::error::{Error, Result};
 use async_trait::async_trait;
 use libp2p::{kad::RecordKey, Multiaddr, PeerId};
 use sn_protocol::safenode_proto::{
-    safe_node_client::SafeNodeClient, NetworkInfoRequest, NodeInfoRequest, RecordAddressesRequest,
-    RestartRequest, StopRequest, UpdateLogLevelRequest, UpdateRequest,
+    safe_node_client::SafeNodeClient, BadNodesRequest, NetworkInfoRequest, NodeInfoRequest,
+    RecordAddressesRequest, RestartRequest, RoutingHealthRequest, StopRequest,
+    UpdateLogLevelRequest, UpdateRequest,
+};
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
 use tokio::time::Duration;
 use tonic::Request;
 use tracing::error;
@@ -27,6 +33,13 @@ pub struct NodeInfo {
     pub version: String,
     pub uptime: Duration,
     pub wallet_balance: u64,
+    pub connections: u32,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Bytes sent per second, over the last few seconds
+    pub upload_rate: u64,
+    /// Bytes received per second, over the last few seconds
+    pub download_rate: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -40,11 +53,40 @@ pub struct RecordAddress {
     pub key: RecordKey,
 }
 
+#[derive(Debug, Clone)]
+pub struct BadNode {
+    pub peer_id: PeerId,
+    /// The issues recorded against the peer, along with the time they were recorded
+    pub issues: Vec<(String, SystemTime)>,
+    pub is_bad: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BucketHealth {
+    /// The ilog2 distance of the peers of the bucket to the node
+    pub distance: u32,
+    pub num_peers: u32,
+    pub capacity: u32,
+    /// The number of peers only reachable through a relay
+    pub relayed_peers: u32,
+    /// When a peer of the bucket last answered a request from the node
+    pub last_successful_query: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoutingHealth {
+    pub buckets: Vec<BucketHealth>,
+    pub estimated_network_size: u64,
+    pub responsible_distance_range: Option<u32>,
+}
+
 #[async_trait]
 pub trait RpcActions: Sync {
     async fn node_info(&self) -> Result<NodeInfo>;
     async fn network_info(&self) -> Result<NetworkInfo>;
     async fn record_addresses(&self) -> Result<Vec<RecordAddress>>;
+    async fn bad_nodes(&self) -> Result<Vec<BadNode>>;
+    async fn routing_health(&self) -> Result<RoutingHealth>;
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()>;
     async fn node_stop(&self, delay_millis: u64) -> Result<()>;
     async fn node_update(&self, delay_millis: u64) -> Result<()>;
@@ -134,6 +176,11 @@ impl RpcActions for RpcClient {
             version: node_info_resp.bin_version.clone(),
             uptime: Duration::from_secs(node_info_resp.uptime_secs),
             wallet_balance: node_info_resp.wallet_balance,
+            connections: node_info_resp.connections,
+            bytes_sent: node_info_resp.bytes_sent,
+            bytes_received: node_info_resp.bytes_received,
+            upload_rate: node_info_resp.upload_rate,
+            download_rate: node_info_resp.download_rate,
         };
         Ok(node_info)
     }
@@ -183,6 +230,69 @@ impl RpcActions for RpcClient {
         Ok(record_addresses)
     }
 
+    async fn bad_nodes(&self) -> Result<Vec<BadNode>> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .bad_nodes(Request::new(BadNodesRequest {}))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain bad nodes through RPC: {e:?}");
+                Error::RpcBadNodesError(e.to_string())
+            })?;
+        let mut bad_nodes = vec![];
+        for bad_node in response.get_ref().bad_nodes.iter() {
+            let peer_id = PeerId::from_bytes(&bad_node.peer_id)?;
+            let issues = bad_node
+                .issues
+                .iter()
+                .map(|issue| {
+                    (
+                        issue.issue.clone(),
+                        UNIX_EPOCH + Duration::from_secs(issue.timestamp_secs),
+                    )
+                })
+                .collect();
+            bad_nodes.push(BadNode {
+                peer_id,
+                issues,
+                is_bad: bad_node.is_bad,
+            });
+        }
+        Ok(bad_nodes)
+    }
+
+    async fn routing_health(&self) -> Result<RoutingHealth> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .routing_health(Request::new(RoutingHealthRequest {}))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain routing health through RPC: {e:?}");
+                Error::RpcRoutingHealthError(e.to_string())
+            })?;
+        let response = response.get_ref();
+        let buckets = response
+            .buckets
+            .iter()
+            .map(|bucket| BucketHealth {
+                distance: bucket.distance,
+                num_peers: bucket.num_peers,
+                capacity: bucket.capacity,
+                relayed_peers: bucket.relayed_peers,
+                last_successful_query: (bucket.last_successful_query_secs > 0)
+                    .then(|| UNIX_EPOCH + Duration::from_secs(bucket.last_successful_query_secs)),
+            })
+            .collect();
+        Ok(RoutingHealth {
+            buckets,
+            estimated_network_size: response.estimated_network_size,
+            responsible_distance_range: response
+                .responsible_distance_range
+                .as_ref()
+                .map(|range| range.distance),
+        })
+    }
+
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()> {
         let mut client = self.connect_with_retry().await?;
         let _response = client
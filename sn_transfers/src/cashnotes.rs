@@ -10,23 +10,28 @@ mod address;
 mod builder;
 mod cashnote;
 mod hash;
+mod multisig;
 mod nano;
 mod signed_spend;
+mod spend_condition;
 mod spend_reason;
 mod transaction;
 mod unique_keys;
 
-pub(crate) use builder::{CashNoteBuilder, TransactionBuilder};
-pub(crate) use transaction::{Input, Output};
+pub(crate) use spend_condition::unix_time_now;
 
 pub use address::SpendAddress;
-pub use builder::UnsignedTransfer;
+pub use builder::{CashNoteBuilder, TransactionBuilder, UnsignedTransfer};
 pub use cashnote::CashNote;
 pub use hash::Hash;
+pub use multisig::{
+    MultisigKeyDeal, MultisigPubkeySet, MultisigSecretKeyShare, PartiallySignedTransfer,
+};
 pub use nano::NanoTokens;
 pub use signed_spend::{SignedSpend, Spend};
+pub use spend_condition::SpendCondition;
 pub use spend_reason::SpendReason;
-pub use transaction::Transaction;
+pub use transaction::{Input, Output, Transaction};
 pub use unique_keys::{DerivationIndex, DerivedSecretKey, MainPubkey, MainSecretKey, UniquePubkey};
 
 #[cfg(test)]
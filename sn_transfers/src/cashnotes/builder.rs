@@ -10,7 +10,7 @@ use super::{
     spend_reason::SpendReason,
     transaction::{Output, Transaction},
     CashNote, DerivationIndex, DerivedSecretKey, Input, MainPubkey, NanoTokens, SignedSpend, Spend,
-    UniquePubkey,
+    SpendCondition, UniquePubkey,
 };
 
 use crate::{Result, TransferError};
@@ -43,6 +43,7 @@ pub struct TransactionBuilder {
     outputs: Vec<Output>,
     input_details: BTreeMap<UniquePubkey, (Option<DerivedSecretKey>, InputSrcTx, DerivationIndex)>,
     output_details: BTreeMap<UniquePubkey, (MainPubkey, DerivationIndex)>,
+    input_preimages: BTreeMap<UniquePubkey, Vec<u8>>,
 }
 
 impl TransactionBuilder {
@@ -73,6 +74,12 @@ impl TransactionBuilder {
         self
     }
 
+    /// Add the preimage unlocking an input whose output is locked with the hash of it
+    pub fn add_input_preimage(mut self, unique_pubkey: UniquePubkey, preimage: Vec<u8>) -> Self {
+        self.input_preimages.insert(unique_pubkey, preimage);
+        self
+    }
+
     /// Add an output given the token, the MainPubkey and the DerivationIndex
     pub fn add_output(
         mut self,
@@ -90,6 +97,63 @@ impl TransactionBuilder {
         self
     }
 
+    /// Add an output whose spend has to meet the given condition
+    pub fn add_output_with_condition(
+        mut self,
+        token: NanoTokens,
+        main_pubkey: MainPubkey,
+        derivation_index: DerivationIndex,
+        condition: SpendCondition,
+    ) -> Self {
+        let unique_pubkey = main_pubkey.new_unique_pubkey(&derivation_index);
+
+        self.output_details
+            .insert(unique_pubkey, (main_pubkey, derivation_index));
+        let output = Output::with_condition(unique_pubkey, token.as_nano(), condition);
+        self.outputs.push(output);
+
+        self
+    }
+
+    /// Add the outputs of a vesting grant to the MainPubkey, given the token, the unlock time
+    /// (in seconds since the Unix epoch) and the DerivationIndex of each tranche
+    pub fn add_vesting_outputs(
+        mut self,
+        main_pubkey: MainPubkey,
+        tranches: impl IntoIterator<Item = (NanoTokens, u64, DerivationIndex)>,
+    ) -> Self {
+        for (token, unlock_time, derivation_index) in tranches.into_iter() {
+            self = self.add_output_with_condition(
+                token,
+                main_pubkey,
+                derivation_index,
+                SpendCondition::NotBefore(unlock_time),
+            );
+        }
+        self
+    }
+
+    /// Add an output held in escrow for the MainPubkey, which can also be spent with the refund key
+    /// once the timeout (in seconds since the Unix epoch) has passed
+    pub fn add_refundable_output(
+        self,
+        token: NanoTokens,
+        main_pubkey: MainPubkey,
+        derivation_index: DerivationIndex,
+        timeout: u64,
+        refund_key: UniquePubkey,
+    ) -> Self {
+        self.add_output_with_condition(
+            token,
+            main_pubkey,
+            derivation_index,
+            SpendCondition::RefundAfter {
+                timeout,
+                refund_key,
+            },
+        )
+    }
+
     /// Add a list of outputs given the tokens, the MainPubkey and the DerivationIndex
     pub fn add_outputs(
         mut self,
@@ -123,6 +187,7 @@ impl TransactionBuilder {
                     amount: input.amount,
                     parent_tx: input_src_tx.clone(),
                     network_royalties: network_royalties.clone(),
+                    preimage: self.input_preimages.get(&input.unique_pubkey).cloned(),
                 };
                 let derived_key_sig = derived_key.sign(&spend.to_bytes_for_signing());
                 signed_spends.insert(SignedSpend {
@@ -158,6 +223,7 @@ impl TransactionBuilder {
                     amount: input.amount,
                     parent_tx: input_src_tx.clone(),
                     network_royalties: network_royalties.clone(),
+                    preimage: self.input_preimages.get(&input.unique_pubkey).cloned(),
                 };
                 spends.insert((spend, *derivation_index));
             }
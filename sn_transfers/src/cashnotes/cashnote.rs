@@ -8,7 +8,7 @@
 
 use super::{
     DerivationIndex, DerivedSecretKey, Hash, MainPubkey, MainSecretKey, NanoTokens, SignedSpend,
-    Transaction, UniquePubkey,
+    SpendCondition, Transaction, UniquePubkey,
 };
 
 use crate::{Result, TransferError};
@@ -121,6 +121,15 @@ impl CashNote {
             .amount)
     }
 
+    /// Return the condition its spend has to meet, if any.
+    pub fn spend_condition(&self) -> Option<&SpendCondition> {
+        self.parent_tx
+            .outputs
+            .iter()
+            .find(|o| &self.unique_pubkey() == o.unique_pubkey())
+            .and_then(|o| o.condition.as_ref())
+    }
+
     /// Generate the hash of this CashNote
     pub fn hash(&self) -> Hash {
         let mut sha3 = Sha3::v256();
@@ -0,0 +1,497 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{
+    DerivationIndex, MainPubkey, MainSecretKey, SignedSpend, Spend, UniquePubkey, UnsignedTransfer,
+};
+use crate::{Result, Signature, TransferError};
+
+use bls::{
+    group::ff::Field,
+    poly::{Commitment, Poly},
+    Ciphertext, Fr, PublicKeySet, SecretKeyShare, SignatureShare, SK_SIZE,
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The public keys of a treasury shared by several members, M of which have to sign to spend its funds.
+///
+/// The treasury receives tokens at its MainPubkey like any other wallet: the outputs paid to it
+/// are derived from the master key of the set. A spend of such an output is signed with a
+/// signature combined from the signature shares of M members, which verifies against the
+/// UniquePubkey of the output as any other signature. Thus the spentbook nodes validate them
+/// without knowing they come from a treasury.
+///
+/// The keys are generated by the members together, see [`MultisigKeyDeal`], so that the secret
+/// key of the treasury is never known to anyone, and each key share only to its member.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigPubkeySet(PublicKeySet);
+
+impl MultisigPubkeySet {
+    /// Combine the deals of all the members of a new treasury into its keys, and the key share
+    /// of the member owning `member_key`. Every member gets the same key set out of the same deals.
+    ///
+    /// The members are identified by their MainPubkey, in the same order for all of them.
+    /// A deal that doesn't match the member's value is rejected, and the generation has to be
+    /// started over, without the dealer if need be.
+    pub fn from_deals(
+        required_signatures: usize,
+        members: &[MainPubkey],
+        member_key: &MainSecretKey,
+        deals: &[MultisigKeyDeal],
+    ) -> Result<(Self, MultisigSecretKeyShare)> {
+        check_required_signatures(required_signatures, members.len())?;
+        let index = member_index(members, &member_key.main_pubkey())?;
+        let dealers: BTreeSet<u64> = deals.iter().map(|deal| deal.dealer).collect();
+        if dealers.len() != deals.len() || dealers != (0..members.len() as u64).collect() {
+            return Err(TransferError::InvalidMultisigKey(format!(
+                "expected one deal from each of the {} members, got the ones of {dealers:?}",
+                members.len()
+            )));
+        }
+
+        let mut commitment = Poly::zero().commitment();
+        let mut value = Fr::zero();
+        for deal in deals {
+            if deal.commitment.degree() != required_signatures - 1 {
+                return Err(TransferError::InvalidMultisigKey(format!(
+                    "the deal of member {} is not for {required_signatures} required signatures",
+                    deal.dealer
+                )));
+            }
+            let dealt_value: Fr = deal
+                .encrypted_values
+                .get(index as usize)
+                .and_then(|ciphertext| member_key.secret_key().decrypt(ciphertext))
+                .and_then(|bytes| <[u8; SK_SIZE]>::try_from(bytes).ok())
+                .and_then(|bytes| Option::from(Fr::from_bytes_be(&bytes)))
+                .ok_or_else(|| {
+                    TransferError::InvalidMultisigKey(format!(
+                        "the deal of member {} has no value for member {index}",
+                        deal.dealer
+                    ))
+                })?;
+            let expected = PublicKeySet::from(deal.commitment.clone()).public_key_share(index);
+            if SecretKeyShare::from_mut(&mut dealt_value.clone()).public_key_share() != expected {
+                return Err(TransferError::InvalidMultisigKey(format!(
+                    "the value dealt by member {} to member {index} doesn't match its commitment",
+                    deal.dealer
+                )));
+            }
+            commitment += &deal.commitment;
+            value += dealt_value;
+        }
+
+        let key_share = MultisigSecretKeyShare {
+            index,
+            share: SecretKeyShare::from_mut(&mut value),
+        };
+        Ok((Self(PublicKeySet::from(commitment)), key_share))
+    }
+
+    /// The address of the treasury, to which tokens are sent.
+    pub fn main_pubkey(&self) -> MainPubkey {
+        MainPubkey::new(self.0.public_key())
+    }
+
+    /// The number of members who have to sign a spend.
+    pub fn required_signatures(&self) -> usize {
+        self.0.threshold() + 1
+    }
+
+    /// Whether the key share is the one of a member of the treasury.
+    pub fn is_member(&self, key_share: &MultisigSecretKeyShare) -> bool {
+        self.0.public_key_share(key_share.index) == key_share.share.public_key_share()
+    }
+
+    /// Combine the signature shares of the members over a spend of the output derived with the
+    /// given index, into the signature of that output's DerivedSecretKey.
+    pub fn combine_signatures(
+        &self,
+        spend: &Spend,
+        index: &DerivationIndex,
+        shares: &BTreeMap<u64, SignatureShare>,
+    ) -> Result<Signature> {
+        let required = self.required_signatures();
+        if shares.len() < required {
+            return Err(TransferError::NotEnoughSignatureShares {
+                unique_pubkey: spend.unique_pubkey,
+                got: shares.len(),
+                required,
+            });
+        }
+
+        let derived_set = self.0.derive_child(&index.0);
+        let msg = spend.to_bytes_for_signing();
+        for (member, share) in shares {
+            if !derived_set.public_key_share(*member).verify(share, &msg) {
+                return Err(TransferError::InvalidSignatureShare(
+                    spend.unique_pubkey,
+                    *member,
+                ));
+            }
+        }
+        let signature = derived_set
+            .combine_signatures(shares.iter().map(|(member, share)| (*member, share)))?;
+
+        if !spend.unique_pubkey.verify(&signature, &msg) {
+            return Err(TransferError::InvalidSpendSignature(spend.unique_pubkey));
+        }
+        Ok(signature)
+    }
+
+    pub fn to_hex(&self) -> Result<String> {
+        let bytes = rmp_serde::to_vec(&self.0)
+            .map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        Ok(hex::encode(bytes))
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let bytes =
+            hex::decode(hex).map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        let key_set = rmp_serde::from_slice(&bytes)
+            .map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        Ok(Self(key_set))
+    }
+}
+
+/// The deal of a member of a new treasury to all its members, to generate the treasury keys.
+///
+/// Each member deals a random polynomial, of which every member gets the value at its index,
+/// encrypted to the member's MainPubkey, while the commitment to the polynomial is public.
+/// The key share of a member is the sum of the values dealt to it, and the key set of the
+/// treasury the sum of the commitments, see [`MultisigPubkeySet::from_deals`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MultisigKeyDeal {
+    dealer: u64,
+    commitment: Commitment,
+    /// The value for each member, by index of the member.
+    encrypted_values: Vec<Ciphertext>,
+}
+
+impl MultisigKeyDeal {
+    /// Deal a random polynomial to the members of a new treasury, `required_signatures` of whom
+    /// will have to sign to spend its funds, the dealer being the member owning `dealer_key`.
+    pub fn new(
+        required_signatures: usize,
+        members: &[MainPubkey],
+        dealer_key: &MainSecretKey,
+        rng: &mut impl RngCore,
+    ) -> Result<Self> {
+        check_required_signatures(required_signatures, members.len())?;
+        let dealer = member_index(members, &dealer_key.main_pubkey())?;
+
+        // a polynomial of degree t needs t + 1 shares to sign
+        let poly = Poly::random(required_signatures - 1, rng);
+        let encrypted_values = members
+            .iter()
+            .enumerate()
+            .map(|(index, member)| {
+                // the share of index i is the value at i + 1, the one at 0 being the secret
+                let value = poly.evaluate(index as u64 + 1);
+                member
+                    .public_key()
+                    .encrypt_with_rng(rng, value.to_bytes_be())
+            })
+            .collect();
+        Ok(Self {
+            dealer,
+            commitment: poly.commitment(),
+            encrypted_values,
+        })
+    }
+
+    /// The index of the member who made the deal.
+    pub fn dealer(&self) -> u64 {
+        self.dealer
+    }
+
+    pub fn to_hex(&self) -> Result<String> {
+        let bytes = rmp_serde::to_vec(self)
+            .map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        Ok(hex::encode(bytes))
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let bytes =
+            hex::decode(hex).map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        rmp_serde::from_slice(&bytes)
+            .map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))
+    }
+}
+
+// Private helper
+fn check_required_signatures(required_signatures: usize, members: usize) -> Result<()> {
+    if required_signatures == 0 || required_signatures > members {
+        return Err(TransferError::InvalidMultisigKey(format!(
+            "{required_signatures} signatures cannot be required out of {members} members"
+        )));
+    }
+    Ok(())
+}
+
+// Private helper
+fn member_index(members: &[MainPubkey], member: &MainPubkey) -> Result<u64> {
+    members
+        .iter()
+        .position(|m| m == member)
+        .map(|index| index as u64)
+        .ok_or_else(|| {
+            TransferError::InvalidMultisigKey(format!("{member:?} is not one of the members"))
+        })
+}
+
+/// The secret key share of a member of a treasury, held privately by that member.
+pub struct MultisigSecretKeyShare {
+    index: u64,
+    share: SecretKeyShare,
+}
+
+impl MultisigSecretKeyShare {
+    /// The index of the member within the treasury.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Sign a spend of the output derived with the given index.
+    pub fn sign(&self, spend: &Spend, index: &DerivationIndex) -> SignatureShare {
+        self.share
+            .derive_child(&index.0)
+            .sign(spend.to_bytes_for_signing())
+    }
+
+    /// Represent as hex, the index of the member followed by the key share.
+    pub fn to_hex(&self) -> String {
+        let mut bytes = self.index.to_be_bytes().to_vec();
+        bytes.extend(self.share.to_bytes());
+        hex::encode(bytes)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let bytes =
+            hex::decode(hex).map_err(|err| TransferError::InvalidMultisigKey(err.to_string()))?;
+        if bytes.len() != 8 + SK_SIZE {
+            return Err(TransferError::InvalidMultisigKey(format!(
+                "expected {} bytes, got {}",
+                8 + SK_SIZE,
+                bytes.len()
+            )));
+        }
+        let (index, share) = bytes.split_at(8);
+        let mut index_bytes = [0u8; 8];
+        index_bytes.copy_from_slice(index);
+        let mut share_bytes = [0u8; SK_SIZE];
+        share_bytes.copy_from_slice(share);
+        Ok(Self {
+            index: u64::from_be_bytes(index_bytes),
+            share: SecretKeyShare::from_bytes(share_bytes)?,
+        })
+    }
+}
+
+/// An unsigned transfer from a treasury, collecting the signature shares of its members
+/// until enough of them have signed it.
+#[derive(custom_debug::Debug, Clone, Serialize, Deserialize)]
+pub struct PartiallySignedTransfer {
+    /// The transfer built by a watch-only wallet of the treasury.
+    pub unsigned_transfer: UnsignedTransfer,
+    /// The keys of the treasury the spent cash_notes belong to.
+    pub key_set: MultisigPubkeySet,
+    /// The signature shares collected for each spend, by index of the member.
+    #[debug(skip)]
+    pub signature_shares: BTreeMap<UniquePubkey, BTreeMap<u64, SignatureShare>>,
+}
+
+impl PartiallySignedTransfer {
+    /// Start collecting signatures for the transfer, whose spends all have to be
+    /// of cash_notes owned by the treasury.
+    pub fn new(unsigned_transfer: UnsignedTransfer, key_set: MultisigPubkeySet) -> Result<Self> {
+        let main_pubkey = key_set.main_pubkey();
+        for (spend, index) in unsigned_transfer.spends.iter() {
+            if main_pubkey.new_unique_pubkey(index) != spend.unique_pubkey {
+                return Err(TransferError::MainPubkeyMismatch);
+            }
+        }
+        Ok(Self {
+            unsigned_transfer,
+            key_set,
+            signature_shares: BTreeMap::new(),
+        })
+    }
+
+    /// Add the signature shares of a member over all the spends of the transfer.
+    pub fn co_sign(&mut self, key_share: &MultisigSecretKeyShare) -> Result<()> {
+        if !self.key_set.is_member(key_share) {
+            return Err(TransferError::InvalidMultisigKey(format!(
+                "key share {} is not one of a member of the treasury",
+                key_share.index()
+            )));
+        }
+        for (spend, index) in self.unsigned_transfer.spends.iter() {
+            let _ = self
+                .signature_shares
+                .entry(spend.unique_pubkey)
+                .or_default()
+                .insert(key_share.index(), key_share.sign(spend, index));
+        }
+        Ok(())
+    }
+
+    /// The indexes of the members who signed all the spends of the transfer.
+    pub fn signers(&self) -> BTreeSet<u64> {
+        let mut per_spend = self.unsigned_transfer.spends.iter().map(|(spend, _)| {
+            self.signature_shares
+                .get(&spend.unique_pubkey)
+                .map(|shares| shares.keys().copied().collect::<BTreeSet<_>>())
+                .unwrap_or_default()
+        });
+        let first = per_spend.next().unwrap_or_default();
+        per_spend.fold(first, |signers, spend_signers| {
+            signers.intersection(&spend_signers).copied().collect()
+        })
+    }
+
+    /// The number of members who still have to sign the transfer.
+    pub fn missing_signatures(&self) -> usize {
+        self.key_set
+            .required_signatures()
+            .saturating_sub(self.signers().len())
+    }
+
+    /// Combine the collected signature shares into the signed spends of the transfer,
+    /// failing if not enough members have signed it yet.
+    pub fn signed_spends(&self) -> Result<BTreeSet<SignedSpend>> {
+        let no_shares = BTreeMap::new();
+        self.unsigned_transfer
+            .spends
+            .iter()
+            .map(|(spend, index)| {
+                let shares = self
+                    .signature_shares
+                    .get(&spend.unique_pubkey)
+                    .unwrap_or(&no_shares);
+                let derived_key_sig = self.key_set.combine_signatures(spend, index, shares)?;
+                Ok(SignedSpend {
+                    spend: spend.clone(),
+                    derived_key_sig,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NanoTokens, SpendReason, Transaction};
+
+    #[test]
+    fn combined_signature_verifies_against_the_unique_pubkey() -> Result<()> {
+        let mut rng = crate::rng::from_seed([0u8; 32]);
+        let (key_set, key_shares) = generate_keys(2, 3, &mut rng)?;
+        let index = DerivationIndex::random(&mut rng);
+        let spend = Spend {
+            unique_pubkey: key_set.main_pubkey().new_unique_pubkey(&index),
+            spent_tx: Transaction::empty(),
+            reason: SpendReason::default(),
+            amount: NanoTokens::from(100),
+            parent_tx: Transaction::empty(),
+            network_royalties: vec![],
+            preimage: None,
+        };
+
+        let mut shares = BTreeMap::new();
+        let _ = shares.insert(key_shares[2].index(), key_shares[2].sign(&spend, &index));
+        assert!(matches!(
+            key_set.combine_signatures(&spend, &index, &shares),
+            Err(TransferError::NotEnoughSignatureShares { got: 1, .. })
+        ));
+
+        let _ = shares.insert(key_shares[0].index(), key_shares[0].sign(&spend, &index));
+        let signature = key_set.combine_signatures(&spend, &index, &shares)?;
+        assert!(spend
+            .unique_pubkey
+            .verify(&signature, spend.to_bytes_for_signing()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_or_missing_deals_are_rejected() -> Result<()> {
+        let mut rng = crate::rng::from_seed([0u8; 32]);
+        let member_keys: Vec<_> = (0..3)
+            .map(|_| MainSecretKey::random_from_rng(&mut rng))
+            .collect();
+        let members: Vec<_> = member_keys.iter().map(|sk| sk.main_pubkey()).collect();
+        let mut deals = member_keys
+            .iter()
+            .map(|sk| MultisigKeyDeal::new(2, &members, sk, &mut rng))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert!(MultisigKeyDeal::new(4, &members, &member_keys[0], &mut rng).is_err());
+        assert!(MultisigKeyDeal::new(
+            2,
+            &members,
+            &MainSecretKey::random_from_rng(&mut rng),
+            &mut rng
+        )
+        .is_err());
+
+        // a value not matching the commitment of its dealer
+        let mut tampered = deals.clone();
+        tampered[1].encrypted_values[0] = tampered[2].encrypted_values[0].clone();
+        assert!(MultisigPubkeySet::from_deals(2, &members, &member_keys[0], &tampered).is_err());
+
+        // a deal for another threshold
+        let mut tampered = deals.clone();
+        tampered[1] = MultisigKeyDeal::new(3, &members, &member_keys[1], &mut rng)?;
+        assert!(MultisigPubkeySet::from_deals(2, &members, &member_keys[0], &tampered).is_err());
+
+        // a missing, or duplicated, deal
+        let _ = deals.pop();
+        assert!(MultisigPubkeySet::from_deals(2, &members, &member_keys[0], &deals).is_err());
+        deals.push(deals[1].clone());
+        assert!(MultisigPubkeySet::from_deals(2, &members, &member_keys[0], &deals).is_err());
+
+        Ok(())
+    }
+
+    // Private helper: every member deals, then combines all the deals into its own key share.
+    fn generate_keys(
+        required_signatures: usize,
+        members: usize,
+        rng: &mut impl RngCore,
+    ) -> Result<(MultisigPubkeySet, Vec<MultisigSecretKeyShare>)> {
+        let member_keys: Vec<_> = (0..members)
+            .map(|_| MainSecretKey::random_from_rng(rng))
+            .collect();
+        let members: Vec<_> = member_keys.iter().map(|sk| sk.main_pubkey()).collect();
+        let deals = member_keys
+            .iter()
+            .map(|sk| MultisigKeyDeal::new(required_signatures, &members, sk, rng))
+            .map(|deal| deal.and_then(|deal| MultisigKeyDeal::from_hex(&deal.to_hex()?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut key_sets = BTreeSet::new();
+        let mut key_shares = vec![];
+        for (index, sk) in member_keys.iter().enumerate() {
+            let (key_set, key_share) =
+                MultisigPubkeySet::from_deals(required_signatures, &members, sk, &deals)?;
+            assert_eq!(key_share.index(), index as u64);
+            assert!(key_set.is_member(&key_share));
+            let _ = key_sets.insert(key_set.to_hex()?);
+            key_shares.push(key_share);
+        }
+        assert_eq!(key_sets.len(), 1);
+
+        let key_set =
+            MultisigPubkeySet::from_hex(&key_sets.into_iter().next().unwrap_or_default())?;
+        Ok((key_set, key_shares))
+    }
+}
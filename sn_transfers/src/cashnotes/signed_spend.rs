@@ -8,7 +8,9 @@
 
 use super::spend_reason::SpendReason;
 use super::{Hash, NanoTokens, Transaction, UniquePubkey};
-use crate::{DerivationIndex, Result, Signature, SpendAddress, TransferError};
+use crate::{
+    cashnotes::unix_time_now, DerivationIndex, Result, Signature, SpendAddress, TransferError,
+};
 
 use custom_debug::Debug;
 use serde::{Deserialize, Serialize};
@@ -76,6 +78,7 @@ impl SignedSpend {
     /// - it was signed by the DerivedSecretKey that owns the CashNote for this Spend
     /// - the signature is valid
     /// - its value didn't change between the two transactions it is involved in (creation and spending)
+    /// - it meets the spend condition of its output, if any, at the current time
     ///
     /// It does NOT check:
     /// - if the spend exists on the Network
@@ -116,14 +119,13 @@ impl SignedSpend {
 
         // check that the value of the spend wasn't tampered with
         let claimed_value = self.spend.amount;
-        let creation_value = self
+        let output = self
             .spend
             .parent_tx
             .outputs
             .iter()
-            .find(|o| o.unique_pubkey == self.spend.unique_pubkey)
-            .map(|o| o.amount)
-            .unwrap_or(NanoTokens::zero());
+            .find(|o| o.unique_pubkey == self.spend.unique_pubkey);
+        let creation_value = output.map(|o| o.amount).unwrap_or(NanoTokens::zero());
         let spent_value = self
             .spend
             .spent_tx
@@ -136,6 +138,11 @@ impl SignedSpend {
             return Err(TransferError::InvalidSpendValue(*self.unique_pubkey()));
         }
 
+        // check the condition of the output, which also decides the keys it can be signed with
+        if let Some(condition) = output.and_then(|o| o.condition.as_ref()) {
+            return condition.verify(&self.spend, &self.derived_key_sig, unix_time_now());
+        }
+
         // check signature
         // the spend is signed by the DerivedSecretKey
         // corresponding to the UniquePubkey of the CashNote being spent.
@@ -254,6 +261,10 @@ pub struct Spend {
     /// Data to claim the Network Royalties (if any) from the Spend's descendants (outputs in spent_tx)
     #[debug(skip)]
     pub network_royalties: Vec<DerivationIndex>,
+    /// The secret unlocking the input CashNote, when its output is locked with the hash of it
+    #[debug(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<Vec<u8>>,
 }
 
 impl Spend {
@@ -266,6 +277,9 @@ impl Spend {
         bytes.extend(self.reason.hash().as_ref());
         bytes.extend(self.amount.to_bytes());
         bytes.extend(self.parent_tx.hash().as_ref());
+        if let Some(preimage) = &self.preimage {
+            bytes.extend(preimage);
+        }
         bytes
     }
 
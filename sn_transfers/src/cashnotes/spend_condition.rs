@@ -0,0 +1,175 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Hash, Spend, UniquePubkey};
+use crate::{Result, Signature, TransferError};
+
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// How far behind the clocks of the nodes validating a spend can be, in seconds, for them to still
+/// agree on a time condition that has been met when the spend was made.
+pub(crate) const CLOCK_SKEW_MARGIN: u64 = 5 * 60;
+
+/// A condition to be met by the spend of an output, on top of being signed by the key of the output.
+///
+/// Times are in seconds since the Unix epoch, as the nodes see them when validating the spend,
+/// give or take the `CLOCK_SKEW_MARGIN`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SpendCondition {
+    /// The output cannot be spent before this time, e.g. the tranche of a vesting grant.
+    NotBefore(u64),
+    /// The spend has to reveal the preimage of this hash.
+    HashLock(Hash),
+    /// Once the timeout has passed, the output can also be spent with the refund key,
+    /// e.g. to give an escrow back to its sender.
+    RefundAfter {
+        timeout: u64,
+        refund_key: UniquePubkey,
+    },
+}
+
+impl SpendCondition {
+    /// Represent as bytes, for the hash of the transaction of the output.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Default::default();
+        match self {
+            Self::NotBefore(unlock_time) => {
+                bytes.extend("not_before".as_bytes());
+                bytes.extend(unlock_time.to_be_bytes());
+            }
+            Self::HashLock(hash) => {
+                bytes.extend("hash_lock".as_bytes());
+                bytes.extend(hash.as_ref());
+            }
+            Self::RefundAfter {
+                timeout,
+                refund_key,
+            } => {
+                bytes.extend("refund_after".as_bytes());
+                bytes.extend(timeout.to_be_bytes());
+                bytes.extend(refund_key.to_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Whether the owner of the output can spend it at the given time with its key alone.
+    pub fn is_unlocked_for_owner(&self, now: u64) -> bool {
+        match self {
+            Self::NotBefore(unlock_time) => now >= *unlock_time,
+            Self::HashLock(_) => false,
+            Self::RefundAfter { .. } => true,
+        }
+    }
+
+    /// Verify that the spend meets the condition at the given time, and that it is signed
+    /// by a key allowed to spend the output.
+    ///
+    /// The time conditions are accepted up to the `CLOCK_SKEW_MARGIN` early, so that a spend made
+    /// once they were met is not rejected by the nodes whose clocks are a bit behind.
+    pub(crate) fn verify(&self, spend: &Spend, signature: &Signature, now: u64) -> Result<()> {
+        let now = now.saturating_add(CLOCK_SKEW_MARGIN);
+        let unique_pubkey = spend.unique_pubkey;
+        let msg = spend.to_bytes_for_signing();
+        let signed_by_owner = unique_pubkey.verify(signature, &msg);
+
+        match self {
+            Self::NotBefore(unlock_time) => {
+                if now < *unlock_time {
+                    return Err(TransferError::SpendConditionNotMet(
+                        unique_pubkey,
+                        format!("it cannot be spent before {unlock_time}"),
+                    ));
+                }
+            }
+            Self::HashLock(hash) => match &spend.preimage {
+                Some(preimage) if Hash::hash(preimage) == *hash => {}
+                _ => {
+                    return Err(TransferError::SpendConditionNotMet(
+                        unique_pubkey,
+                        format!("the preimage of {hash:?} is missing or wrong"),
+                    ))
+                }
+            },
+            Self::RefundAfter {
+                timeout,
+                refund_key,
+            } => {
+                if !signed_by_owner && now >= *timeout && refund_key.verify(signature, &msg) {
+                    return Ok(());
+                }
+            }
+        }
+
+        if signed_by_owner {
+            Ok(())
+        } else {
+            Err(TransferError::InvalidSpendSignature(unique_pubkey))
+        }
+    }
+}
+
+/// The current time, in seconds since the Unix epoch.
+pub(crate) fn unix_time_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DerivationIndex, MainSecretKey, NanoTokens, SpendReason, Transaction};
+
+    #[test]
+    fn conditions_are_enforced_on_the_spend() -> Result<()> {
+        let mut rng = crate::rng::from_seed([0u8; 32]);
+        let owner_key =
+            MainSecretKey::random_from_rng(&mut rng).derive_key(&DerivationIndex::random(&mut rng));
+        let refund_key =
+            MainSecretKey::random_from_rng(&mut rng).derive_key(&DerivationIndex::random(&mut rng));
+        let mut spend = Spend {
+            unique_pubkey: owner_key.unique_pubkey(),
+            spent_tx: Transaction::empty(),
+            reason: SpendReason::default(),
+            amount: NanoTokens::from(100),
+            parent_tx: Transaction::empty(),
+            network_royalties: vec![],
+            preimage: None,
+        };
+
+        let not_before = SpendCondition::NotBefore(1000);
+        let sig = owner_key.sign(&spend.to_bytes_for_signing());
+        assert!(not_before
+            .verify(&spend, &sig, 999 - CLOCK_SKEW_MARGIN)
+            .is_err());
+        not_before.verify(&spend, &sig, 1000 - CLOCK_SKEW_MARGIN)?;
+
+        let hash_lock = SpendCondition::HashLock(Hash::hash(b"secret"));
+        assert!(hash_lock.verify(&spend, &sig, 0).is_err());
+        spend.preimage = Some(b"secret".to_vec());
+        let sig = owner_key.sign(&spend.to_bytes_for_signing());
+        hash_lock.verify(&spend, &sig, 0)?;
+
+        let refund_after = SpendCondition::RefundAfter {
+            timeout: 1000,
+            refund_key: refund_key.unique_pubkey(),
+        };
+        refund_after.verify(&spend, &sig, 0)?;
+        let refund_sig = refund_key.sign(&spend.to_bytes_for_signing());
+        assert!(matches!(
+            refund_after.verify(&spend, &refund_sig, 999 - CLOCK_SKEW_MARGIN),
+            Err(TransferError::InvalidSpendSignature(_))
+        ));
+        refund_after.verify(&spend, &refund_sig, 1000 - CLOCK_SKEW_MARGIN)?;
+
+        Ok(())
+    }
+}
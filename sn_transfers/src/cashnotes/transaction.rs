@@ -4,7 +4,7 @@
 // This SAFE Network Software is licensed under the BSD-3-Clause license.
 // Please see the LICENSE file for more details.
 
-use super::{NanoTokens, SignedSpend, UniquePubkey};
+use super::{NanoTokens, SignedSpend, SpendCondition, UniquePubkey};
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::BTreeSet};
 use tiny_keccak::{Hasher, Sha3};
@@ -43,6 +43,9 @@ impl Input {
 pub struct Output {
     pub unique_pubkey: UniquePubkey,
     pub amount: NanoTokens,
+    /// The condition to be met by the spend of this output, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<SpendCondition>,
 }
 
 impl Output {
@@ -50,6 +53,19 @@ impl Output {
         Self {
             unique_pubkey,
             amount: NanoTokens::from(amount),
+            condition: None,
+        }
+    }
+
+    /// Create an output whose spend has to meet the condition.
+    pub fn with_condition(
+        unique_pubkey: UniquePubkey,
+        amount: u64,
+        condition: SpendCondition,
+    ) -> Self {
+        Self {
+            condition: Some(condition),
+            ..Self::new(unique_pubkey, amount)
         }
     }
 
@@ -57,6 +73,10 @@ impl Output {
         let mut v: Vec<u8> = Default::default();
         v.extend(self.unique_pubkey.to_bytes().as_ref());
         v.extend(self.amount.to_bytes());
+        // outputs without condition keep the bytes, thus the tx hashes, they always had
+        if let Some(condition) = &self.condition {
+            v.extend(condition.to_bytes());
+        }
         v
     }
 
@@ -10,8 +10,10 @@ use crate::rand::{distributions::Standard, Rng, RngCore};
 use crate::wallet::{Error, Result};
 
 use bls::{serde_impl::SerdeSecret, PublicKey, SecretKey, PK_SIZE};
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use sha2::Sha256;
+use std::{collections::BTreeSet, fmt};
 
 /// This is used to generate a new UniquePubkey
 /// from a MainPubkey, and the corresponding
@@ -36,6 +38,34 @@ impl DerivationIndex {
         rng.fill_bytes(&mut bytes);
         DerivationIndex(bytes)
     }
+
+    /// The derivation index of the change output of a transaction spending the given inputs,
+    /// going back to the owner of `owner`.
+    ///
+    /// Unlike a random one, it can be found again by the owner from the inputs of the transaction,
+    /// to recover its CashNotes from the Network. It is keyed with a secret derived from the
+    /// MainSecretKey, so the change outputs can't be told apart by anyone else, even knowing
+    /// the MainPubkey of the owner.
+    pub fn for_change<'a>(
+        owner: &MainSecretKey,
+        inputs: impl IntoIterator<Item = &'a UniquePubkey>,
+    ) -> DerivationIndex {
+        let inputs: BTreeSet<_> = inputs.into_iter().collect();
+        let mut mac = new_mac(&owner.change_derivation_secret());
+        for input in inputs {
+            mac.update(&input.to_bytes());
+        }
+        DerivationIndex(mac.finalize().into_bytes().into())
+    }
+}
+
+/// Domain separating the secret keying the derivation indexes of the change outputs.
+const CHANGE_DERIVATION_DOMAIN: &[u8] = b"safe_network change derivation secret";
+
+// Private helper
+fn new_mac(key: &[u8]) -> Hmac<Sha256> {
+    // HMAC can take a key of any size
+    <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC to accept keys of any size")
 }
 
 /// A Unique Public Key is the unique identifier of a CashNote and its SignedSpend on the Network when it is spent.
@@ -277,6 +307,14 @@ impl MainSecretKey {
         self.0.sign(msg)
     }
 
+    /// The secret keying the derivation indexes of the change outputs going back to this key.
+    /// See [`DerivationIndex::for_change`].
+    fn change_derivation_secret(&self) -> [u8; 32] {
+        let mut mac = new_mac(&self.0.to_bytes());
+        mac.update(CHANGE_DERIVATION_DOMAIN);
+        mac.finalize().into_bytes().into()
+    }
+
     /// Derive the key - the DerivedSecretKey - corresponding to a UniquePubkey
     /// which was also derived using the same DerivationIndex.
     ///
@@ -370,4 +408,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_change_derivation_index_is_keyed_by_the_owner_secret() {
+        let owner = MainSecretKey::random();
+        let other = MainSecretKey::random();
+        let inputs: Vec<_> = (0..3)
+            .map(|_| UniquePubkey::new(bls::SecretKey::random().public_key()))
+            .collect();
+
+        // the order of the inputs doesn't matter
+        let index = DerivationIndex::for_change(&owner, &inputs);
+        assert_eq!(
+            index,
+            DerivationIndex::for_change(&owner, inputs.iter().rev())
+        );
+
+        assert_ne!(index, DerivationIndex::for_change(&other, &inputs));
+        assert_ne!(index, DerivationIndex::for_change(&owner, &inputs[1..]));
+    }
 }
@@ -33,6 +33,8 @@ pub enum TransferError {
     InvalidParentSpend(String),
     #[error("Invalid Spend Signature for {0:?}")]
     InvalidSpendSignature(UniquePubkey),
+    #[error("Spend condition of {0:?} is not met: {1}")]
+    SpendConditionNotMet(UniquePubkey, String),
     #[error("Transaction hash is different from the hash in the the Spend: {0:?} != {1:?}")]
     TransactionHashMismatch(Hash, Hash),
     #[error("CashNote ciphers are not present in transaction outputs.")]
@@ -83,6 +85,16 @@ pub enum TransferError {
     TransferSerializationFailed,
     #[error("Transfer deserialisation failed")]
     TransferDeserializationFailed,
+    #[error("Invalid multisig key: {0}")]
+    InvalidMultisigKey(String),
+    #[error("Not enough signature shares for {unique_pubkey:?}: got {got}, {required} required")]
+    NotEnoughSignatureShares {
+        unique_pubkey: UniquePubkey,
+        got: usize,
+        required: usize,
+    },
+    #[error("Invalid signature share from member {1} for {0:?}")]
+    InvalidSignatureShare(UniquePubkey, u64),
 
     #[error("Bls error: {0}")]
     Blsttc(#[from] bls::error::Error),
@@ -102,6 +102,7 @@ lazy_static! {
         tx.outputs = vec![Output {
             unique_pubkey: *GENESIS_SPEND_UNIQUE_KEY,
             amount: NanoTokens::from(GENESIS_CASHNOTE_AMOUNT),
+            condition: None,
         }];
         tx
     };
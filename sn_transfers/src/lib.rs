@@ -15,12 +15,12 @@ mod genesis;
 mod transfers;
 mod wallet;
 
-pub(crate) use cashnotes::{Input, Output, TransactionBuilder};
-
 /// Types used in the public API
 pub use cashnotes::{
-    CashNote, DerivationIndex, DerivedSecretKey, Hash, MainPubkey, MainSecretKey, NanoTokens,
-    SignedSpend, Spend, SpendAddress, SpendReason, Transaction, UniquePubkey, UnsignedTransfer,
+    CashNote, CashNoteBuilder, DerivationIndex, DerivedSecretKey, Hash, Input, MainPubkey,
+    MainSecretKey, MultisigKeyDeal, MultisigPubkeySet, MultisigSecretKeyShare, NanoTokens, Output,
+    PartiallySignedTransfer, SignedSpend, Spend, SpendAddress, SpendCondition, SpendReason,
+    Transaction, TransactionBuilder, UniquePubkey, UnsignedTransfer,
 };
 pub use error::{Result, TransferError};
 /// Utilities exposed
@@ -7,10 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    cashnotes::{CashNoteBuilder, UnsignedTransfer},
-    rng, CashNote, DerivationIndex, DerivedSecretKey, Input, MainPubkey, NanoTokens, Result,
-    SignedSpend, SpendReason, Transaction, TransactionBuilder, TransferError, UniquePubkey,
-    NETWORK_ROYALTIES_PK,
+    cashnotes::{unix_time_now, CashNoteBuilder, UnsignedTransfer},
+    rng, CashNote, DerivationIndex, DerivedSecretKey, Input, MainPubkey, MainSecretKey, NanoTokens,
+    Result, SignedSpend, SpendCondition, SpendReason, Transaction, TransactionBuilder,
+    TransferError, UniquePubkey, NETWORK_ROYALTIES_PK,
 };
 
 use serde::{Deserialize, Serialize};
@@ -92,42 +92,103 @@ impl OfflineTransfer {
         change_to: MainPubkey,
         input_reason_hash: SpendReason,
     ) -> Result<Self> {
-        let total_output_amount = recipients
-            .iter()
-            .try_fold(NanoTokens::zero(), |total, (amount, _, _)| {
-                total.checked_add(*amount)
-            })
-            .ok_or_else(|| {
-                TransferError::CashNoteReissueFailed(
-                    "Overflow occurred while summing the amounts for the recipients.".to_string(),
-                )
-            })?;
-
-        // We need to select the necessary number of cash_notes from those that we were passed.
-        let (cash_notes_to_spend, change_amount) =
-            select_inputs(available_cash_notes, total_output_amount)?;
+        create_offline_transfer(
+            available_cash_notes,
+            recipients,
+            BTreeMap::new(),
+            (change_to, None),
+            input_reason_hash,
+        )
+    }
 
-        let selected_inputs = TransferInputs {
-            cash_notes_to_spend,
+    /// Create an offline transfer, as with `OfflineTransfer::new`, whose change goes back to the
+    /// owner of `change_key`, with a derivation index it can find again to restore the change
+    /// from the Network should its wallet be lost. See [`DerivationIndex::for_change`].
+    pub fn new_with_restorable_change(
+        available_cash_notes: CashNotesAndSecretKey,
+        recipients: Vec<(NanoTokens, MainPubkey, DerivationIndex)>,
+        change_key: &MainSecretKey,
+        input_reason_hash: SpendReason,
+    ) -> Result<Self> {
+        Self::new_with_conditions(
+            available_cash_notes,
             recipients,
-            change: (change_amount, change_to),
-        };
+            BTreeMap::new(),
+            change_key,
+            input_reason_hash,
+        )
+    }
 
-        create_offline_transfer_with(selected_inputs, input_reason_hash)
+    /// Create an offline transfer, as with `OfflineTransfer::new_with_restorable_change`, whose
+    /// outputs to the recipients can be locked with a spend condition, by the UniquePubkey of the output.
+    pub fn new_with_conditions(
+        available_cash_notes: CashNotesAndSecretKey,
+        recipients: Vec<(NanoTokens, MainPubkey, DerivationIndex)>,
+        conditions: BTreeMap<UniquePubkey, SpendCondition>,
+        change_key: &MainSecretKey,
+        input_reason_hash: SpendReason,
+    ) -> Result<Self> {
+        create_offline_transfer(
+            available_cash_notes,
+            recipients,
+            conditions,
+            (change_key.main_pubkey(), Some(change_key)),
+            input_reason_hash,
+        )
     }
 }
 
+// Private helper
+fn create_offline_transfer(
+    available_cash_notes: CashNotesAndSecretKey,
+    recipients: Vec<(NanoTokens, MainPubkey, DerivationIndex)>,
+    conditions: BTreeMap<UniquePubkey, SpendCondition>,
+    (change_to, change_key): (MainPubkey, Option<&MainSecretKey>),
+    input_reason_hash: SpendReason,
+) -> Result<OfflineTransfer> {
+    let total_output_amount = recipients
+        .iter()
+        .try_fold(NanoTokens::zero(), |total, (amount, _, _)| {
+            total.checked_add(*amount)
+        })
+        .ok_or_else(|| {
+            TransferError::CashNoteReissueFailed(
+                "Overflow occurred while summing the amounts for the recipients.".to_string(),
+            )
+        })?;
+
+    // We need to select the necessary number of cash_notes from those that we were passed.
+    let (cash_notes_to_spend, change_amount) =
+        select_inputs(available_cash_notes, total_output_amount)?;
+
+    let selected_inputs = TransferInputs {
+        cash_notes_to_spend,
+        recipients,
+        conditions,
+        change: (change_amount, change_to),
+        change_key,
+    };
+
+    create_offline_transfer_with(selected_inputs, input_reason_hash)
+}
+
 /// The input details necessary to
 /// carry out a transfer of tokens.
-#[derive(Debug)]
-struct TransferInputs {
+#[derive(custom_debug::Debug)]
+struct TransferInputs<'a> {
     /// The selected cash_notes to spend, with the necessary amounts contained
     /// to transfer the below specified amount of tokens to each recipients.
     pub cash_notes_to_spend: CashNotesAndSecretKey,
     /// The amounts and cash_note ids for the cash_notes that will be created to hold the transferred tokens.
     pub recipients: Vec<(NanoTokens, MainPubkey, DerivationIndex)>,
+    /// The spend conditions of the outputs to the recipients, if any.
+    pub conditions: BTreeMap<UniquePubkey, SpendCondition>,
     /// Any surplus amount after spending the necessary input cash_notes.
     pub change: (NanoTokens, MainPubkey),
+    /// The key of the owner of the change, to derive a change that can be restored from the Network.
+    /// The change gets a random derivation index without it.
+    #[debug(skip)]
+    pub change_key: Option<&'a MainSecretKey>,
 }
 
 /// A function for creating an unsigned transfer of tokens.
@@ -151,7 +212,9 @@ pub fn create_unsigned_transfer(
     let selected_inputs = TransferInputs {
         cash_notes_to_spend,
         recipients,
+        conditions: BTreeMap::new(),
         change: (change_amount, change_to),
+        change_key: None,
     };
 
     // gather the network_royalties derivation indexes
@@ -176,10 +239,18 @@ fn select_inputs(
     let mut cash_notes_to_spend = Vec::new();
     let mut total_input_amount = NanoTokens::zero();
     let mut change_amount = total_output_amount;
+    let now = unix_time_now();
 
     for (cash_note, derived_key) in available_cash_notes {
         let input_key = cash_note.unique_pubkey();
 
+        if let Some(condition) = cash_note.spend_condition() {
+            if !condition.is_unlocked_for_owner(now) {
+                debug!("Skipping input CashNote (id: {input_key:?}) locked by {condition:?}");
+                continue;
+            }
+        }
+
         let cash_note_balance = match cash_note.value() {
             Ok(token) => token,
             Err(err) => {
@@ -238,6 +309,8 @@ fn create_transaction_builder_with(
 )> {
     let TransferInputs {
         change: (change, change_to),
+        change_key,
+        mut conditions,
         ..
     } = selected_inputs;
 
@@ -265,11 +338,24 @@ fn create_transaction_builder_with(
     }
 
     // Build the transaction and create change cash_note if needed
-    let mut tx_builder = TransactionBuilder::default()
-        .add_inputs(inputs)
-        .add_outputs(selected_inputs.recipients);
-    let mut rng = rng::thread_rng();
-    let derivation_index = DerivationIndex::random(&mut rng);
+    let mut tx_builder = TransactionBuilder::default().add_inputs(inputs);
+    for (token, main_pubkey, derivation_index) in selected_inputs.recipients {
+        let unique_pubkey = main_pubkey.new_unique_pubkey(&derivation_index);
+        tx_builder = match conditions.remove(&unique_pubkey) {
+            Some(condition) => tx_builder.add_output_with_condition(
+                token,
+                main_pubkey,
+                derivation_index,
+                condition,
+            ),
+            None => tx_builder.add_output(token, main_pubkey, derivation_index),
+        };
+    }
+    // the change can then be recovered from the Network, should the wallet be lost
+    let derivation_index = match change_key {
+        Some(change_key) => DerivationIndex::for_change(change_key, src_txs.keys()),
+        None => DerivationIndex::random(&mut rng::thread_rng()),
+    };
     let change_id = change_to.new_unique_pubkey(&derivation_index);
     if !change.is_zero() {
         tx_builder = tx_builder.add_output(change, change_to, derivation_index);
@@ -0,0 +1,108 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::error::{Error, Result};
+use super::keys::bls_secret_from_hex;
+use crate::MainSecretKey;
+use aes_gcm_siv::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256GcmSiv, Nonce,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use hex::{decode, encode};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// A main secret key encrypted with a key derived from a password, as stored on disk.
+///
+/// The key is derived with Argon2id, whose parameters are kept along with the ciphertext so
+/// that they can be raised later on without breaking the existing files.
+#[derive(Serialize, Deserialize)]
+pub(super) struct EncryptedSecretKey {
+    /// Hex-encoded AES-256-GCM-SIV ciphertext of the hex-encoded main secret key
+    ciphertext: String,
+    /// Hex-encoded salt of the key derivation
+    salt: String,
+    /// Hex-encoded nonce of the encryption
+    nonce: String,
+    /// Argon2 memory cost, in KiB
+    m_cost: u32,
+    /// Argon2 number of iterations
+    t_cost: u32,
+    /// Argon2 degree of parallelism
+    p_cost: u32,
+}
+
+impl EncryptedSecretKey {
+    /// Encrypts the main key with the password, using a fresh salt and nonce.
+    pub(super) fn encrypt(main_key: &MainSecretKey, password: &str) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let params = Params::default();
+
+        let cipher = derive_cipher(password, &salt, &params)?;
+        let plaintext = encode(main_key.to_bytes());
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+            .map_err(|err| Error::FailedToEncryptKey(err.to_string()))?;
+
+        Ok(Self {
+            ciphertext: encode(ciphertext),
+            salt: encode(salt),
+            nonce: encode(nonce),
+            m_cost: params.m_cost(),
+            t_cost: params.t_cost(),
+            p_cost: params.p_cost(),
+        })
+    }
+
+    /// Decrypts the main key, failing if the password is not the one it was encrypted with.
+    pub(super) fn decrypt(&self, password: &str) -> Result<MainSecretKey> {
+        let salt = decode(&self.salt).map_err(|_| Error::FailedToDecryptKey)?;
+        let nonce = decode(&self.nonce).map_err(|_| Error::FailedToDecryptKey)?;
+        let ciphertext = decode(&self.ciphertext).map_err(|_| Error::FailedToDecryptKey)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(Error::FailedToDecryptKey);
+        }
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, None)
+            .map_err(|_| Error::FailedToDecryptKey)?;
+
+        let cipher =
+            derive_cipher(password, &salt, &params).map_err(|_| Error::FailedToDecryptKey)?;
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| Error::FailedToDecryptKey)?;
+
+        Ok(MainSecretKey::new(bls_secret_from_hex(plaintext)?))
+    }
+
+    pub(super) fn from_file(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    pub(super) fn to_file(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+fn derive_cipher(password: &str, salt: &[u8], params: &Params) -> Result<Aes256GcmSiv> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone())
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|err| Error::FailedToEncryptKey(err.to_string()))?;
+    Aes256GcmSiv::new_from_slice(&key).map_err(|err| Error::FailedToEncryptKey(err.to_string()))
+}
@@ -58,6 +58,18 @@ pub enum Error {
     /// Main secret key not found when loading wallet from path
     #[error("Main secret key not found: {0:#?}")]
     MainSecretKeyNotFound(std::path::PathBuf),
+    /// Main secret key is encrypted and was not unlocked with its password
+    #[error("Main secret key is encrypted, a password is needed to unlock it: {0:#?}")]
+    EncryptedMainSecretKey(std::path::PathBuf),
+    /// Main secret key is already encrypted
+    #[error("Main secret key is already encrypted: {0:#?}")]
+    MainSecretKeyAlreadyEncrypted(std::path::PathBuf),
+    /// Failed to decrypt the main secret key
+    #[error("Could not decrypt main secret key, the password may be wrong")]
+    FailedToDecryptKey,
+    /// Failed to encrypt the main secret key
+    #[error("Could not encrypt main secret key: {0}")]
+    FailedToEncryptKey(String),
     /// Failed to parse bytes into a bls key
     #[error("Failed to parse bls key")]
     FailedToParseBlsKey,
@@ -95,6 +107,9 @@ pub enum Error {
     /// MsgPack deserialisation error
     #[error("MsgPack deserialisation error:: {0}")]
     Deserialisation(#[from] rmp_serde::decode::Error),
+    /// Encrypted key file (de)serialisation error
+    #[error("Encrypted key file (de)serialisation error: {0}")]
+    KeyFileSerialisation(#[from] serde_json::Error),
     /// I/O error
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -9,7 +9,11 @@
 use super::{
     api::{WalletApi, WALLET_DIR_NAME},
     data_payments::{PaymentDetails, PaymentQuote},
-    keys::{get_main_key_from_disk, store_new_keypair},
+    keys::{
+        change_main_key_password, encrypt_main_key_on_disk, get_main_key_from_disk,
+        get_main_pubkey, is_main_key_encrypted, store_new_encrypted_keypair, store_new_keypair,
+        unlock_main_key,
+    },
     wallet_file::{
         get_confirmed_spend, get_unconfirmed_spend_requests, load_created_cash_note,
         remove_cash_notes, remove_unconfirmed_spend_requests, store_created_cash_notes,
@@ -23,8 +27,8 @@ use crate::{
     cashnotes::UnsignedTransfer,
     transfers::{CashNotesAndSecretKey, OfflineTransfer},
     CashNote, CashNoteRedemption, DerivationIndex, DerivedSecretKey, MainPubkey, MainSecretKey,
-    NanoTokens, SignedSpend, Spend, SpendAddress, SpendReason, Transaction, Transfer, UniquePubkey,
-    WalletError, NETWORK_ROYALTIES_PK,
+    NanoTokens, SignedSpend, Spend, SpendAddress, SpendCondition, SpendReason, Transaction,
+    Transfer, UniquePubkey, WalletError, NETWORK_ROYALTIES_PK,
 };
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
@@ -156,11 +160,33 @@ impl HotWallet {
     /// Creates a serialized wallet for a path and main key.
     /// This will overwrite any existing wallet, unlike load_from_main_key
     pub fn create_from_key(root_dir: &Path, key: MainSecretKey) -> Result<Self> {
+        Self::create_from_key_and_password(root_dir, key, None)
+    }
+
+    /// Creates a serialized wallet for a path and main key, the main key being stored
+    /// encrypted with the password.
+    /// This will overwrite any existing wallet, unlike load_from_main_key
+    pub fn create_from_key_with_password(
+        root_dir: &Path,
+        key: MainSecretKey,
+        password: &str,
+    ) -> Result<Self> {
+        Self::create_from_key_and_password(root_dir, key, Some(password))
+    }
+
+    fn create_from_key_and_password(
+        root_dir: &Path,
+        key: MainSecretKey,
+        password: Option<&str>,
+    ) -> Result<Self> {
         let wallet_dir = root_dir.join(WALLET_DIR_NAME);
         // This creates the received_cash_notes dir if it doesn't exist.
         std::fs::create_dir_all(&wallet_dir)?;
         // Create the new wallet for this key
-        store_new_keypair(&wallet_dir, &key)?;
+        match password {
+            Some(password) => store_new_encrypted_keypair(&wallet_dir, &key, password)?,
+            None => store_new_keypair(&wallet_dir, &key)?,
+        }
         let unconfirmed_spend_requests =
             (get_unconfirmed_spend_requests(&wallet_dir)?).unwrap_or_default();
         let watchonly_wallet = WatchOnlyWallet::load_from(&wallet_dir, key.main_pubkey())?;
@@ -178,6 +204,33 @@ impl HotWallet {
         Self::load_from_path(&wallet_dir, None)
     }
 
+    /// Loads a serialized wallet from a path, unlocking its main key with the password if it is
+    /// encrypted. The main key then stays unlocked for the rest of the session, so that the
+    /// wallet can be loaded again without the password.
+    pub fn load_from_with_password(root_dir: &Path, password: &str) -> Result<Self> {
+        let wallet_dir = root_dir.join(WALLET_DIR_NAME);
+        if is_main_key_encrypted(&wallet_dir) {
+            let _ = unlock_main_key(&wallet_dir, password)?;
+        }
+        Self::load_from_path(&wallet_dir, None)
+    }
+
+    /// Whether the main key of the wallet is stored encrypted with a password.
+    pub fn is_encrypted(root_dir: &Path) -> bool {
+        is_main_key_encrypted(&root_dir.join(WALLET_DIR_NAME))
+    }
+
+    /// Encrypts the plain main key of an existing wallet with the password, removing the
+    /// plain one from disk.
+    pub fn encrypt(root_dir: &Path, password: &str) -> Result<()> {
+        encrypt_main_key_on_disk(&root_dir.join(WALLET_DIR_NAME), password)
+    }
+
+    /// Encrypts the main key of a wallet with a new password, given the current one.
+    pub fn change_password(root_dir: &Path, password: &str, new_password: &str) -> Result<()> {
+        change_main_key_password(&root_dir.join(WALLET_DIR_NAME), password, new_password)
+    }
+
     /// Tries to loads a serialized wallet from a path, bailing out if it doesn't exist.
     pub fn try_load_from(root_dir: &Path) -> Result<Self> {
         let wallet_dir = root_dir.join(WALLET_DIR_NAME);
@@ -202,9 +255,13 @@ impl HotWallet {
     /// Moves all files for the current wallet, including keys and cashnotes
     /// to directory root_dir/wallet_ADDRESS
     pub fn stash(root_dir: &Path) -> Result<PathBuf> {
-        let wallet = HotWallet::load_from(root_dir)?;
         let wallet_dir = root_dir.join(WALLET_DIR_NAME);
-        let addr_hex = &format!("{:?}", wallet.address());
+        // the address is read from the public key, so that a locked wallet can be stashed too
+        let address = match get_main_pubkey(&wallet_dir)? {
+            Some(address) => address,
+            None => HotWallet::load_from(root_dir)?.address(),
+        };
+        let addr_hex = &format!("{address:?}");
         let new_name = format!("{WALLET_DIR_NAME}_{addr_hex}");
         let moved_dir = root_dir.join(new_name);
         let _ = std::fs::rename(wallet_dir, moved_dir.clone());
@@ -342,8 +399,52 @@ impl HotWallet {
 
         let reason = reason.unwrap_or_default();
 
-        let transfer =
-            OfflineTransfer::new(available_cash_notes, to_unique_keys, self.address(), reason)?;
+        let transfer = OfflineTransfer::new_with_restorable_change(
+            available_cash_notes,
+            to_unique_keys,
+            &self.key,
+            reason,
+        )?;
+
+        let created_cash_notes = transfer.cash_notes_for_recipient.clone();
+
+        self.update_local_wallet(transfer, exclusive_access, true)?;
+
+        trace!("Releasing wallet lock"); // by dropping _exclusive_access
+        Ok(created_cash_notes)
+    }
+
+    /// Make a vesting grant to the recipient and return all created cash_notes, one per tranche.
+    /// Each tranche is given with the time it unlocks at, in seconds since the Unix epoch, and
+    /// cannot be spent by the recipient before then.
+    pub fn local_send_vesting_grant(
+        &mut self,
+        to: MainPubkey,
+        tranches: Vec<(NanoTokens, u64)>,
+        reason: Option<SpendReason>,
+    ) -> Result<Vec<CashNote>> {
+        let mut rng = &mut rand::rngs::OsRng;
+        let mut recipients = vec![];
+        let mut conditions = BTreeMap::new();
+        for (amount, unlock_time) in tranches {
+            let derivation_index = DerivationIndex::random(&mut rng);
+            let _ = conditions.insert(
+                to.new_unique_pubkey(&derivation_index),
+                SpendCondition::NotBefore(unlock_time),
+            );
+            recipients.push((amount, to, derivation_index));
+        }
+
+        let (available_cash_notes, exclusive_access) = self.available_cash_notes()?;
+        let reason = reason.unwrap_or_default();
+
+        let transfer = OfflineTransfer::new_with_conditions(
+            available_cash_notes,
+            recipients,
+            conditions,
+            &self.key,
+            reason,
+        )?;
 
         let created_cash_notes = transfer.cash_notes_for_recipient.clone();
 
@@ -409,10 +510,10 @@ impl HotWallet {
             .map(|(amount, address)| (amount, address, DerivationIndex::random(&mut rng)))
             .collect();
 
-        let transfer = OfflineTransfer::new(
+        let transfer = OfflineTransfer::new_with_restorable_change(
             available_cash_notes,
             to_unique_keys,
-            self.address(),
+            &self.key,
             spend_reason,
         )?;
 
@@ -484,10 +585,10 @@ impl HotWallet {
 
         let spend_reason = Default::default();
         let start = Instant::now();
-        let offline_transfer = OfflineTransfer::new(
+        let offline_transfer = OfflineTransfer::new_with_restorable_change(
             available_cash_notes,
             recipients,
-            self.address(),
+            &self.key,
             spend_reason,
         )?;
         trace!(
@@ -671,6 +772,8 @@ impl HotWallet {
 
                 key
             }
+            // never overwrite a main key which is only encrypted
+            Err(error @ Error::EncryptedMainSecretKey(_)) => return Err(error),
             Err(error) => {
                 if let Some(key) = main_key {
                     store_new_keypair(wallet_dir, &key)?;
@@ -708,7 +811,7 @@ mod tests {
             data_payments::PaymentQuote, hot_wallet::WALLET_DIR_NAME, wallet_file::store_wallet,
             watch_only::WatchOnlyWallet, KeyLessWallet,
         },
-        MainSecretKey, NanoTokens, SpendAddress,
+        MainSecretKey, NanoTokens, SpendAddress, TransferError, WalletError,
     };
     use assert_fs::TempDir;
     use eyre::Result;
@@ -935,6 +1038,45 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn vesting_grant_tranches_are_only_spent_once_unlocked() -> Result<()> {
+        let sender_dir = create_temp_dir();
+        let mut sender = HotWallet::create_from_key(sender_dir.path(), MainSecretKey::random())?;
+        let sender_cash_note =
+            create_first_cash_note_from_key(&sender.key).expect("Genesis creation to succeed.");
+        sender.deposit_and_store_to_disk(&vec![sender_cash_note])?;
+
+        let recipient_dir = create_temp_dir();
+        let mut recipient =
+            HotWallet::create_from_key(recipient_dir.path(), MainSecretKey::random())?;
+        let now = crate::cashnotes::unix_time_now();
+        let tranches = vec![
+            (NanoTokens::from(100), now - 60),
+            (NanoTokens::from(200), now + 3600),
+        ];
+        let created_cash_notes =
+            sender.local_send_vesting_grant(recipient.address(), tranches, None)?;
+        assert_eq!(2, created_cash_notes.len());
+        assert!(created_cash_notes
+            .iter()
+            .all(|cash_note| cash_note.spend_condition().is_some()));
+
+        recipient.deposit_and_store_to_disk(&created_cash_notes)?;
+        assert_eq!(NanoTokens::from(300), recipient.balance());
+
+        // only the unlocked tranche can be spent yet
+        let to = vec![(NanoTokens::from(150), MainSecretKey::random().main_pubkey())];
+        assert!(matches!(
+            recipient.local_send(to, None),
+            Err(WalletError::Transfer(TransferError::NotEnoughBalance(..)))
+        ));
+        let to = vec![(NanoTokens::from(50), MainSecretKey::random().main_pubkey())];
+        let _ = recipient.local_send(to, None)?;
+        assert_eq!(NanoTokens::from(250), recipient.balance());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn send_wallet_to_and_from_file() -> Result<()> {
         let dir = create_temp_dir();
@@ -6,16 +6,50 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::error::{Error, Result};
+use super::{
+    encryption::EncryptedSecretKey,
+    error::{Error, Result},
+};
 use crate::{MainPubkey, MainSecretKey};
 use hex::{decode, encode};
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
 /// Filename for storing the node's reward (BLS hex-encoded) main secret key.
 const MAIN_SECRET_KEY_FILENAME: &str = "main_secret_key";
+/// Filename for storing the main secret key encrypted with a password, in place of the plain one.
+const ENCRYPTED_MAIN_SECRET_KEY_FILENAME: &str = "main_secret_key.encrypted";
 /// Filename for storing the node's reward (BLS hex-encoded) public key.
 const MAIN_PUBKEY_FILENAME: &str = "main_pubkey";
 
+/// The encrypted main keys unlocked with their password during this session, by wallet dir.
+static UNLOCKED_KEYS: Mutex<BTreeMap<PathBuf, bls::SecretKey>> = Mutex::new(BTreeMap::new());
+
+fn set_unlocked_key(wallet_dir: &Path, main_key: Option<&MainSecretKey>) {
+    let mut unlocked_keys = UNLOCKED_KEYS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match main_key {
+        Some(main_key) => {
+            let _ = unlocked_keys.insert(wallet_dir.to_path_buf(), main_key.secret_key().clone());
+        }
+        None => {
+            let _ = unlocked_keys.remove(wallet_dir);
+        }
+    }
+}
+
+fn get_unlocked_key(wallet_dir: &Path) -> Option<MainSecretKey> {
+    UNLOCKED_KEYS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(wallet_dir)
+        .map(|secret_key| MainSecretKey::new(secret_key.clone()))
+}
+
 /// Writes the public address and main key (hex-encoded) to different locations at disk.
 pub(crate) fn store_new_keypair(wallet_dir: &Path, main_key: &MainSecretKey) -> Result<()> {
     let secret_key_path = wallet_dir.join(MAIN_SECRET_KEY_FILENAME);
@@ -23,11 +57,47 @@ pub(crate) fn store_new_keypair(wallet_dir: &Path, main_key: &MainSecretKey) ->
     std::fs::write(secret_key_path, encode(main_key.to_bytes()))?;
     std::fs::write(public_key_path, encode(main_key.main_pubkey().to_bytes()))
         .map_err(|e| Error::FailedToHexEncodeKey(e.to_string()))?;
+
+    // an encrypted key left over would take precedence over the new one
+    let encrypted_key_path = wallet_dir.join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME);
+    if encrypted_key_path.is_file() {
+        std::fs::remove_file(encrypted_key_path)?;
+    }
+    set_unlocked_key(wallet_dir, None);
+    Ok(())
+}
+
+/// Writes the public address (hex-encoded) and the main key encrypted with the password to disk,
+/// removing the plain main key if any. The main key is kept unlocked for the rest of the session.
+pub(super) fn store_new_encrypted_keypair(
+    wallet_dir: &Path,
+    main_key: &MainSecretKey,
+    password: &str,
+) -> Result<()> {
+    let encrypted_key_path = wallet_dir.join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME);
+    // write then rename, not to lose the key previously stored if anything goes wrong
+    let tmp_path = encrypted_key_path.with_extension("tmp");
+    EncryptedSecretKey::encrypt(main_key, password)?.to_file(&tmp_path)?;
+    std::fs::rename(tmp_path, encrypted_key_path)?;
+    store_new_pubkey(wallet_dir, &main_key.main_pubkey())?;
+
+    let secret_key_path = wallet_dir.join(MAIN_SECRET_KEY_FILENAME);
+    if secret_key_path.is_file() {
+        std::fs::remove_file(secret_key_path)?;
+    }
+    set_unlocked_key(wallet_dir, Some(main_key));
     Ok(())
 }
 
 /// Returns sn_transfers::MainSecretKey or None if file doesn't exist. It assumes it's hex-encoded.
+/// An encrypted main key is only returned if it was unlocked during this session.
 pub(super) fn get_main_key_from_disk(wallet_dir: &Path) -> Result<MainSecretKey> {
+    let encrypted_key_path = wallet_dir.join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME);
+    if encrypted_key_path.is_file() {
+        return get_unlocked_key(wallet_dir)
+            .ok_or(Error::EncryptedMainSecretKey(encrypted_key_path));
+    }
+
     let path = wallet_dir.join(MAIN_SECRET_KEY_FILENAME);
     if !path.is_file() {
         return Err(Error::MainSecretKeyNotFound(path));
@@ -39,6 +109,46 @@ pub(super) fn get_main_key_from_disk(wallet_dir: &Path) -> Result<MainSecretKey>
     Ok(MainSecretKey::new(secret))
 }
 
+/// Whether the main key stored in the wallet dir is encrypted with a password.
+pub(super) fn is_main_key_encrypted(wallet_dir: &Path) -> bool {
+    wallet_dir
+        .join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME)
+        .is_file()
+}
+
+/// Decrypts the main key stored in the wallet dir, keeping it unlocked for the rest of the session.
+pub(super) fn unlock_main_key(wallet_dir: &Path, password: &str) -> Result<MainSecretKey> {
+    let path = wallet_dir.join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME);
+    if !path.is_file() {
+        return Err(Error::MainSecretKeyNotFound(path));
+    }
+
+    let main_key = EncryptedSecretKey::from_file(&path)?.decrypt(password)?;
+    set_unlocked_key(wallet_dir, Some(&main_key));
+    Ok(main_key)
+}
+
+/// Encrypts the plain main key stored in the wallet dir with the password, replacing it.
+pub(super) fn encrypt_main_key_on_disk(wallet_dir: &Path, password: &str) -> Result<()> {
+    if is_main_key_encrypted(wallet_dir) {
+        return Err(Error::MainSecretKeyAlreadyEncrypted(
+            wallet_dir.join(ENCRYPTED_MAIN_SECRET_KEY_FILENAME),
+        ));
+    }
+    let main_key = get_main_key_from_disk(wallet_dir)?;
+    store_new_encrypted_keypair(wallet_dir, &main_key, password)
+}
+
+/// Encrypts the main key stored in the wallet dir with a new password.
+pub(super) fn change_main_key_password(
+    wallet_dir: &Path,
+    password: &str,
+    new_password: &str,
+) -> Result<()> {
+    let main_key = unlock_main_key(wallet_dir, password)?;
+    store_new_encrypted_keypair(wallet_dir, &main_key, new_password)
+}
+
 /// Writes the public address (hex-encoded) to disk.
 pub(crate) fn store_new_pubkey(wallet_dir: &Path, main_pubkey: &MainPubkey) -> Result<()> {
     let public_key_path = wallet_dir.join(MAIN_PUBKEY_FILENAME);
@@ -73,7 +183,11 @@ pub fn bls_secret_from_hex<T: AsRef<[u8]>>(hex: T) -> Result<bls::SecretKey> {
 
 #[cfg(test)]
 mod test {
-    use super::{get_main_key_from_disk, store_new_keypair, MainSecretKey};
+    use super::{
+        change_main_key_password, encrypt_main_key_on_disk, get_main_key_from_disk,
+        get_main_pubkey, store_new_keypair, unlock_main_key, MainSecretKey,
+    };
+    use crate::wallet::Error;
     use assert_fs::TempDir;
     use eyre::Result;
 
@@ -88,6 +202,43 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn encrypted_key_is_only_loaded_once_unlocked() -> Result<()> {
+        let main_key = MainSecretKey::random();
+        let dir = create_temp_dir();
+        let root_dir = dir.path().to_path_buf();
+        store_new_keypair(&root_dir, &main_key)?;
+
+        // migrating the plain key leaves it unlocked for the session
+        encrypt_main_key_on_disk(&root_dir, "password")?;
+        assert!(!root_dir.join(super::MAIN_SECRET_KEY_FILENAME).exists());
+        assert_eq!(
+            get_main_key_from_disk(&root_dir)?.main_pubkey(),
+            main_key.main_pubkey()
+        );
+        assert_eq!(get_main_pubkey(&root_dir)?, Some(main_key.main_pubkey()));
+
+        // a new session has to unlock it with the current password
+        super::set_unlocked_key(&root_dir, None);
+        assert!(matches!(
+            get_main_key_from_disk(&root_dir),
+            Err(Error::EncryptedMainSecretKey(_))
+        ));
+        change_main_key_password(&root_dir, "password", "new password")?;
+        super::set_unlocked_key(&root_dir, None);
+        assert!(matches!(
+            unlock_main_key(&root_dir, "password"),
+            Err(Error::FailedToDecryptKey)
+        ));
+        let unlocked = unlock_main_key(&root_dir, "new password")?;
+        assert_eq!(unlocked.main_pubkey(), main_key.main_pubkey());
+        assert_eq!(
+            get_main_key_from_disk(&root_dir)?.main_pubkey(),
+            main_key.main_pubkey()
+        );
+        Ok(())
+    }
+
     fn create_temp_dir() -> TempDir {
         TempDir::new().expect("Should be able to create a temp dir.")
     }